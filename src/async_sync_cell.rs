@@ -0,0 +1,352 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A cell for sharing non-Sync types between tasks, whose lock may be held across `.await`.
+
+This module provides [`AsyncSyncCell<T>`], an async counterpart to [`crate::SyncCell`].
+`SyncCell` deliberately only accepts synchronous closures, because holding a blocking
+mutex across an `.await` can stall or deadlock an executor. `AsyncSyncCell` instead uses
+an async-aware mutex: tasks waiting for the lock are suspended rather than blocking
+their thread, so [`AsyncSyncCell::with_async`] can keep exclusive access to the wrapped
+value for the whole duration of an async closure.
+
+This module is only available with the `async` feature.
+
+# Choosing Between SyncCell and AsyncSyncCell
+
+| Type | Lock | Closure | Held across `.await` |
+|------|------|---------|----------------------|
+| [`crate::SyncCell`] | Blocking mutex | Synchronous | No |
+| [`AsyncSyncCell`] | Async mutex | Asynchronous | Yes |
+
+Prefer [`crate::SyncCell`] when each access is short and synchronous; it is cheaper
+and can be used from any context. Reach for `AsyncSyncCell` when an operation on the
+wrapped value must itself await, for example sending a request over a connection and
+waiting for the reply before anyone else may use the connection.
+
+# Examples
+
+```rust
+use send_cells::AsyncSyncCell;
+use std::cell::RefCell;
+
+# async fn fetch(id: u32) -> String { format!("item {id}") }
+async fn cached_fetch(cache: &AsyncSyncCell<RefCell<Vec<String>>>) {
+    cache
+        .with_async(async |cache| {
+            // The lock is still held after this await, so no other task can
+            // observe the cache half-updated
+            let item = fetch(1).await;
+            cache.borrow_mut().push(item);
+        })
+        .await;
+}
+```
+*/
+
+use crate::unsafe_sync_cell::UnsafeSyncCell;
+use async_lock::Mutex;
+use std::fmt::{Debug, Formatter};
+
+/// A cell that allows sharing non-Sync types between tasks through async closures.
+///
+/// `AsyncSyncCell<T>` wraps a value of type `T` (which may not implement `Sync`) and
+/// provides a `Sync` implementation using an async mutex. Access to the wrapped value is
+/// provided through closure-based methods; the async methods suspend the calling task
+/// while another task holds the lock.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::AsyncSyncCell;
+/// use std::rc::Rc;
+///
+/// async fn example() {
+///     let cell = AsyncSyncCell::new(Rc::new(42));
+///
+///     let doubled = cell.with_async(async |value| **value * 2).await;
+///     assert_eq!(doubled, 84);
+/// }
+/// ```
+///
+/// # Thread Safety
+///
+/// The cell implements both `Send` and `Sync` when the wrapped type implements `Send`.
+/// Access is always protected by the internal mutex, ensuring thread safety.
+pub struct AsyncSyncCell<T> {
+    inner: UnsafeSyncCell<T>,
+    mutex: Mutex<()>,
+}
+
+impl<T> AsyncSyncCell<T> {
+    /// Creates a new `AsyncSyncCell` wrapping the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// let cell = AsyncSyncCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.into_inner(), vec![1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn new(value: T) -> AsyncSyncCell<T> {
+        AsyncSyncCell {
+            inner: UnsafeSyncCell::new(value),
+            mutex: Mutex::new(()),
+        }
+    }
+
+    /// Accesses the underlying value mutably through an async closure.
+    ///
+    /// The internal mutex is acquired (suspending the current task if another task holds
+    /// it) before calling the closure, and released once the future returned by the
+    /// closure completes. The closure may therefore `.await` while keeping exclusive
+    /// access to the value.
+    ///
+    /// If the returned future is dropped before it completes, the lock is released.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// # async fn tick() {}
+    /// async fn example() {
+    ///     let cell = AsyncSyncCell::new(0);
+    ///     cell.with_async(async |value| {
+    ///         *value += 1;
+    ///         tick().await;
+    ///         *value += 1;
+    ///     })
+    ///     .await;
+    ///     assert_eq!(cell.with(|value| *value).await, 2);
+    /// }
+    /// ```
+    pub async fn with_async<R>(&self, f: impl AsyncFnOnce(&mut T) -> R) -> R {
+        let _guard = self.mutex.lock().await;
+        //safe since we hold the lock until the closure's future completes
+        let value = unsafe { self.inner.get_mut_unchecked() };
+        f(value).await
+    }
+
+    /// Accesses the underlying value mutably through a synchronous closure.
+    ///
+    /// This waits for the lock like [`Self::with_async`], but the closure itself runs
+    /// synchronously, like [`crate::SyncCell::with_mut`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// async fn example() {
+    ///     let cell = AsyncSyncCell::new(vec![1, 2, 3]);
+    ///     cell.with(|vec| vec.push(4)).await;
+    ///     assert_eq!(cell.with(|vec| vec.len()).await, 4);
+    /// }
+    /// ```
+    pub async fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let _guard = self.mutex.lock().await;
+        //safe since we hold the lock
+        let value = unsafe { self.inner.get_mut_unchecked() };
+        f(value)
+    }
+
+    /// Accesses the underlying value through a synchronous closure, if the lock is free.
+    ///
+    /// Returns `None` without calling the closure if another task currently holds the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// let cell = AsyncSyncCell::new(42);
+    /// assert_eq!(cell.try_with(|value| *value), Some(42));
+    /// ```
+    pub fn try_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let _guard = self.mutex.try_lock()?;
+        //safe since we hold the lock
+        let value = unsafe { self.inner.get_mut_unchecked() };
+        Some(f(value))
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// let mut cell = AsyncSyncCell::new(1);
+    /// *cell.get_mut() = 2;
+    /// assert_eq!(cell.into_inner(), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::AsyncSyncCell;
+    ///
+    /// let cell = AsyncSyncCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+// SAFETY: AsyncSyncCell<T> can be Send when T: Send because moving the cell moves the value,
+// exactly like moving a T.
+unsafe impl<T: Send> Send for AsyncSyncCell<T> {}
+
+// SAFETY: All access to the value is serialized by the internal mutex, so only one task
+// holds a reference at a time. That task may run on any thread, which requires T: Send.
+unsafe impl<T: Send> Sync for AsyncSyncCell<T> {}
+
+impl<T: Debug> Debug for AsyncSyncCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Waiting for the lock here could deadlock a task that holds it across an await
+        match self.try_with(|value| value.fmt(f)) {
+            Some(result) => result,
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
+impl<T: Default> Default for AsyncSyncCell<T> {
+    fn default() -> AsyncSyncCell<T> {
+        AsyncSyncCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for AsyncSyncCell<T> {
+    fn from(value: T) -> Self {
+        AsyncSyncCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    // Helper function to create a no-op waker for testing
+    fn noop_waker() -> Waker {
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        unsafe { Waker::from_raw(raw_waker) }
+    }
+
+    // A future that returns Pending once before completing
+    async fn yield_once() {
+        let mut yielded = false;
+        std::future::poll_fn(|_| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let cell = AsyncSyncCell::new(42);
+
+        {
+            let future = pin!(cell.with_async(async |value| {
+                *value += 1;
+                *value
+            }));
+            assert_eq!(future.poll(&mut cx), Poll::Ready(43));
+
+            let future = pin!(cell.with(|value| *value * 2));
+            assert_eq!(future.poll(&mut cx), Poll::Ready(86));
+        }
+        assert_eq!(cell.into_inner(), 43);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_lock_held_across_await() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let cell = AsyncSyncCell::new(vec![1]);
+
+        let mut holder = pin!(cell.with_async(async |vec| {
+            vec.push(2);
+            yield_once().await;
+            vec.push(3);
+        }));
+        assert_eq!(holder.as_mut().poll(&mut cx), Poll::Pending);
+
+        // While the first closure is suspended, nobody else gets the lock
+        assert_eq!(cell.try_with(|vec| vec.len()), None);
+        assert_eq!(format!("{:?}", cell), "<locked>");
+        let mut waiter = pin!(cell.with(|vec| vec.clone()));
+        assert_eq!(waiter.as_mut().poll(&mut cx), Poll::Pending);
+
+        assert_eq!(holder.as_mut().poll(&mut cx), Poll::Ready(()));
+        assert_eq!(waiter.as_mut().poll(&mut cx), Poll::Ready(vec![1, 2, 3]));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_dropped_future_releases_lock() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let cell = AsyncSyncCell::new(0);
+
+        {
+            let holder = pin!(cell.with_async(async |_| yield_once().await));
+            assert_eq!(holder.poll(&mut cx), Poll::Pending);
+        }
+        assert_eq!(cell.try_with(|value| *value), Some(0));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        // RefCell is Send but not Sync
+        let cell = AsyncSyncCell::new(std::cell::RefCell::new(42));
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+}
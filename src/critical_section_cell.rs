@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A cell for sharing non-Sync values with interrupt handlers.
+
+This module provides [`CriticalSectionCell<T>`], which offers the closure-based API of
+[`crate::SyncCell`] on top of the [`critical_section`] crate. Every access runs inside a
+critical section, which on a single-core microcontroller usually means with interrupts
+disabled, so main code and interrupt handlers can share a `!Sync` peripheral or driver
+without a lock that an interrupt could deadlock on.
+
+The platform must provide a critical section implementation, as described in the
+`critical-section` documentation; on hosted targets its `std` feature provides one.
+
+# Comparison with SpinSyncCell
+
+| Type | Exclusion | Safe from interrupt handlers | Re-entrant access |
+|------|-----------|------------------------------|-------------------|
+| [`crate::SpinSyncCell`] | Spinlock | No: an interrupt spinning on a lock held by the code it interrupted never returns | Deadlocks |
+| [`CriticalSectionCell`] | Critical section | Yes | Panics |
+
+# Examples
+
+```rust
+use send_cells::CriticalSectionCell;
+use std::cell::RefCell;
+
+// A driver that is Send but not Sync, shared with an interrupt handler
+static DRIVER: CriticalSectionCell<Option<RefCell<Vec<u8>>>> = CriticalSectionCell::new(None);
+
+fn init() {
+    DRIVER.with_mut(|driver| *driver = Some(RefCell::new(Vec::new())));
+}
+
+fn on_interrupt() {
+    DRIVER.with(|driver| {
+        if let Some(driver) = driver {
+            driver.borrow_mut().push(1);
+        }
+    });
+}
+
+init();
+on_interrupt();
+assert_eq!(DRIVER.with(|driver| driver.as_ref().map(|d| d.borrow().len())), Some(1));
+```
+*/
+
+use core::cell::RefCell;
+use core::fmt::{Debug, Formatter};
+
+/// A cell that shares a value between threads and interrupt handlers inside critical
+/// sections.
+///
+/// `CriticalSectionCell<T>` is `Sync` whenever `T: Send`, like [`crate::SyncCell`], and
+/// gives access to the value through closures that run inside a critical section. Critical
+/// sections nest, so accessing the same cell again from within one of its closures would
+/// alias the value; that panics instead.
+///
+/// Closures should be short, since nothing else (including interrupts, on most platforms)
+/// runs while they do.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::CriticalSectionCell;
+/// use std::cell::Cell;
+///
+/// // Cell is Send but not Sync
+/// static TICKS: CriticalSectionCell<Cell<u32>> = CriticalSectionCell::new(Cell::new(0));
+///
+/// TICKS.with(|ticks| ticks.set(ticks.get() + 1));
+/// assert_eq!(TICKS.with(|ticks| ticks.get()), 1);
+/// ```
+pub struct CriticalSectionCell<T> {
+    mutex: critical_section::Mutex<RefCell<T>>,
+}
+
+impl<T> CriticalSectionCell<T> {
+    /// Creates a new `CriticalSectionCell` wrapping the given value.
+    ///
+    /// The constructor is `const`, so the cell can be used in a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::CriticalSectionCell;
+    ///
+    /// static COUNTER: CriticalSectionCell<u64> = CriticalSectionCell::new(0);
+    ///
+    /// COUNTER.with_mut(|count| *count += 1);
+    /// assert_eq!(COUNTER.with(|count| *count), 1);
+    /// ```
+    #[inline]
+    pub const fn new(value: T) -> CriticalSectionCell<T> {
+        CriticalSectionCell {
+            mutex: critical_section::Mutex::new(RefCell::new(value)),
+        }
+    }
+
+    /// Accesses the wrapped value through a closure inside a critical section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within a [`Self::with_mut`] closure on the same cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::CriticalSectionCell;
+    ///
+    /// let cell = CriticalSectionCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.with(|vec| vec.len()), 3);
+    /// ```
+    #[inline]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        critical_section::with(|cs| f(&self.mutex.borrow_ref(cs)))
+    }
+
+    /// Mutably accesses the wrapped value through a closure inside a critical section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within a [`Self::with`] or [`Self::with_mut`] closure on the
+    /// same cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::CriticalSectionCell;
+    ///
+    /// let cell = CriticalSectionCell::new(vec![1, 2, 3]);
+    /// cell.with_mut(|vec| vec.push(4));
+    /// assert_eq!(cell.with(|vec| vec.len()), 4);
+    /// ```
+    #[inline]
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.mutex.borrow_ref_mut(cs)))
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// No critical section is needed, since the exclusive borrow of the cell guarantees
+    /// that nothing else can access it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::CriticalSectionCell;
+    ///
+    /// let mut cell = CriticalSectionCell::new(1);
+    /// *cell.get_mut() += 1;
+    /// assert_eq!(cell.with(|value| *value), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.mutex.get_mut().get_mut()
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::CriticalSectionCell;
+    ///
+    /// let cell = CriticalSectionCell::new(String::from("hello"));
+    /// assert_eq!(cell.into_inner(), "hello");
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.mutex.into_inner().into_inner()
+    }
+}
+
+impl<T: Debug> Debug for CriticalSectionCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // Borrowing here could panic in a Debug call made from within a with_mut closure
+        critical_section::with(|cs| match self.mutex.borrow(cs).try_borrow() {
+            Ok(value) => (*value).fmt(f),
+            Err(_) => f.write_str("<borrowed>"),
+        })
+    }
+}
+
+impl<T: Default> Default for CriticalSectionCell<T> {
+    fn default() -> CriticalSectionCell<T> {
+        CriticalSectionCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for CriticalSectionCell<T> {
+    fn from(value: T) -> Self {
+        CriticalSectionCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let mut cell = CriticalSectionCell::new(42);
+        assert_eq!(cell.with(|value| *value), 42);
+        cell.with_mut(|value| *value += 1);
+        cell.with_mut(|_| assert_eq!(format!("{cell:?}"), "<borrowed>"));
+        assert_eq!(format!("{cell:?}"), "43");
+
+        *cell.get_mut() += 1;
+        assert_eq!(cell.into_inner(), 44);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        // Cell is Send but not Sync
+        let cell = CriticalSectionCell::new(std::cell::Cell::new(0));
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_reentrant_access_panics() {
+        let cell = CriticalSectionCell::new(1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cell.with_mut(|_| cell.with(|value| *value))
+        }));
+        assert!(result.is_err());
+
+        // The panic ended the critical section and released the borrow
+        assert_eq!(cell.with(|value| *value), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concurrent_access() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cell = Arc::new(CriticalSectionCell::new(0u32));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || (0..1000).for_each(|_| cell.with_mut(|v| *v += 1)))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cell.with(|v| *v), 4000);
+    }
+}
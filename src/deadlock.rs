@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Lock-order tracking behind the `deadlock-detection` feature.
+
+Every [`crate::SyncCell`] carries a [`LockId`]. Before a cell is locked, the current thread's
+held locks are consulted: locking a cell the thread already holds is reported as a
+re-entrant lock, and every held cell is recorded as ordered before the new one in a global
+graph. If the graph already orders the new cell before a held one, the two orders
+together form a cycle that could deadlock, and the cycle is reported.
+
+Both cases panic before the mutex is touched, so the panic replaces a hang. Without the
+feature, or in release builds, [`LockId`] is zero-sized and all of this compiles away.
+*/
+
+#[cfg(all(feature = "deadlock-detection", debug_assertions))]
+mod imp {
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, BTreeSet, VecDeque};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, PoisonError};
+
+    /// Identifies a cell in the lock-order graph.
+    ///
+    /// Ids are never reused, so a cell that moves keeps its id and a dropped cell's
+    /// edges cannot be mistaken for a new cell's. Dropping the id removes the cell from
+    /// the graph, since a dropped cell can no longer take part in a deadlock.
+    #[derive(Debug)]
+    pub(crate) struct LockId(u64);
+
+    struct Node {
+        name: &'static str,
+        // Cells that were locked while this one was held
+        after: BTreeSet<u64>,
+        // Cells that were held while this one was locked, so dropping it can find the
+        // edges pointing to it
+        before: BTreeSet<u64>,
+    }
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    static GRAPH: Mutex<BTreeMap<u64, Node>> = Mutex::new(BTreeMap::new());
+
+    thread_local! {
+        static HELD: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl LockId {
+        pub(crate) fn new() -> LockId {
+            LockId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+        }
+
+        /// Checks that locking this cell cannot deadlock and records the order, panicking
+        /// otherwise. `outer` are locks whose order with this one is already fixed by the
+        /// caller, and are left out of the graph.
+        pub(crate) fn before_lock(&self, name: &'static str, outer: &[&LockId]) {
+            // The message is built while `HELD` is borrowed and the graph is locked, and
+            // the panic waits until both are released
+            if let Some(message) = HELD.with(|held| self.check(name, outer, &held.borrow())) {
+                panic!("{message}");
+            }
+        }
+
+        fn check(&self, name: &'static str, outer: &[&LockId], held: &[u64]) -> Option<String> {
+            if held.contains(&self.0) {
+                return Some(format!(
+                    "Re-entrant lock of {name} (#{}): the current thread already holds it, \
+                     so locking it again would deadlock",
+                    self.0
+                ));
+            }
+            let mut graph = GRAPH.lock().unwrap_or_else(PoisonError::into_inner);
+            graph.entry(self.0).or_insert_with(|| Node {
+                name,
+                after: BTreeSet::new(),
+                before: BTreeSet::new(),
+            });
+            for &outer_id in held.iter().filter(|&&id| !outer.iter().any(|o| o.0 == id)) {
+                if let Some(path) = find_path(&graph, self.0, outer_id) {
+                    let cycle = path
+                        .iter()
+                        .chain(std::iter::once(&self.0))
+                        .map(|id| format!("{} (#{id})", graph[id].name))
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+                    return Some(format!(
+                        "Inconsistent lock order: locking {name} (#{}) while holding #{outer_id}, \
+                         but they were previously locked in the opposite order. Cycle: {cycle}",
+                        self.0
+                    ));
+                }
+                if let Some(node) = graph.get_mut(&outer_id) {
+                    node.after.insert(self.0);
+                    if let Some(node) = graph.get_mut(&self.0) {
+                        node.before.insert(outer_id);
+                    }
+                }
+            }
+            None
+        }
+
+        pub(crate) fn after_lock(&self) {
+            HELD.with(|held| held.borrow_mut().push(self.0));
+        }
+
+        pub(crate) fn after_unlock(&self) {
+            HELD.with(|held| {
+                let mut held = held.borrow_mut();
+                if let Some(index) = held.iter().rposition(|&id| id == self.0) {
+                    held.remove(index);
+                }
+            });
+        }
+    }
+
+    impl Drop for LockId {
+        fn drop(&mut self) {
+            let mut graph = GRAPH.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(node) = graph.remove(&self.0) else {
+                // Never locked
+                return;
+            };
+            for id in node.before {
+                if let Some(before) = graph.get_mut(&id) {
+                    before.after.remove(&self.0);
+                }
+            }
+            for id in node.after {
+                if let Some(after) = graph.get_mut(&id) {
+                    after.before.remove(&self.0);
+                }
+            }
+        }
+    }
+
+    /// Finds the chain of recorded orderings from `from` to `to`, if there is one.
+    fn find_path(graph: &BTreeMap<u64, Node>, from: u64, to: u64) -> Option<Vec<u64>> {
+        let mut previous = BTreeMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                let mut path = vec![to];
+                while let Some(&before) = previous.get(path.last().unwrap()) {
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in graph.get(&id).into_iter().flat_map(|node| &node.after) {
+                if next != from && !previous.contains_key(&next) {
+                    previous.insert(next, id);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_drop_removes_node_and_edges() {
+            let first = LockId::new();
+            let second = LockId::new();
+            first.before_lock("first", &[]);
+            first.after_lock();
+            second.before_lock("second", &[]);
+            second.after_lock();
+            second.after_unlock();
+            first.after_unlock();
+            let (first_id, second_id) = (first.0, second.0);
+            {
+                let graph = GRAPH.lock().unwrap();
+                assert!(graph[&first_id].after.contains(&second_id));
+                assert!(graph[&second_id].before.contains(&first_id));
+            }
+
+            drop(second);
+            {
+                let graph = GRAPH.lock().unwrap();
+                assert!(!graph.contains_key(&second_id));
+                assert!(graph[&first_id].after.is_empty());
+            }
+            drop(first);
+            assert!(!GRAPH.lock().unwrap().contains_key(&first_id));
+        }
+    }
+}
+
+#[cfg(not(all(feature = "deadlock-detection", debug_assertions)))]
+mod imp {
+    #[derive(Debug)]
+    pub(crate) struct LockId;
+
+    impl LockId {
+        #[inline]
+        pub(crate) fn new() -> LockId {
+            LockId
+        }
+
+        #[inline]
+        pub(crate) fn before_lock(&self, _name: &'static str, _outer: &[&LockId]) {}
+
+        #[inline]
+        pub(crate) fn after_lock(&self) {}
+
+        #[inline]
+        pub(crate) fn after_unlock(&self) {}
+    }
+}
+
+pub(crate) use imp::LockId;
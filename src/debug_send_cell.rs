@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A cell that checks thread access in debug builds and is zero-cost in release builds.
+
+This module provides [`DebugSendCell<T>`], which sits between [`crate::SendCell`] and
+[`crate::UnsafeSendCell`]:
+
+- When `debug_assertions` are enabled (debug builds, and typically CI test runs), it
+  behaves like [`crate::SendCell`]: it remembers the thread it was created on and panics
+  if accessed or dropped from any other thread.
+- When `debug_assertions` are disabled (release builds), the thread id is not stored and
+  no checks are performed, exactly like [`crate::UnsafeSendCell`].
+
+This lets teams validate their threading assumptions in tests without paying for the
+checks in production.
+
+# Safety Model
+
+Because release builds perform no checks, creating a `DebugSendCell` is `unsafe`: the
+caller promises the same thing [`crate::UnsafeSendCell::new_unchecked`] requires, namely
+that the value is only accessed and dropped on the thread that created it. Debug builds
+verify that promise at runtime; release builds trust it.
+
+| Type | Debug builds | Release builds | Constructor |
+|------|--------------|----------------|-------------|
+| [`crate::SendCell`] | Checked | Checked | Safe |
+| [`DebugSendCell`] | Checked | Unchecked | `unsafe` |
+| [`crate::UnsafeSendCell`] | Unchecked | Unchecked | `unsafe` |
+
+# Examples
+
+```rust
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
+use send_cells::DebugSendCell;
+use std::rc::Rc;
+
+// SAFETY: The cell is only accessed and dropped on this thread
+let cell = unsafe { DebugSendCell::new(Rc::new(42)) };
+
+// Access is safe once the cell exists; debug builds check the thread
+assert_eq!(**cell.get(), 42);
+```
+*/
+
+#[cfg(debug_assertions)]
+use crate::sys::ThreadId;
+use crate::unsafe_send_cell::UnsafeSendCell;
+use core::fmt::{Debug, Formatter};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+/// A cell that is runtime-checked like [`crate::SendCell`] in debug builds, and zero-cost
+/// like [`crate::UnsafeSendCell`] in release builds.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
+/// use send_cells::DebugSendCell;
+/// use std::cell::RefCell;
+///
+/// // SAFETY: The cell is only accessed and dropped on this thread
+/// let mut cell = unsafe { DebugSendCell::new(RefCell::new(vec![1, 2])) };
+///
+/// cell.get_mut().borrow_mut().push(3);
+/// assert_eq!(cell.into_inner().into_inner(), vec![1, 2, 3]);
+/// ```
+///
+/// # Panics
+///
+/// In debug builds, all methods panic if called from a different thread than the one
+/// where the cell was created. In release builds, no method panics for this reason.
+pub struct DebugSendCell<T> {
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    #[cfg(debug_assertions)]
+    thread_id: ThreadId,
+}
+
+impl<T> DebugSendCell<T> {
+    /// Creates a new `DebugSendCell` wrapping the given value.
+    ///
+    /// In debug builds, the cell remembers the current thread.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the value is only accessed and dropped on the current
+    /// thread. Debug builds panic if this is violated, but release builds do not check,
+    /// so a violation there may lead to undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::DebugSendCell;
+    /// use std::rc::Rc;
+    ///
+    /// // SAFETY: The cell stays on this thread
+    /// let cell = unsafe { DebugSendCell::new(Rc::new("hello")) };
+    /// assert_eq!(**cell.get(), "hello");
+    /// ```
+    #[inline]
+    pub unsafe fn new(t: T) -> DebugSendCell<T> {
+        DebugSendCell {
+            // SAFETY: The caller upholds the same contract as UnsafeSendCell::new_unchecked
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(t) }),
+            #[cfg(debug_assertions)]
+            thread_id: crate::sys::current_id(),
+        }
+    }
+
+    /// Accesses the underlying value.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if called from a different thread than the one where this
+    /// cell was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::DebugSendCell;
+    ///
+    /// // SAFETY: The cell stays on this thread
+    /// let cell = unsafe { DebugSendCell::new(42) };
+    /// assert_eq!(*cell.get(), 42);
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &T {
+        self.check("Access");
+        //safe since the constructor's contract is checked in debug builds
+        unsafe { self.inner.get() }
+    }
+
+    /// Accesses the underlying value mutably.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if called from a different thread than the one where this
+    /// cell was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::DebugSendCell;
+    ///
+    /// // SAFETY: The cell stays on this thread
+    /// let mut cell = unsafe { DebugSendCell::new(42) };
+    /// *cell.get_mut() = 100;
+    /// assert_eq!(*cell.get(), 100);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.check("Access");
+        //safe since the constructor's contract is checked in debug builds
+        unsafe { self.inner.get_mut() }
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if called from a different thread than the one where this
+    /// cell was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::DebugSendCell;
+    ///
+    /// // SAFETY: The cell stays on this thread
+    /// let cell = unsafe { DebugSendCell::new(42) };
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.check("Access");
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so the value is taken exactly once.
+        let inner = unsafe { ManuallyDrop::take(&mut this.inner) };
+        //safe since the constructor's contract is checked in debug builds
+        unsafe { inner.into_inner() }
+    }
+
+    /// Panics in debug builds if the current thread is not the cell's thread.
+    #[inline]
+    fn check(&self, operation: &str) {
+        #[cfg(debug_assertions)]
+        if crate::instrument::checked(self.thread_id != crate::sys::current_id()) {
+            crate::sys::violation(format_args!(
+                "{} DebugSendCell<{}> from incorrect thread (origin thread {}, current thread {})",
+                operation,
+                core::any::type_name::<T>(),
+                crate::sys::Described(self.thread_id),
+                crate::sys::Described(crate::sys::current_id())
+            ));
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = operation;
+    }
+}
+
+impl<T> Drop for DebugSendCell<T> {
+    fn drop(&mut self) {
+        if core::mem::needs_drop::<T>() {
+            self.check("Drop");
+        }
+        // SAFETY: The value is dropped exactly once, here; into_inner skips this Drop.
+        unsafe { ManuallyDrop::drop(&mut self.inner) }
+    }
+}
+
+// Trait implementations that delegate to the wrapped value
+// All of these perform the debug-build thread check through get() and get_mut()
+impl<T: Debug> Debug for DebugSendCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T> AsRef<T> for DebugSendCell<T> {
+    fn as_ref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> AsMut<T> for DebugSendCell<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T> Deref for DebugSendCell<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for DebugSendCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let mut cell = unsafe { DebugSendCell::new(Rc::new(42)) };
+        assert_eq!(**cell.get(), 42);
+
+        *cell.get_mut() = Rc::new(100);
+        assert_eq!(**cell, 100);
+        assert_eq!(format!("{:?}", cell), "100");
+        assert_eq!(*cell.into_inner(), 100);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let cell = unsafe { DebugSendCell::new(Rc::new(42)) };
+        assert_send(&cell);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_release_is_zero_cost() {
+        // Without debug assertions, the cell is exactly as large as the value
+        #[cfg(not(debug_assertions))]
+        assert_eq!(
+            std::mem::size_of::<DebugSendCell<u64>>(),
+            std::mem::size_of::<u64>()
+        );
+        #[cfg(debug_assertions)]
+        assert!(std::mem::size_of::<DebugSendCell<u64>>() > std::mem::size_of::<u64>());
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_cross_thread_panic_in_debug() {
+        use crate::sys::thread;
+
+        // Not Send, but without drop glue so the failed access doesn't also fail the drop
+        struct NonSend(i32, std::marker::PhantomData<*const ()>);
+
+        let cell = unsafe { DebugSendCell::new(NonSend(42, std::marker::PhantomData)) };
+        let result = thread::spawn(move || cell.get().0).join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when accessing DebugSendCell from incorrect thread"
+        );
+    }
+}
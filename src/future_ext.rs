@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+An extension trait for making any future `Send` in one chained call.
+
+[`FutureExt`] is implemented for every [`Future`]. It is a shortcut for wrapping the
+future in a cell and converting it back:
+
+| Method | Equivalent to |
+|--------|---------------|
+| [`FutureExt::send_checked`] | `SendCell::new(future).into_future()` |
+| [`FutureExt::send_unchecked`] | `UnsafeSendCell::new_unchecked(future).into_future()` |
+
+The trait can be imported anonymously (`use send_cells::FutureExt as _;`) to avoid a name
+clash with `futures::FutureExt`.
+
+# Examples
+
+```rust
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
+use send_cells::FutureExt as _;
+use std::rc::Rc;
+
+let local = Rc::new(42);
+let future = async move { *local }.send_checked();
+
+fn requires_send<T: Send>(_: T) {}
+requires_send(future);
+```
+*/
+
+use crate::{SendCell, SendFuture, UnsafeSendCell, UnsafeSendFuture};
+use core::future::Future;
+
+/// Extension methods that make any future `Send`.
+///
+/// See the [module documentation](self) for an overview.
+pub trait FutureExt: Future + Sized {
+    /// Wraps the future in a [`SendFuture`] bound to the current thread.
+    ///
+    /// # Panics
+    ///
+    /// The returned future will panic if polled from a different thread than the current
+    /// one. The panic message includes the location of this call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::FutureExt as _;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(42);
+    /// let send_future = async move { *local }.send_checked();
+    ///
+    /// fn requires_send<T: Send>(_: T) {}
+    /// requires_send(send_future);
+    /// ```
+    #[track_caller]
+    fn send_checked(self) -> SendFuture<Self> {
+        SendCell::new(self).into_future()
+    }
+
+    /// Wraps the future in an [`UnsafeSendFuture`], without runtime checks.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`UnsafeSendCell::into_future`]: the caller must ensure that the
+    /// future is never polled concurrently from multiple threads, and that it is safe to
+    /// move, poll and drop on every thread it reaches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::FutureExt as _;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(42);
+    /// // SAFETY: The future is only ever polled on this thread
+    /// let send_future = unsafe { async move { *local }.send_unchecked() };
+    ///
+    /// fn requires_send<T: Send>(_: T) {}
+    /// requires_send(send_future);
+    /// ```
+    #[inline]
+    unsafe fn send_unchecked(self) -> UnsafeSendFuture<Self> {
+        // SAFETY: The caller upholds the requirements of both calls
+        unsafe { UnsafeSendCell::new_unchecked(self).into_future() }
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll, Waker};
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_checked_and_unchecked() {
+        fn assert_send<T: Send>(_: &T) {}
+        let mut context = Context::from_waker(Waker::noop());
+
+        let local = Rc::new(1);
+        let future = async move { *local }.send_checked();
+        assert_send(&future);
+        assert_eq!(pin!(future).poll(&mut context), Poll::Ready(1));
+
+        let local = Rc::new(2);
+        let future = unsafe { async move { *local }.send_unchecked() };
+        assert_send(&future);
+        assert_eq!(pin!(future).poll(&mut context), Poll::Ready(2));
+    }
+}
@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Lock and poll instrumentation behind the `metrics` and `tracing` features.
+
+Every [`crate::SyncCell`] locks its mutex through a [`Recorder`]. With the `metrics`
+feature, the recorder counts acquisitions and measures how long each acquisition waited
+for the mutex and how long the mutex was then held; [`crate::SyncCell::stats`] returns a
+[`LockStats`] snapshot. With the `tracing` feature, it emits events for acquisitions,
+contention and holds longer than [`LONG_HOLD`]. Without either feature, the recorder is
+zero-sized and locking goes straight to the mutex.
+
+With the `tracing` feature, every poll of a [`crate::SendFuture`] also runs inside a
+[`PollSpan`] recording the future's origin thread, the polling thread and how long the poll
+took, and [`crate::SendCell`] accesses and drops emit events through [`cell_access`].
+Values leaked by a drop on the wrong thread are reported through [`leaked`].
+
+Every runtime thread check, in [`crate::SendCell`] and the other checked wrappers, goes
+through [`checked`]. With the `metrics` feature, it counts checks and the violations they
+caught in process-wide counters, which [`check_stats`] returns as a [`CheckStats`]
+snapshot; without it, it compiles down to the comparison.
+*/
+
+use lock_api::{MutexGuard, RawMutex};
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(any(feature = "metrics", feature = "tracing"))]
+use std::time::{Duration, Instant};
+
+/// Holds longer than this are reported at `WARN` level by the `tracing` feature.
+#[cfg(feature = "tracing")]
+pub(crate) const LONG_HOLD: Duration = Duration::from_millis(10);
+
+/// Contention statistics of a [`crate::SyncCell`], returned by [`crate::SyncCell::stats`].
+///
+/// Times are measured with [`std::time::Instant`], which is unavailable on
+/// `wasm32-unknown-unknown`; on that target only the counts are recorded and all
+/// durations are zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncCell;
+///
+/// let cell = SyncCell::new(0);
+/// cell.with_mut(|value| *value += 1);
+/// let stats = cell.stats();
+/// assert_eq!(stats.acquisitions, 1);
+/// assert_eq!(stats.contended, 0);
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LockStats {
+    /// How many times the mutex was acquired.
+    pub acquisitions: u64,
+    /// How many acquisitions found the mutex already locked and had to wait.
+    pub contended: u64,
+    /// Total time spent waiting for the mutex.
+    pub total_wait: Duration,
+    /// Longest single wait for the mutex.
+    pub max_wait: Duration,
+    /// Total time the mutex was held.
+    pub total_hold: Duration,
+    /// Longest single hold of the mutex.
+    pub max_hold: Duration,
+}
+
+/// Process-wide runtime thread check statistics, returned by [`check_stats`].
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SendCell;
+///
+/// let before = send_cells::check_stats();
+/// let cell = SendCell::new(42);
+/// assert_eq!(*cell.get(), 42);
+/// let after = send_cells::check_stats();
+/// // Other threads may be checking cells concurrently
+/// assert!(after.checks > before.checks);
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CheckStats {
+    /// How many runtime thread checks were performed.
+    pub checks: u64,
+    /// How many of those checks found the wrong thread, whether that panicked, returned an
+    /// error or leaked the value.
+    pub violations: u64,
+}
+
+#[cfg(feature = "metrics")]
+static CHECKS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "metrics")]
+static VIOLATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Counts one runtime thread check, which caught a violation if `wrong_thread`, and
+/// returns `wrong_thread`.
+#[inline(always)]
+pub(crate) fn checked(wrong_thread: bool) -> bool {
+    #[cfg(feature = "metrics")]
+    {
+        CHECKS.fetch_add(1, Ordering::Relaxed);
+        if wrong_thread {
+            VIOLATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    wrong_thread
+}
+
+/// Returns how many runtime thread checks the process has performed so far, and how many
+/// of them found the wrong thread.
+///
+/// The counters cover every checked wrapper ([`crate::SendCell`], [`crate::SendFuture`],
+/// [`crate::LateBoundCell`] and the others), to quantify how hot the checking is before
+/// reaching for the unchecked variants. They are relaxed atomics, so checks on other
+/// threads may not be reflected yet.
+///
+/// This function is only available with the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub fn check_stats() -> CheckStats {
+    CheckStats {
+        checks: CHECKS.load(Ordering::Relaxed),
+        violations: VIOLATIONS.load(Ordering::Relaxed),
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    #[cfg(feature = "metrics")]
+    acquisitions: AtomicU64,
+    #[cfg(feature = "metrics")]
+    contended: AtomicU64,
+    #[cfg(feature = "metrics")]
+    wait_nanos: AtomicU64,
+    #[cfg(feature = "metrics")]
+    max_wait_nanos: AtomicU64,
+    #[cfg(feature = "metrics")]
+    hold_nanos: AtomicU64,
+    #[cfg(feature = "metrics")]
+    max_hold_nanos: AtomicU64,
+}
+
+/// When the mutex was acquired, for measuring the hold time.
+#[derive(Debug)]
+pub(crate) struct Held {
+    #[cfg(any(feature = "metrics", feature = "tracing"))]
+    at: Option<Instant>,
+}
+
+impl Recorder {
+    #[inline]
+    pub(crate) fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    /// Locks `mutex` on behalf of the cell type `cell`, recording the acquisition and how
+    /// long it waited.
+    #[inline]
+    pub(crate) fn lock<'a, M: RawMutex, T>(
+        &self,
+        mutex: &'a lock_api::Mutex<M, T>,
+        cell: &'static str,
+    ) -> (MutexGuard<'a, M, T>, Held) {
+        #[cfg(not(any(feature = "metrics", feature = "tracing")))]
+        {
+            _ = cell;
+            (mutex.lock(), Held {})
+        }
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        {
+            if let Some(guard) = mutex.try_lock() {
+                return (guard, self.acquired(cell));
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(cell, "waiting for contended SyncCell lock");
+            let start = now();
+            let guard = mutex.lock();
+            let waited = elapsed(start);
+            #[cfg(feature = "metrics")]
+            {
+                let nanos = nanos(waited);
+                self.contended.fetch_add(1, Ordering::Relaxed);
+                self.wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+                self.max_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(cell, ?waited, "acquired contended SyncCell lock");
+            (guard, self.acquired(cell))
+        }
+    }
+
+    /// Records an acquisition, starting its hold time.
+    #[inline]
+    pub(crate) fn acquired(&self, cell: &'static str) -> Held {
+        _ = cell;
+        #[cfg(feature = "metrics")]
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(cell, "acquired SyncCell lock");
+        Held {
+            #[cfg(any(feature = "metrics", feature = "tracing"))]
+            at: now(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn released(&self, held: &Held, cell: &'static str) {
+        _ = (held, cell);
+        #[cfg(any(feature = "metrics", feature = "tracing"))]
+        let held = elapsed(held.at);
+        #[cfg(feature = "metrics")]
+        {
+            let nanos = nanos(held);
+            self.hold_nanos.fetch_add(nanos, Ordering::Relaxed);
+            self.max_hold_nanos.fetch_max(nanos, Ordering::Relaxed);
+        }
+        #[cfg(feature = "tracing")]
+        if held >= LONG_HOLD {
+            tracing::warn!(cell, ?held, "SyncCell lock was held for a long time");
+        } else {
+            tracing::trace!(cell, ?held, "released SyncCell lock");
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(crate) fn stats(&self) -> LockStats {
+        let duration = |nanos: &AtomicU64| Duration::from_nanos(nanos.load(Ordering::Relaxed));
+        LockStats {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            total_wait: duration(&self.wait_nanos),
+            max_wait: duration(&self.max_wait_nanos),
+            total_hold: duration(&self.hold_nanos),
+            max_hold: duration(&self.max_hold_nanos),
+        }
+    }
+}
+
+/// Enters a `TRACE` span covering one closure-based access to a cell.
+#[cfg(feature = "tracing")]
+#[inline]
+pub(crate) fn access_span(method: &'static str, cell: &'static str) -> tracing::span::EnteredSpan {
+    tracing::trace_span!("SyncCell access", method, cell).entered()
+}
+
+/// Emits a `TRACE` event for one access to, or drop of, a [`crate::SendCell`], recording
+/// its origin thread and the accessing thread.
+///
+/// Emitted before the thread check, so wrong-thread accesses show up in traces too.
+#[cfg(feature = "tracing")]
+#[inline]
+pub(crate) fn cell_access(method: &'static str, cell: &'static str, origin: crate::sys::ThreadId) {
+    let thread = crate::sys::current_id();
+    tracing::trace!(
+        method,
+        cell,
+        ?origin,
+        ?thread,
+        cross_thread = origin != thread,
+        "SendCell access"
+    );
+}
+
+/// Emits a `WARN` event for a value leaked because its [`crate::SendCell`],
+/// [`crate::SendFuture`] or other thread-checked wrapper was dropped on the wrong thread.
+///
+/// This often runs while unwinding, so the threads are recorded by id only: looking up
+/// their names would lock the name registry.
+#[cfg(feature = "tracing")]
+#[cold]
+pub(crate) fn leaked(
+    kind: &'static str,
+    value: &'static str,
+    created_at: Option<&'static core::panic::Location<'static>>,
+    origin: crate::sys::ThreadId,
+) {
+    let thread = crate::sys::current_id();
+    tracing::warn!(
+        value,
+        created_at = created_at.map(tracing::field::display),
+        ?origin,
+        ?thread,
+        "{kind} dropped from incorrect thread; leaking its value"
+    );
+}
+
+/// A `TRACE` span covering one poll of a [`crate::SendFuture`], which records the poll's
+/// duration when dropped.
+#[cfg(feature = "tracing")]
+pub(crate) struct PollSpan {
+    span: tracing::span::EnteredSpan,
+    start: Option<Instant>,
+}
+
+#[cfg(feature = "tracing")]
+impl PollSpan {
+    #[inline]
+    pub(crate) fn enter(
+        future: &'static str,
+        origin: crate::sys::ThreadId,
+        thread: crate::sys::ThreadId,
+    ) -> PollSpan {
+        let span = tracing::trace_span!(
+            "SendFuture poll",
+            future,
+            ?origin,
+            ?thread,
+            duration = tracing::field::Empty
+        );
+        PollSpan {
+            span: span.entered(),
+            start: now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for PollSpan {
+    fn drop(&mut self) {
+        self.span
+            .record("duration", tracing::field::debug(elapsed(self.start)));
+    }
+}
+
+// Instant::now panics on wasm32-unknown-unknown, so no time is measured there
+#[cfg(any(feature = "metrics", feature = "tracing"))]
+fn now() -> Option<Instant> {
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+    return Some(Instant::now());
+    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    return None;
+}
+
+#[cfg(any(feature = "metrics", feature = "tracing"))]
+fn elapsed(start: Option<Instant>) -> Duration {
+    start.map_or(Duration::ZERO, |start| start.elapsed())
+}
+
+#[cfg(feature = "metrics")]
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
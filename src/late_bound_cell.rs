@@ -0,0 +1,470 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A runtime-checked cell that binds to a thread on first access rather than on construction.
+
+[`crate::SendCell`] records the thread it is created on. Sometimes the thread that
+creates a value is not the one that will use it: a loader thread may prepare state for
+a worker that has not been spawned yet, and whose thread id is therefore unknown.
+[`LateBoundCell<T>`] records no thread at construction. The first thread to access the
+value (through [`LateBoundCell::get`], [`LateBoundCell::get_mut`] or
+[`LateBoundCell::into_inner`]) becomes its owner, and from then on the cell behaves like
+a `SendCell` bound to that thread.
+
+# Safety Model
+
+Before the cell is bound, the value may travel from the creating thread to the thread
+that first accesses it, which is exactly what `!Send` forbids in general. Constructing a
+`LateBoundCell` is therefore `unsafe`: the caller promises that the value may be moved
+to whichever thread first accesses (or drops) it. Once bound, all further accesses are
+checked at runtime.
+
+# Futures
+
+A future is often built on a setup thread and then handed to a single executor thread.
+[`LateBoundCell::into_future`] turns a cell holding a future into a [`LateBoundFuture`],
+which binds to the thread that first polls it, where a [`crate::SendFuture`] would bind
+to the setup thread and panic on the executor.
+
+# Examples
+
+```rust
+use send_cells::LateBoundCell;
+use std::cell::RefCell;
+use std::thread;
+
+// Prepared on the loader thread...
+// SAFETY: A freshly created RefCell has no ties to the loader thread
+let state = unsafe { LateBoundCell::new(RefCell::new(Vec::<u32>::new())) };
+
+// ...and used exclusively by a worker spawned later
+thread::spawn(move || {
+    state.get().borrow_mut().push(1);
+    assert_eq!(state.get().borrow().len(), 1);
+}).join().unwrap();
+```
+*/
+
+use crate::sys::{Described, ThreadId};
+use crate::unsafe_send_cell::UnsafeSendCell;
+use std::cell::OnceCell;
+use std::fmt::{Debug, Formatter};
+use std::future::{Future, IntoFuture};
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A runtime-checked cell whose thread affinity is set by the first access.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::LateBoundCell;
+/// use std::rc::Rc;
+///
+/// // SAFETY: The Rc is not shared, so it may be moved to the first accessing thread
+/// let cell = unsafe { LateBoundCell::new(Rc::new(42)) };
+/// assert_eq!(cell.bound_thread(), None);
+///
+/// assert_eq!(**cell.get(), 42);
+/// assert_eq!(cell.bound_thread(), Some(send_cells::sys::current_id()));
+/// ```
+///
+/// # Panics
+///
+/// Once bound, all methods that access the value panic if called from a different
+/// thread than the one that first accessed it. Dropping the cell there also panics if
+/// the value needs to be dropped.
+pub struct LateBoundCell<T> {
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: OnceCell<ThreadId>,
+}
+
+impl<T> LateBoundCell<T> {
+    /// Creates a new `LateBoundCell` that is not yet bound to any thread.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the value may be moved to, used on, and dropped on
+    /// whichever thread first accesses it (or drops it, if it is never accessed).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// // SAFETY: A Cell has no thread-local ties
+    /// let cell = unsafe { LateBoundCell::new(std::cell::Cell::new(1)) };
+    /// assert_eq!(cell.get().get(), 1);
+    /// ```
+    #[inline]
+    pub unsafe fn new(t: T) -> LateBoundCell<T> {
+        LateBoundCell {
+            // SAFETY: The caller vouches for moving the value to its first accessor, and
+            // all access after that is checked
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(t) }),
+            thread_id: OnceCell::new(),
+        }
+    }
+
+    /// Returns the thread the cell is bound to, or `None` if it has not been accessed yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// let cell = unsafe { LateBoundCell::new(42) };
+    /// assert!(cell.bound_thread().is_none());
+    /// cell.get();
+    /// assert!(cell.bound_thread().is_some());
+    /// ```
+    #[inline]
+    pub fn bound_thread(&self) -> Option<ThreadId> {
+        self.thread_id.get().copied()
+    }
+
+    /// Accesses the underlying value, binding the cell to the current thread if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is bound to a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// let cell = unsafe { LateBoundCell::new(vec![1, 2, 3]) };
+    /// assert_eq!(cell.get().len(), 3);
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &T {
+        self.bind_or_check("Access");
+        //safe with the check above
+        unsafe { self.inner.get() }
+    }
+
+    /// Accesses the underlying value mutably, binding the cell to the current thread if
+    /// needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is bound to a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// let mut cell = unsafe { LateBoundCell::new(vec![1, 2, 3]) };
+    /// cell.get_mut().push(4);
+    /// assert_eq!(cell.get().len(), 4);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.bind_or_check("Access");
+        //safe with the check above
+        unsafe { self.inner.get_mut() }
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is bound to a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// let cell = unsafe { LateBoundCell::new(42) };
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.bind_or_check("Access");
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so the value is taken exactly once.
+        let inner = unsafe { ManuallyDrop::take(&mut this.inner) };
+        //safe with the check above
+        unsafe { inner.into_inner() }
+    }
+
+    /// Binds the cell to the current thread if it is unbound, otherwise checks the thread.
+    fn bind_or_check(&self, operation: &str) {
+        let current = crate::sys::current_id();
+        let origin = *self.thread_id.get_or_init(|| current);
+        if crate::instrument::checked(origin != current) {
+            crate::sys::violation(format_args!(
+                "{} LateBoundCell<{}> from incorrect thread (origin thread {}, current thread {})",
+                operation,
+                std::any::type_name::<T>(),
+                Described(origin),
+                Described(current)
+            ));
+        }
+    }
+}
+
+impl<T> Drop for LateBoundCell<T> {
+    fn drop(&mut self) {
+        if std::mem::needs_drop::<T>() {
+            self.bind_or_check("Drop");
+        }
+        // SAFETY: The value is dropped exactly once, here; into_inner skips this Drop.
+        unsafe { ManuallyDrop::drop(&mut self.inner) }
+    }
+}
+
+// Trait implementations that delegate to the wrapped value
+// All of these bind or check the thread through get() and get_mut()
+impl<T: Debug> Debug for LateBoundCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T> Deref for LateBoundCell<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T> DerefMut for LateBoundCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
+    }
+}
+
+impl<T: Future> LateBoundCell<T> {
+    /// Converts the cell into a future that binds to the thread that first polls it.
+    ///
+    /// The cell may already be bound, in which case the future is polled on that thread
+    /// only. `LateBoundCell` also implements [`IntoFuture`] this way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll, Waker};
+    /// use std::thread;
+    ///
+    /// // Built on a setup thread...
+    /// let local = Rc::new(42);
+    /// // SAFETY: The Rc is not shared, so the future may move to its executor
+    /// let future = unsafe { LateBoundCell::new(async move { *local }) }.into_future();
+    ///
+    /// // ...and polled to completion on a different executor thread
+    /// let result = thread::spawn(move || {
+    ///     let mut context = Context::from_waker(Waker::noop());
+    ///     let mut future = pin!(future);
+    ///     loop {
+    ///         if let Poll::Ready(value) = future.as_mut().poll(&mut context) {
+    ///             return value;
+    ///         }
+    ///     }
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// assert_eq!(result, 42);
+    /// ```
+    #[inline]
+    pub fn into_future(self) -> LateBoundFuture<T> {
+        LateBoundFuture { cell: self }
+    }
+}
+
+// Allows `cell.await`, equivalent to `cell.into_future().await`
+impl<T: Future> IntoFuture for LateBoundCell<T> {
+    type Output = T::Output;
+    type IntoFuture = LateBoundFuture<T>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        LateBoundCell::into_future(self)
+    }
+}
+
+/// A future that binds to the thread that first polls it.
+///
+/// `LateBoundFuture<T>` is the [`crate::SendFuture`] counterpart of [`LateBoundCell`]:
+/// it is `Send`, records no thread until it is first polled, and from then on panics if
+/// polled from any other thread. Dropping it on another thread after the first poll also
+/// panics, if the future needs to be dropped.
+///
+/// A `LateBoundFuture` is created with [`LateBoundCell::into_future`].
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::LateBoundCell;
+/// use std::rc::Rc;
+///
+/// let local = Rc::new(42);
+/// // SAFETY: The Rc is not shared, so the future may be moved to its first poller
+/// let future = unsafe { LateBoundCell::new(async move { *local }) }.into_future();
+///
+/// fn requires_send<T: Send>(_: T) {}
+/// requires_send(future);
+/// ```
+#[derive(Debug)]
+pub struct LateBoundFuture<T> {
+    cell: LateBoundCell<T>,
+}
+
+impl<T> LateBoundFuture<T> {
+    /// Returns the thread the future is bound to, or `None` if it has not been polled yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    /// use std::future::Future;
+    /// use std::task::{Context, Waker};
+    ///
+    /// let mut future = Box::pin(unsafe { LateBoundCell::new(async { 42 }) }.into_future());
+    /// assert!(future.bound_thread().is_none());
+    ///
+    /// let _ = future.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+    /// assert_eq!(future.bound_thread(), Some(send_cells::sys::current_id()));
+    /// ```
+    #[inline]
+    pub fn bound_thread(&self) -> Option<ThreadId> {
+        self.cell.bound_thread()
+    }
+
+    /// Consumes the wrapper and returns the wrapped future, binding it to the current
+    /// thread if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future is bound to a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::LateBoundCell;
+    ///
+    /// let late = unsafe { LateBoundCell::new(async { 42 }) }.into_future();
+    /// let future = late.into_inner();
+    /// # drop(future);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.cell.into_inner()
+    }
+}
+
+impl<T: Future> Future for LateBoundFuture<T> {
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.cell.bind_or_check("Poll");
+        // SAFETY: After the thread check, we can safely access the inner future. We never
+        // move the inner value out of a pinned LateBoundFuture.
+        let inner = unsafe {
+            let self_mut = self.get_unchecked_mut();
+            Pin::new_unchecked(self_mut.cell.inner.get_mut())
+        };
+        inner.poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_binds_on_first_access() {
+        let mut cell = unsafe { LateBoundCell::new(Rc::new(1)) };
+        assert_eq!(cell.bound_thread(), None);
+
+        *cell.get_mut() = Rc::new(2);
+        assert_eq!(cell.bound_thread(), Some(crate::sys::current_id()));
+        assert_eq!(**cell, 2);
+        assert_eq!(*cell.into_inner(), 2);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let cell = unsafe { LateBoundCell::new(Rc::new(42)) };
+        assert_send(&cell);
+    }
+
+    #[test]
+    fn test_future_binds_on_first_poll() {
+        use crate::sys::thread;
+
+        let mut context = Context::from_waker(std::task::Waker::noop());
+
+        let local = Rc::new(21);
+        let future = unsafe { LateBoundCell::new(async move { *local * 2 }) }.into_future();
+        assert_eq!(future.bound_thread(), None);
+        let poll = thread::spawn(move || {
+            let mut future = Box::pin(future);
+            let poll = future
+                .as_mut()
+                .poll(&mut Context::from_waker(std::task::Waker::noop()));
+            assert_eq!(future.bound_thread(), Some(crate::sys::current_id()));
+            poll
+        })
+        .join()
+        .unwrap();
+        assert_eq!(poll, Poll::Ready(42));
+
+        // Once polled on the executor, the future may not be polled anywhere else.
+        // Pending has no drop glue, so dropping it here is still allowed.
+        let future = unsafe { LateBoundCell::new(std::future::pending::<()>()) }.into_future();
+        let mut future = thread::spawn(move || {
+            let mut future = Box::pin(future);
+            let poll = future
+                .as_mut()
+                .poll(&mut Context::from_waker(std::task::Waker::noop()));
+            assert!(poll.is_pending());
+            future
+        })
+        .join()
+        .unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            future.as_mut().poll(&mut context)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_worker_binds() {
+        use crate::sys::thread;
+
+        let cell = unsafe { LateBoundCell::new(std::cell::Cell::new(0)) };
+        let (cell, worker) = thread::spawn(move || {
+            cell.get().set(1);
+            (cell, crate::sys::current_id())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(cell.bound_thread(), Some(worker));
+
+        // Bound to the worker, so the spawning thread may no longer access it. Cell<i32>
+        // has no drop glue, so dropping it here is still allowed.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| cell.get().get()));
+        assert!(result.is_err());
+    }
+}
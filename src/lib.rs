@@ -21,6 +21,12 @@ but provides a more ergonomic API and additional unsafe variants for maximum per
 # Quick Start
 
 ```rust
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
 use send_cells::{SendCell, SyncCell};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -56,19 +62,126 @@ Allows sending non-Send types between threads with runtime thread checking:
 - Remembers the thread it was created on
 - Panics if accessed from a different thread
 - Perfect for single-threaded async contexts
+- Holds unsized values such as `[T]` and `dyn Trait` behind a `Box`
+
+## [`LateBoundCell<T>`]
+
+A `SendCell` that binds to a thread on first access:
+- Records no thread at construction
+- The first thread to access the value becomes its owner
+- Useful when a value is prepared for a worker that hasn't been spawned yet
+- `into_future` gives a `LateBoundFuture` that binds to the thread that first polls it,
+  for futures built on a setup thread and run on a different executor thread
+
+## [`SendCellStatic<T>`]
+
+A `SendCell` for `static` items:
+- `const` constructor taking an initialization function
+- The value is created by, and bound to, the first thread that accesses it
+- Ideal for singletons that must stay on one thread, like a main-thread UI handle
 
 ## [`SyncCell<T>`]
 
 Allows sharing non-Sync types between threads with mutex-based synchronization:
 - Uses internal mutex for thread-safe access
+- The mutex is pluggable: any `lock_api::RawMutex` (a spinlock, a critical section)
+  can back the cell through `SyncCell::with_raw_mutex`
+- `SyncCell::new_fair` hands the lock to waiting threads in FIFO order, so a busy
+  thread cannot starve the others
 - Closure-based API prevents holding locks across await points
+- `lock` returns a `!Send` guard for passing the borrow to helper functions
+- `with_both` and `lock_many` lock several cells in a fixed order, so operations spanning
+  several cells cannot deadlock
+- Threads can sleep until the value satisfies a predicate with `wait_until`, woken by any
+  release of the lock
+- Poisoning can be inspected and cleared with `is_poisoned`, `with_checked` and
+  `clear_poison`
 - Ideal for shared state in multi-threaded applications
 
+## [`ReentrantSyncCell<T>`]
+
+Shares non-Sync types like `SyncCell`, but lets the locking thread re-enter:
+- Nested `with` calls from inside a closure get another `&T` instead of deadlocking
+- `with_mut` stays exclusive and panics on conflicting re-entry, like a `RefCell`
+- Ideal for callback-heavy code such as GUI toolkits
+
+## [`SpinSyncCell<T>`]
+
+Shares non-Sync types behind a spinlock, without operating system support:
+- Same closure-based `with`/`with_mut` API as `SyncCell`, built on `core` atomics alone
+- `const` constructor, so it can live in a `static`
+- Ideal for bare-metal and kernel-adjacent code with short critical sections
+
+## `CriticalSectionCell<T>`
+
+Shares non-Sync values between main code and interrupt handlers (feature `critical-section`):
+- Same closure-based `with`/`with_mut` API as `SyncCell`, with every access inside a
+  critical section from the `critical-section` crate
+- `const` constructor and no `std` needed, for `static` peripherals and drivers
+- Re-entrant access from within a closure panics instead of aliasing the value
+
+## [`SyncWatchCell<T>`]
+
+Shares a non-Sync value and notifies readers when it changes, like a watch channel:
+- Writers `send` a new value or `modify` it in place
+- Readers `watch` the cell and wait for the next change with `wait_changed` or `changed().await`
+- Ideal for configuration and status that consumers react to
+
+## [`SyncRwCell<T>`]
+
+Allows many concurrent readers with reader-writer locking:
+- Uses an internal `RwLock` so readers don't serialize behind each other
+- Same closure-based API as `SyncCell`, split into `with_read` and `with_write`, with the
+  same poisoning methods
+- Ideal for read-heavy shared state whose type is itself `Sync`
+
+## [`SeqlockCell<T>`]
+
+Shares small `Copy` values using a sequence lock:
+- Lock-free reads that never block each other or writers
+- Exclusive writes via `set` and `update`
+- Ideal for telemetry and state structs read from many threads
+
+## [`SwapCell<T>`]
+
+Publishes whole values that readers snapshot as `Arc<T>`:
+- Readers get a cheap snapshot that never changes underneath them
+- Writers replace the value without waiting for readers to finish
+- Ideal for read-mostly configuration and lookup tables
+
+## `AsyncSyncCell<T>`
+
+Shares non-Sync types between async tasks (requires the `async` feature):
+- Uses an async mutex, so waiting tasks are suspended instead of blocking a thread
+- `with_async` keeps the lock for the duration of an async closure, across `.await`
+- Ideal for state whose operations must themselves await, like connections
+
+## [`ThreadLocalCell<T>`]
+
+Gives every thread its own value, created on demand:
+- Each accessing thread gets an independent `T` from an initialization closure
+- Threads never see each other's values, so `T` may be neither `Send` nor `Sync`
+- Ideal for per-thread caches and FFI contexts stored in shared structs
+
+## [`SendWaker<F>`]
+
+Turns a non-Send wake callback into a `Waker` usable from any thread:
+- Wakes are forwarded through a channel to the thread that created it
+- The callback runs on that thread via `run_pending` or `wait`
+- Pairs with `SendFuture` in callback-driven and single-threaded runtimes
+
 ## [`SendFuture<T>`]
 
 Wraps non-Send futures to make them Send:
 - Runtime checks ensure the future is only polled on the correct thread
 - Enables use of non-Send futures with thread pool executors
+- `SendFuture::from_pin` and `SendCell::into_future_pinned` wrap futures pinned in a `Box`,
+  which can then be recovered even after being polled
+- `with_origin_pump` forwards polls from other threads to the origin thread through an
+  `OriginPump` instead of panicking, so the future can run on a multi-threaded executor
+- The `FutureExt` extension trait wraps any future in one chained call:
+  `future.send_checked()` for a `SendFuture`, or `unsafe { future.send_unchecked() }`
+  for an `UnsafeSendFuture`
 
 # Unsafe Wrappers
 
@@ -81,11 +194,19 @@ Allows sending non-Send types without runtime checks:
 - Requires `unsafe` blocks for all access
 - Suitable for platform-specific thread guarantees
 
+## [`DebugSendCell<T>`]
+
+Checks thread access in debug builds only:
+- Behaves like `SendCell` when `debug_assertions` are enabled
+- Compiles to the zero-cost `UnsafeSendCell` behavior in release builds
+- Requires `unsafe` to construct, since release builds trust the caller
+
 ## [`UnsafeSendFuture<T>`]
 
 Wraps non-Send futures without runtime checks:
 - Zero overhead compared to the underlying future
 - Requires manual verification of thread safety
+- `get_ref`, `get_mut` and `into_inner` give (unsafe) access to the wrapped future
 
 ## [`UnsafeSyncCell<T>`]
 
@@ -99,9 +220,22 @@ Allows sharing non-Sync types without runtime checks:
 | Type | Use When | Performance | Safety |
 |------|----------|------------|--------|
 | `SendCell` | Moving non-Send types in async contexts | Good | Runtime checked |
+| `LateBoundCell` | Values prepared for a not-yet-known thread | Good | Runtime checked after first access |
+| `SendCellStatic` | Non-Send values in `static` items | Good | Runtime checked |
 | `SyncCell` | Sharing non-Sync types between threads | Good | Mutex protected |
+| `ReentrantSyncCell` | Shared state re-entered from callbacks | Good | Re-entrant mutex protected |
+| `SpinSyncCell` | Sharing non-Sync types without an OS | Good for short accesses | Spinlock protected |
+| `CriticalSectionCell` | Sharing with interrupt handlers | Good for short accesses | Critical section protected |
+| `SyncWatchCell` | Values whose readers react to changes | Good | Mutex protected, versioned |
+| `SyncRwCell` | Read-heavy shared state | Good | RwLock protected |
+| `SeqlockCell` | Small `Copy` values read from many threads | Best for readers | Sequence lock |
+| `SwapCell` | Read-mostly values replaced wholesale | Best for readers | Arc snapshots |
+| `AsyncSyncCell` | Shared state held across `.await` | Good | Async mutex protected |
+| `ThreadLocalCell` | Per-thread copies of non-Send resources | Good | Per-thread values |
+| `SendWaker` | Waking non-Send executors from any thread | Good | Runtime checked |
 | `SendFuture` | Using non-Send futures with Send requirements | Good | Runtime checked |
 | `UnsafeSendCell` | Platform guarantees thread safety | Best | Manual verification |
+| `DebugSendCell` | Validating thread use in CI, zero cost in release | Best in release | Checked in debug builds |
 | `UnsafeSyncCell` | External synchronization guarantees | Best | Manual verification |
 | `UnsafeSendFuture` | Maximum performance for futures | Best | Manual verification |
 
@@ -116,6 +250,133 @@ Full support for all major platforms with standard library support.
 This crate has full `wasm32-unknown-unknown` support with runtime thread checks
 for web workers. Thread IDs are properly tracked even in WASM environments.
 
+With the `atomics` target feature (threads), every web worker gets its own thread id, so
+cells are checked across workers just like across native threads. Because the browser
+main thread may not block, `SyncCell` uses `raw_mutex::WasmRawMutex` there by default: a
+lock that spins on the main thread and sleeps in workers. The `SyncCell::wait_*` methods
+block by design and panic on the main thread; call them from a worker.
+
+## Emscripten
+
+`wasm32-unknown-emscripten` is supported as well. There, threads are Emscripten's
+pthreads and the crate uses `std::thread` directly, without `wasm_thread` or
+`wasm-bindgen`; every pthread gets its own thread id, and `SyncCell` uses its usual lock.
+
+## `no_std`
+
+Without the default `std` feature the crate is `no_std`, needing only `core` and `alloc`.
+`SendCell`, `SendFuture`, `DebugSendCell`, `UnsafeSendCell`, `UnsafeSyncCell`,
+`SeqlockCell`, `SpinSyncCell`, `CriticalSectionCell` and `SyncCell` remain. The checked cells get thread ids from
+a function the platform installs once with the unsafe `sys::set_id_source`, which must
+return a distinct id for every running thread, and `SyncCell` defaults
+to `raw_mutex::SpinRawMutex` and can use any other `lock_api::RawMutex`. Without `std`,
+`SyncCell` can't wait for changes and is never poisoned, since panics can't be detected.
+
+## Miri
+
+The crate's unsafe code follows strict provenance: pointers are only derived by
+reborrowing or `cast`, never through integers, so crates using it can run their tests
+under Miri with `-Zmiri-strict-provenance`. Under `cfg(miri)`, `SeqlockCell` readers take
+the writer lock instead of copying optimistically, since Miri would report the discarded
+racing copies as data races. `scripts/native/miri` runs the crate's own tests this way.
+
+## Thread Identity
+
+The thread identity used by all runtime checks is public: [`sys::current_id`] returns the
+current thread's `sys::ThreadId`, so other crates can build their own thread-affinity
+checks that behave the same on every platform. Ids come from a process-wide 64-bit
+counter and are never reused, so a cell can't be unlocked by a new thread that happens
+to get a dead thread's operating system id.
+
+Wrong-thread panics of `SendCell` and `SendFuture` print where the cell or future was
+created (its constructors are `#[track_caller]`), and both the origin thread and the
+current thread, each by id and, if it has one, by name. `sys::thread_name` looks up the
+name of a running thread by id, and `sys::thread::is_main_thread` tells whether the
+current thread is the process's main thread on macOS, iOS, Linux, Android, Windows with
+the MSVC toolchain, the BSDs with `pthread_main_np` and WebAssembly.
+
+# Cargo Features
+
+All features except `std` and `wasm-bindgen` are disabled by default.
+
+- `std` (default): Everything that needs the standard library. Turning off default
+  features makes the crate `no_std`, as described under Platform Support. All other
+  features except `nightly` and `critical-section` enable `std`.
+- `wasm-bindgen` (default): On `wasm32`, uses `wasm_thread` for web worker support and
+  to keep the browser main thread from blocking. Builds with their own JavaScript glue,
+  or without threads, can turn off default features to avoid depending on
+  `wasm-bindgen`; thread identity and all runtime checks keep working. Has no effect on
+  other targets.
+- `futures`: Wrappers for traits from the `futures` ecosystem, such as
+  `SendSink` for non-Send `Sink`s (created with `SendCell::into_sink`),
+  `SendStream` for non-Send `Stream`s (created with `SendCell::into_stream`) and the
+  zero-cost `UnsafeSendStream` for non-Send `Stream`s (created with
+  `UnsafeSendCell::into_stream`). `SendFuture` also implements `FusedFuture` when the
+  wrapped future does, so it can be used in `select!` loops without `.fuse()`.
+- `futures-io`, `tokio-io`: `SendAsyncRead` and `SendAsyncWrite`, which make non-Send
+  asynchronous I/O objects usable with Send-bounded codecs and servers (created with
+  `SendCell::into_async_read` and `SendCell::into_async_write`). Each feature enables
+  the corresponding ecosystem's I/O traits on the wrappers.
+- `async`: `AsyncSyncCell`, a `SyncCell` whose lock can be held across `.await`.
+- `tower`: `SendService`, which makes a non-Send `tower` service usable in Send-bounded
+  middleware stacks (created with `SendCell::into_service`).
+- `serde`: `Serialize` and `Deserialize` for `SendCell`. Serializing checks the thread
+  like any other access; a deserialized cell is bound to the deserializing thread.
+- `fragile`: `From` conversions between `SendCell` and `fragile::Fragile`, for
+  codebases migrating between the two crates. Conversions must happen on the owning
+  thread, so the converted value stays bound to it.
+- `send_wrapper`: `From` conversions between `SendCell` and
+  `send_wrapper::SendWrapper`, for values crossing API boundaries that use that type.
+  As with `fragile`, conversions must happen on the owning thread.
+- `std-thread-id`: running threads' `sys::ThreadId`s are also recorded under their
+  `std::thread::ThreadId`s, so stored std ids convert with `sys::ThreadId::from_std`.
+- `test-util`: `sys::thread::mock_current_id` and `sys::thread::with_current_id` make
+  the current thread report a simulated id from `sys::thread::mock_id`, so tests can
+  trigger wrong-thread checks without spawning threads. Mock ids can't leave the thread
+  that created them, and real threads' ids can't be mocked, so the checks stay sound.
+- `backtrace`: `SendCell` and `SendFuture` capture a backtrace when they are created and
+  print it in wrong-thread panics, to find which of many cells was misused. Capturing is
+  slow, so this is meant for debugging builds.
+- `abort-on-violation`: Thread-affinity violations (a checked type accessed or dropped on
+  the wrong thread) print their message and abort the process instead of panicking, for
+  FFI-heavy programs where unwinding through foreign frames is undefined behavior. Code
+  that catches wrong-thread panics, such as tests, no longer can.
+- `windows`: On Windows, `SendCell::new_bound_to_window` binds a cell to the thread that
+  owns a window (looked up with `sys::thread::window_thread_id`), so wrapped window
+  handles and COM objects are checked against the UI thread rather than the thread that
+  wrapped them.
+- `critical-section`: `CriticalSectionCell`, which shares values with interrupt handlers
+  through the `critical-section` crate. Does not need `std`; the platform must provide a
+  critical section implementation.
+- `nightly`: Implements the unstable `CoerceUnsized` trait for `UnsafeSendCell` and
+  `UnsafeSyncCell`, so that for example an `UnsafeSendCell<Box<T>>` coerces to
+  `UnsafeSendCell<Box<dyn Trait>>` like a `Box` does. `SyncCell` keeps its value inside a
+  `lock_api::Mutex`, `SyncRwCell` inside a `std::sync::RwLock`, and `SendCell` inside a
+  `ManuallyDrop` (so that it is never dropped on the wrong thread), none of which supports
+  the coercion; a `Box<SendCell<T>>` still coerces to `Box<SendCell<dyn Trait>>` without
+  it. Requires a nightly compiler.
+- `parking_lot`: `SyncCell` uses `parking_lot`'s raw mutex by default, instead of one
+  built on `std::sync`. Poisoning is deliberately kept, so enabling the feature anywhere in
+  the dependency graph never changes how a cell behaves after a panic.
+- `deadlock-detection`: In debug builds, `SyncCell` tracks the cells each thread holds
+  and panics, describing the cycle, when a cell is locked re-entrantly or two cells are
+  locked in the opposite order to an earlier acquisition. Release builds are unaffected.
+- `metrics`: `SyncCell::stats` reports how often a cell was locked, how often callers had
+  to wait, and how long they waited and held the lock, to find hot cells in production.
+  `check_stats` reports how many runtime thread checks `SendCell` and the other checked
+  wrappers performed process-wide, and how many caught a violation.
+- `tracing`: `SyncCell` emits `tracing` spans around `with` and `with_mut`, and events
+  for lock acquisition, contention and holds longer than 10ms, so lock behavior shows up
+  in traces without manual wrapping. Every `SendFuture` poll runs in a span recording the
+  origin thread, the polling thread and the poll's duration, and `SendCell` emits a
+  `TRACE` event with the origin and current thread on every `get`, `get_mut`,
+  `into_inner` and drop, to reconstruct cross-thread access patterns.
+
+Building with `RUSTFLAGS="--cfg loom"` (a cfg rather than a feature, following the
+`loom` crate's convention) swaps `SyncCell`'s default raw mutex, its waiting primitives
+and the thread id source for `loom`'s equivalents, so code using `SyncCell` can be
+model-checked with `loom::model`. Cells must then only be used inside the model.
+
 # Examples
 
 ## Async Runtime Integration
@@ -224,8 +485,22 @@ and can rigorously verify thread safety.
 
 ## Memory Overhead
 
-- **SendCell**: One `ThreadId` + wrapped value
-- **SyncCell**: One `Mutex<()>` + wrapped value
+- **SendCell**: One `ThreadId` + one reference to the creation `Location` + one drop-policy pointer + wrapped value;
+  with the `backtrace` feature, also one `Arc<Backtrace>`
+- **LateBoundCell**: One `OnceCell<ThreadId>` + wrapped value
+- **SendCellStatic**: One `OnceLock` + one `ThreadId` + wrapped value + init function pointer
+- **SyncCell**: One `lock_api::Mutex` holding the wrapped value + one poison flag + one pointer to the `wait_until`
+  state, which the first waiting thread allocates; the `metrics` and `deadlock-detection` features add their own fields
+- **ReentrantSyncCell**: One re-entrant mutex (raw mutex + owner and count) + one flag + wrapped value
+- **SpinSyncCell**: One `AtomicBool` + wrapped value
+- **CriticalSectionCell**: One `RefCell` borrow flag + wrapped value
+- **SyncWatchCell**: One `SyncCell` holding the wrapped value + one `Mutex` (version and wakers) + one `Condvar`
+- **SyncRwCell**: One `std::sync::RwLock` holding the wrapped value
+- **SeqlockCell**: One `AtomicUsize` + wrapped value
+- **AsyncSyncCell**: One async mutex + wrapped value
+- **SwapCell**: One `arc_swap::ArcSwap<T>` (a pointer); each published value is a separate allocation
+- **ThreadLocalCell**: One `Mutex<HashMap>` + one boxed value per accessing thread
+- **DebugSendCell**: One `ThreadId` + wrapped value in debug builds; no overhead in release
 - **UnsafeSendCell**: No overhead (transparent wrapper)
 
 # Related Crates
@@ -234,13 +509,87 @@ and can rigorously verify thread safety.
 - [once_cell](https://crates.io/crates/once_cell) - Lazy initialization primitives
 - [parking_lot](https://crates.io/crates/parking_lot) - Alternative synchronization primitives
 */
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "nightly", feature(coerce_unsized))]
+
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod async_sync_cell;
+#[cfg(feature = "critical-section")]
+pub mod critical_section_cell;
+mod deadlock;
+pub mod debug_send_cell;
+pub mod future_ext;
+mod instrument;
+#[cfg(feature = "std")]
+pub mod late_bound_cell;
+#[cfg(feature = "std")]
+pub mod origin_pump;
+pub mod raw_mutex;
+#[cfg(feature = "std")]
+pub mod reentrant_sync_cell;
 pub mod send_cell;
+#[cfg(feature = "std")]
+pub mod send_cell_static;
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+pub mod send_io;
+#[cfg(feature = "tower")]
+pub mod send_service;
+#[cfg(feature = "std")]
+pub mod send_waker;
+pub mod seqlock_cell;
+pub mod spin_sync_cell;
+#[cfg(feature = "std")]
+pub mod swap_cell;
 pub mod sync_cell;
+#[cfg(feature = "std")]
+pub mod sync_rw_cell;
+#[cfg(feature = "std")]
+pub mod sync_watch_cell;
 pub mod sys;
+#[cfg(feature = "std")]
+pub mod thread_local_cell;
 pub mod unsafe_send_cell;
 pub mod unsafe_sync_cell;
 
-pub use send_cell::{SendCell, SendFuture};
-pub use sync_cell::SyncCell;
+#[cfg(feature = "async")]
+pub use async_sync_cell::AsyncSyncCell;
+#[cfg(feature = "critical-section")]
+pub use critical_section_cell::CriticalSectionCell;
+pub use debug_send_cell::DebugSendCell;
+pub use future_ext::FutureExt;
+#[cfg(feature = "metrics")]
+pub use instrument::{CheckStats, LockStats, check_stats};
+#[cfg(feature = "std")]
+pub use late_bound_cell::{LateBoundCell, LateBoundFuture};
+#[cfg(feature = "std")]
+pub use origin_pump::{OriginPump, Pump, PumpHandle, PumpJob, PumpedSendFuture};
+#[cfg(feature = "std")]
+pub use reentrant_sync_cell::ReentrantSyncCell;
+pub use send_cell::{SendCell, SendFuture, SendIter, TrySendFuture, WrongThreadError};
+#[cfg(feature = "futures")]
+pub use send_cell::{SendSink, SendStream};
+#[cfg(feature = "std")]
+pub use send_cell_static::SendCellStatic;
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+pub use send_io::{SendAsyncRead, SendAsyncWrite};
+#[cfg(feature = "tower")]
+pub use send_service::SendService;
+#[cfg(feature = "std")]
+pub use send_waker::SendWaker;
+pub use seqlock_cell::SeqlockCell;
+pub use spin_sync_cell::{SpinGuard, SpinSyncCell};
+#[cfg(feature = "std")]
+pub use swap_cell::SwapCell;
+pub use sync_cell::{SyncCell, SyncGuard};
+#[cfg(feature = "std")]
+pub use sync_rw_cell::SyncRwCell;
+#[cfg(feature = "std")]
+pub use sync_watch_cell::{SyncWatchCell, WatchRef, Watcher};
+#[cfg(feature = "std")]
+pub use thread_local_cell::ThreadLocalCell;
+#[cfg(feature = "futures")]
+pub use unsafe_send_cell::UnsafeSendStream;
 pub use unsafe_send_cell::{UnsafeSendCell, UnsafeSendFuture};
 pub use unsafe_sync_cell::UnsafeSyncCell;
@@ -0,0 +1,605 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Remote polling of non-Send futures from any thread.
+
+A [`crate::SendFuture`] panics when a multi-threaded executor polls it from the wrong
+thread, so in practice it can only run on an executor that keeps the task where it was
+spawned. [`crate::SendFuture::with_origin_pump`] lifts that restriction: the returned
+[`PumpedSendFuture`] may be polled from any thread. Polls on the origin thread go straight
+to the wrapped future. A poll on any other thread submits a job to a [`Pump`] and returns
+`Pending`; the pump runs the job on the origin thread, which polls the future there with
+the foreign task's waker.
+
+[`OriginPump`] is a ready-made pump: a channel whose jobs the origin thread runs with
+[`OriginPump::run_pending`] or [`OriginPump::wait`], for example from its event loop.
+Any other scheduler can act as a pump by implementing [`Pump`], which is implemented for
+closures taking a [`PumpJob`].
+
+# Examples
+
+```rust
+use send_cells::{OriginPump, SendCell};
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+let pump = OriginPump::new();
+let local = Rc::new(21);
+let future = SendCell::new(async move { *local * 2 })
+    .into_future()
+    .with_origin_pump(pump.handle());
+
+// An executor thread polls the !Send future
+let executor = thread::spawn(move || {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::yield_now();
+    }
+});
+
+// Meanwhile the origin thread runs the polls it forwarded
+while !executor.is_finished() {
+    pump.run_pending();
+    thread::yield_now();
+}
+assert_eq!(executor.join().unwrap(), 42);
+```
+*/
+
+use crate::SendCell;
+use crate::SendFuture;
+use crate::sys::ThreadId;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// A job that must run on the origin thread of a [`PumpedSendFuture`].
+pub type PumpJob = Box<dyn FnOnce() + Send>;
+
+/// Runs [`PumpJob`]s on the origin thread of the futures that submit them.
+///
+/// A pump is typically a queue drained by the origin thread's event loop. Jobs must
+/// eventually be run on that thread; running one on any other thread panics. A pump that
+/// drops jobs instead (for example because its event loop has exited) leaves the futures
+/// waiting on them pending forever, and their values are leaked rather than dropped on the
+/// wrong thread.
+///
+/// `Pump` is implemented for closures, so a job can be posted to any scheduler:
+///
+/// ```rust
+/// use send_cells::PumpJob;
+/// use std::sync::mpsc::channel;
+///
+/// let (sender, receiver) = channel::<PumpJob>();
+/// let pump = move |job: PumpJob| {
+///     let _ = sender.send(job);
+/// };
+/// # let _ = (pump, receiver);
+/// ```
+pub trait Pump: Send + Sync + 'static {
+    /// Submits `job` to be run on the origin thread.
+    fn submit(&self, job: PumpJob);
+}
+
+impl<F: Fn(PumpJob) + Send + Sync + 'static> Pump for F {
+    fn submit(&self, job: PumpJob) {
+        self(job)
+    }
+}
+
+/// A channel-based [`Pump`] whose jobs are run by the thread that owns it.
+///
+/// Create the `OriginPump` on the origin thread, pass [`Self::handle`] to
+/// [`crate::SendFuture::with_origin_pump`], and call [`Self::run_pending`] or
+/// [`Self::wait`] from that thread to run the forwarded polls.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::OriginPump;
+/// use send_cells::Pump;
+///
+/// let pump = OriginPump::new();
+/// pump.handle().submit(Box::new(|| println!("on the origin thread")));
+/// assert_eq!(pump.run_pending(), 1);
+/// ```
+pub struct OriginPump {
+    sender: Sender<PumpJob>,
+    receiver: Receiver<PumpJob>,
+}
+
+/// A handle for submitting jobs to an [`OriginPump`] from any thread.
+///
+/// Jobs submitted after the `OriginPump` is dropped are discarded.
+#[derive(Clone)]
+pub struct PumpHandle {
+    sender: Sender<PumpJob>,
+}
+
+impl OriginPump {
+    /// Creates a new, empty `OriginPump`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::OriginPump;
+    ///
+    /// let pump = OriginPump::new();
+    /// assert_eq!(pump.run_pending(), 0);
+    /// ```
+    pub fn new() -> OriginPump {
+        let (sender, receiver) = channel();
+        OriginPump { sender, receiver }
+    }
+
+    /// Returns a handle that submits jobs to this pump.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{OriginPump, SendCell};
+    ///
+    /// let pump = OriginPump::new();
+    /// let future = SendCell::new(async { 42 })
+    ///     .into_future()
+    ///     .with_origin_pump(pump.handle());
+    /// # drop(future);
+    /// ```
+    #[inline]
+    pub fn handle(&self) -> PumpHandle {
+        PumpHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Runs every job submitted so far, without blocking.
+    ///
+    /// Returns how many jobs were run.
+    ///
+    /// # Panics
+    ///
+    /// Jobs submitted by a [`PumpedSendFuture`] panic if run on a thread other than the
+    /// future's origin thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{OriginPump, Pump};
+    ///
+    /// let pump = OriginPump::new();
+    /// let handle = pump.handle();
+    /// handle.submit(Box::new(|| {}));
+    /// handle.submit(Box::new(|| {}));
+    /// assert_eq!(pump.run_pending(), 2);
+    /// assert_eq!(pump.run_pending(), 0);
+    /// ```
+    pub fn run_pending(&self) -> usize {
+        self.receiver.try_iter().map(|job| job()).count()
+    }
+
+    /// Blocks the current thread until a job is submitted, then runs it and any others
+    /// that are pending.
+    ///
+    /// Returns immediately if a job is already pending.
+    ///
+    /// # Panics
+    ///
+    /// Jobs submitted by a [`PumpedSendFuture`] panic if run on a thread other than the
+    /// future's origin thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{OriginPump, Pump};
+    /// use std::thread;
+    ///
+    /// let pump = OriginPump::new();
+    /// let handle = pump.handle();
+    /// thread::spawn(move || handle.submit(Box::new(|| {})));
+    ///
+    /// // Returns once the other thread has submitted its job
+    /// pump.wait();
+    /// ```
+    pub fn wait(&self) {
+        // The OriginPump holds a sender, so the channel is never disconnected
+        let job = self.receiver.recv().expect("pump channel disconnected");
+        job();
+        self.run_pending();
+    }
+}
+
+impl Default for OriginPump {
+    fn default() -> OriginPump {
+        OriginPump::new()
+    }
+}
+
+impl Debug for OriginPump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OriginPump").finish_non_exhaustive()
+    }
+}
+
+impl Pump for PumpHandle {
+    fn submit(&self, job: PumpJob) {
+        // If the OriginPump is gone nobody can run the job, so a send error is ignored
+        let _ = self.sender.send(job);
+    }
+}
+
+impl Debug for PumpHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PumpHandle").finish_non_exhaustive()
+    }
+}
+
+/// State shared between a [`PumpedSendFuture`] and the jobs it submits.
+struct Shared<T: Future> {
+    origin: ThreadId,
+    state: Mutex<State<T>>,
+}
+
+struct State<T: Future> {
+    // None while the origin thread polls it, and once it has completed
+    future: Option<SendCell<Pin<Box<T>>>>,
+    output: Option<T::Output>,
+    // The waker of the most recent foreign poll
+    waker: Option<Waker>,
+    // Whether a poll job is waiting to run on the origin thread
+    scheduled: bool,
+    taken: bool,
+}
+
+impl<T: Future> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // The future is never polled or dropped under the lock, so a panic there can't
+        // leave the state inconsistent
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Polls the future on the origin thread, storing its output once it is ready.
+    fn poll_here(&self, waker: &Waker) {
+        let Some(mut future) = self.lock().future.take() else {
+            return;
+        };
+        let poll = future
+            .get_mut()
+            .as_mut()
+            .poll(&mut Context::from_waker(waker));
+        match poll {
+            Poll::Ready(output) => {
+                self.lock().output = Some(output);
+                // Dropped here, on the origin thread
+                drop(future);
+            }
+            Poll::Pending => self.lock().future = Some(future),
+        }
+    }
+
+    /// The job submitted by a foreign poll.
+    fn run_job(&self) {
+        let waker = {
+            let mut state = self.lock();
+            state.scheduled = false;
+            state.waker.clone()
+        };
+        let Some(waker) = waker else { return };
+        self.poll_here(&waker);
+        // A pending future has the waker and wakes the task itself
+        let waker = {
+            let state = self.lock();
+            if state.output.is_none() {
+                return;
+            }
+            state.waker.clone()
+        };
+        waker.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T: Future> Drop for Shared<T> {
+    fn drop(&mut self) {
+        if self.origin != crate::sys::current_id() {
+            let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+            // Dropping the future here would be unsound; it can only be leaked
+            std::mem::forget(state.future.take());
+        }
+    }
+}
+
+/// A [`crate::SendFuture`] that can be polled from any thread, created by
+/// [`crate::SendFuture::with_origin_pump`].
+///
+/// Polling on the origin thread polls the wrapped future directly. Polling on any other
+/// thread submits a job to the [`Pump`] and returns `Pending`; once the pump has run the
+/// job on the origin thread, the task is woken as the wrapped future makes progress, and
+/// the output is returned by the next poll.
+///
+/// Dropping a `PumpedSendFuture` never panics: on a foreign thread, the wrapped future is
+/// handed to the pump to be dropped on the origin thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::{OriginPump, SendCell};
+///
+/// let pump = OriginPump::new();
+/// let future = SendCell::new(async { 42 })
+///     .into_future()
+///     .with_origin_pump(pump.handle());
+///
+/// fn requires_send<T: Send>(_: T) {}
+/// requires_send(future);
+/// ```
+pub struct PumpedSendFuture<T: Future + 'static>
+where
+    T::Output: Send,
+{
+    shared: Arc<Shared<T>>,
+    pump: Box<dyn Pump>,
+}
+
+impl<T: Future + 'static> SendFuture<T>
+where
+    T::Output: Send,
+{
+    /// Converts the future into one that forwards polls from other threads to the origin
+    /// thread through `pump`, instead of panicking.
+    ///
+    /// This makes non-Send futures usable on multi-threaded executors, as long as the
+    /// origin thread runs the pump's jobs, for example with an [`OriginPump`] drained by
+    /// its event loop. The wrapped future is only ever polled and dropped on the origin
+    /// thread; see [`PumpedSendFuture`].
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if polled after it has returned its output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{OriginPump, SendCell};
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll, Waker};
+    /// use std::thread;
+    ///
+    /// let pump = OriginPump::new();
+    /// let local = Rc::new(42);
+    /// let future = SendCell::new(async move { *local })
+    ///     .into_future()
+    ///     .with_origin_pump(pump.handle());
+    ///
+    /// // A foreign poll forwards the poll to the origin thread
+    /// let future = thread::spawn(move || {
+    ///     let mut future = Box::pin(future);
+    ///     let poll = future.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+    ///     assert!(poll.is_pending());
+    ///     future
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// // The origin thread runs it, and the output is ready for the next poll
+    /// assert_eq!(pump.run_pending(), 1);
+    /// let mut future = pin!(future);
+    /// let poll = future.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+    /// assert_eq!(poll, Poll::Ready(42));
+    /// ```
+    pub fn with_origin_pump(self, pump: impl Pump) -> PumpedSendFuture<T> {
+        let (inner, origin) = self.into_parts();
+        // SAFETY: The future is only moved into a box, which belongs to the origin thread
+        // just like the future did; it is not accessed here.
+        let future = unsafe { SendCell::new_bound_to(Box::pin(inner.into_inner()), origin) };
+        PumpedSendFuture {
+            shared: Arc::new(Shared {
+                origin,
+                state: Mutex::new(State {
+                    future: Some(future),
+                    output: None,
+                    waker: None,
+                    scheduled: false,
+                    taken: false,
+                }),
+            }),
+            pump: Box::new(pump),
+        }
+    }
+}
+
+impl<T: Future + 'static> Future for PumpedSendFuture<T>
+where
+    T::Output: Send,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let shared = &self.shared;
+        if shared.origin == crate::sys::current_id() {
+            shared.poll_here(cx.waker());
+        }
+        let mut state = shared.lock();
+        assert!(!state.taken, "PumpedSendFuture polled after completion");
+        if let Some(output) = state.output.take() {
+            state.taken = true;
+            return Poll::Ready(output);
+        }
+        if shared.origin != crate::sys::current_id() {
+            state.waker = Some(cx.waker().clone());
+            if !state.scheduled {
+                state.scheduled = true;
+                drop(state);
+                let shared = Arc::clone(shared);
+                self.pump.submit(Box::new(move || shared.run_job()));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<T: Future + 'static> Drop for PumpedSendFuture<T>
+where
+    T::Output: Send,
+{
+    fn drop(&mut self) {
+        if self.shared.origin != crate::sys::current_id() && self.shared.lock().future.is_some() {
+            // Let the last reference, and with it the future, go on the origin thread
+            let shared = Arc::clone(&self.shared);
+            self.pump.submit(Box::new(move || drop(shared)));
+        }
+    }
+}
+
+impl<T: Future + 'static> Debug for PumpedSendFuture<T>
+where
+    T::Output: Send,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PumpedSendFuture")
+            .field("origin", &self.shared.origin)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_origin_poll_is_direct() {
+        let pump = OriginPump::new();
+        let local = Rc::new(42);
+        let future = SendCell::new(async move { *local })
+            .into_future()
+            .with_origin_pump(pump.handle());
+        let mut future = std::pin::pin!(future);
+        let poll = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()));
+        assert_eq!(poll, Poll::Ready(42));
+        assert_eq!(pump.run_pending(), 0);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let local = Rc::new(());
+        let future = SendCell::new(async move {
+            let _ = &local;
+        })
+        .into_future()
+        .with_origin_pump(|job: PumpJob| job());
+        assert_send(&future);
+    }
+
+    /// Runs `future` to completion on the current thread, parking while it is pending.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        use std::task::Wake;
+
+        struct Unpark(crate::sys::thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(Unpark(crate::sys::thread::current())));
+        let mut future = std::pin::pin!(future);
+        loop {
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => crate::sys::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_foreign_poll_runs_on_origin() {
+        use crate::sys::thread;
+        use std::sync::mpsc::channel;
+
+        let pump = OriginPump::new();
+        let origin = crate::sys::current_id();
+        let local = Rc::new(2);
+        let future = SendCell::new(async move {
+            // Suspend once, so the task is woken by the future itself
+            let mut yielded = false;
+            std::future::poll_fn(|cx| {
+                assert_eq!(crate::sys::current_id(), origin);
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+            *local * 21
+        })
+        .into_future()
+        .with_origin_pump(pump.handle());
+
+        let (sender, receiver) = channel();
+        let handle = thread::spawn(move || sender.send(block_on(future)).unwrap());
+        let output = loop {
+            pump.run_pending();
+            if let Ok(output) = receiver.try_recv() {
+                break output;
+            }
+            thread::yield_now();
+        };
+        handle.join().unwrap();
+        assert_eq!(output, 42);
+    }
+
+    #[test]
+    fn test_foreign_drop_runs_on_origin() {
+        use crate::sys::thread;
+
+        let pump = OriginPump::new();
+        let local = Rc::new(());
+        let weak = Rc::downgrade(&local);
+        let future = SendCell::new(async move {
+            let _local = local;
+            std::future::pending::<()>().await
+        })
+        .into_future()
+        .with_origin_pump(pump.handle());
+
+        thread::spawn(move || {
+            let mut future = Box::pin(future);
+            let poll = future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()));
+            assert!(poll.is_pending());
+        })
+        .join()
+        .unwrap();
+        assert!(weak.upgrade().is_some());
+
+        // The forwarded poll, then the drop
+        assert_eq!(pump.run_pending(), 2);
+        assert!(weak.upgrade().is_none());
+    }
+}
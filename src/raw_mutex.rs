@@ -0,0 +1,675 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Raw mutexes that can back a [`crate::SyncCell`].
+
+[`SyncCell<T, M>`](crate::SyncCell) is generic over a [`lock_api::RawMutex`], so embedded
+and specialized users can supply their own lock (a spinlock, a critical section, a
+priority-aware mutex) without forking the crate. The default, [`DefaultRawMutex`], is
+[`StdRawMutex`], which is built on `std::sync`; with the `parking_lot` feature it is
+`parking_lot::RawMutex` instead. On `wasm32` with the `atomics` target feature it is
+[`WasmRawMutex`], whatever the features, except on Emscripten, which can block anywhere.
+Without the `std` feature it is [`SpinRawMutex`], the only lock here that needs no `std`.
+
+Neither of those is fair: a thread that releases the lock and immediately locks it again
+usually wins against threads that have been waiting. [`FairRawMutex`] hands the lock to
+waiters in the order they arrived; [`crate::SyncCell::new_fair`] creates a cell using it.
+
+[`SpinRawMutex`] busy-waits instead of blocking and only uses `core` atomics, so it works
+where there is no operating system to block on; [`crate::SpinSyncCell`] is a cell built on
+it for `no_std` users.
+
+# Examples
+
+A minimal lock of your own (here a bare spinlock, a simpler [`SpinRawMutex`]), plugged into a `SyncCell` with [`crate::SyncCell::with_raw_mutex`]:
+
+```rust
+use send_cells::SyncCell;
+use send_cells::raw_mutex::{GuardSend, RawMutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+struct SpinLock(AtomicBool);
+
+unsafe impl RawMutex for SpinLock {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: SpinLock = SpinLock(AtomicBool::new(false));
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        while !self.try_lock() {
+            std::hint::spin_loop();
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        self.0
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+let cell = SyncCell::with_raw_mutex(vec![1, 2], SpinLock::INIT);
+cell.with_mut(|vec| vec.push(3));
+assert_eq!(cell.with(|vec| vec.len()), 3);
+```
+*/
+
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicU8;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex, PoisonError};
+
+pub use lock_api::{GuardNoSend, GuardSend, RawMutex};
+
+/// The raw mutex used by [`crate::SyncCell`] when none is specified.
+///
+/// This is [`StdRawMutex`], or `parking_lot::RawMutex` with the `parking_lot` feature.
+/// On `wasm32` with the `atomics` target feature, except Emscripten, it is [`WasmRawMutex`].
+#[cfg(all(
+    not(loom),
+    feature = "std",
+    not(feature = "parking_lot"),
+    not(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        target_feature = "atomics"
+    ))
+))]
+pub type DefaultRawMutex = StdRawMutex;
+
+/// The raw mutex used by [`crate::SyncCell`] when none is specified.
+///
+/// This is [`StdRawMutex`], or `parking_lot::RawMutex` with the `parking_lot` feature.
+/// On `wasm32` with the `atomics` target feature, except Emscripten, it is [`WasmRawMutex`].
+#[cfg(all(
+    not(loom),
+    feature = "parking_lot",
+    not(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        target_feature = "atomics"
+    ))
+))]
+pub type DefaultRawMutex = parking_lot::RawMutex;
+
+/// The raw mutex used by [`crate::SyncCell`] when none is specified.
+///
+/// This is [`StdRawMutex`], or `parking_lot::RawMutex` with the `parking_lot` feature.
+/// On `wasm32` with the `atomics` target feature, except Emscripten, it is [`WasmRawMutex`].
+#[cfg(all(
+    not(loom),
+    feature = "std",
+    target_arch = "wasm32",
+    not(target_os = "emscripten"),
+    target_feature = "atomics"
+))]
+pub type DefaultRawMutex = WasmRawMutex;
+
+/// The raw mutex used by [`crate::SyncCell`] when none is specified.
+///
+/// Without the `std` feature there is nothing to block on, so this is [`SpinRawMutex`].
+#[cfg(all(not(loom), not(feature = "std")))]
+pub type DefaultRawMutex = SpinRawMutex;
+
+/// The raw mutex used by [`crate::SyncCell`] when none is specified.
+///
+/// When building with `--cfg loom`, this is [`LoomRawMutex`], so loom can model-check
+/// code using `SyncCell`.
+#[cfg(loom)]
+pub type DefaultRawMutex = LoomRawMutex;
+
+/// A [`RawMutex`] built on `loom`'s mutex and condition variable, available when building
+/// with `--cfg loom`.
+///
+/// It is a flag guarded by a mutex, with a condition variable to wait for it, and every
+/// operation is visible to loom's scheduler, so `loom::model` explores the interleavings
+/// of threads contending for a [`crate::SyncCell`]. loom's primitives can't be created in a `const`, so they are created
+/// on first use; a `LoomRawMutex` must therefore only be used inside the `loom::model` run
+/// that first locked it.
+#[cfg(loom)]
+#[derive(Debug, Default)]
+pub struct LoomRawMutex {
+    inner: std::sync::OnceLock<(loom::sync::Mutex<bool>, loom::sync::Condvar)>,
+}
+
+#[cfg(loom)]
+impl LoomRawMutex {
+    fn inner(&self) -> &(loom::sync::Mutex<bool>, loom::sync::Condvar) {
+        self.inner.get_or_init(Default::default)
+    }
+}
+
+// SAFETY: The `locked` flag is only changed under loom's mutex, so at most one caller can
+// observe it as false and set it, and unlock wakes a thread blocked in lock.
+#[cfg(loom)]
+unsafe impl RawMutex for LoomRawMutex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: LoomRawMutex = LoomRawMutex {
+        inner: std::sync::OnceLock::new(),
+    };
+    type GuardMarker = GuardNoSend;
+
+    fn lock(&self) {
+        let (locked, unlocked) = self.inner();
+        let mut locked = locked.lock().unwrap_or_else(PoisonError::into_inner);
+        while *locked {
+            locked = unlocked
+                .wait(locked)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *locked = true;
+    }
+
+    fn try_lock(&self) -> bool {
+        let (locked, _) = self.inner();
+        let mut locked = locked.lock().unwrap_or_else(PoisonError::into_inner);
+        !core::mem::replace(&mut *locked, true)
+    }
+
+    unsafe fn unlock(&self) {
+        let (locked, unlocked) = self.inner();
+        *locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        unlocked.notify_one();
+    }
+}
+
+/// A [`RawMutex`] built on an atomic state word, which blocks on `std::sync` only when
+/// the lock is contended.
+///
+/// This is the default lock of [`crate::SyncCell`] without the `parking_lot` feature. It
+/// works wherever `std::sync` does, including `wasm32` with threads. Locking and unlocking
+/// an uncontended mutex are single atomic operations. A thread that finds the lock held
+/// spins briefly, then records that there are waiters and sleeps on a
+/// [`std::sync::Condvar`] from a small global table shared by all mutexes, so the mutex
+/// itself is one byte; unlocking only wakes sleepers when waiters were recorded.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::raw_mutex::{RawMutex, StdRawMutex};
+///
+/// let mutex = StdRawMutex::INIT;
+/// mutex.lock();
+/// assert!(!mutex.try_lock());
+/// // SAFETY: The mutex was locked above
+/// unsafe { mutex.unlock() };
+/// assert!(mutex.try_lock());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct StdRawMutex {
+    state: AtomicU8,
+}
+
+#[cfg(feature = "std")]
+impl StdRawMutex {
+    const UNLOCKED: u8 = 0;
+    const LOCKED: u8 = 1;
+    // Locked, and threads may be sleeping until it is unlocked
+    const CONTENDED: u8 = 2;
+    /// How many times a waiting thread spins before it sleeps.
+    const SPINS: u32 = 100;
+
+    /// The table entry the mutex's sleepers wait on.
+    fn parking(&self) -> &'static Parking {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: Parking = Parking {
+            mutex: Mutex::new(()),
+            wakeup: Condvar::new(),
+        };
+        static TABLE: [Parking; 64] = [EMPTY; 64];
+        let address = core::ptr::from_ref(self).addr();
+        &TABLE[(address >> 3) % TABLE.len()]
+    }
+
+    #[cold]
+    fn lock_contended(&self) {
+        for _ in 0..Self::SPINS {
+            match self.state.load(Ordering::Relaxed) {
+                Self::UNLOCKED if self.try_lock() => return,
+                // Others are already sleeping, so spinning longer won't help
+                Self::CONTENDED => break,
+                _ => core::hint::spin_loop(),
+            }
+        }
+        let parking = self.parking();
+        let mut guard = parking.mutex.lock().unwrap_or_else(PoisonError::into_inner);
+        // Marking the lock contended makes the unlocking thread wake us. Acquiring it in
+        // this state is fine: at worst the next unlock wakes sleepers needlessly.
+        while self.state.swap(Self::CONTENDED, Ordering::Acquire) != Self::UNLOCKED {
+            guard = parking
+                .wakeup
+                .wait(guard)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    #[cold]
+    fn wake(&self) {
+        let parking = self.parking();
+        // A sleeper either saw the lock unlocked under this mutex or is already waiting,
+        // so taking the mutex here can't miss one. The condition variable is shared with
+        // other mutexes, so every sleeper must wake to check its own.
+        drop(parking.mutex.lock().unwrap_or_else(PoisonError::into_inner));
+        parking.wakeup.notify_all();
+    }
+}
+
+/// Where threads sleep while a [`StdRawMutex`] is contended.
+#[cfg(feature = "std")]
+struct Parking {
+    // Never held while user code runs, so poisoning can be ignored
+    mutex: Mutex<()>,
+    wakeup: Condvar,
+}
+
+// SAFETY: The state only leaves UNLOCKED through a successful compare-exchange or swap, so
+// at most one caller holds the lock; Acquire/Release orderings order the protected data
+// with it. Sleepers mark the state CONTENDED before waiting, so unlock wakes them.
+#[cfg(feature = "std")]
+unsafe impl RawMutex for StdRawMutex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: StdRawMutex = StdRawMutex {
+        state: AtomicU8::new(StdRawMutex::UNLOCKED),
+    };
+    type GuardMarker = GuardNoSend;
+
+    #[inline]
+    fn lock(&self) {
+        if !self.try_lock() {
+            self.lock_contended();
+        }
+    }
+
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.state
+            .compare_exchange(
+                Self::UNLOCKED,
+                Self::LOCKED,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        if self.state.swap(Self::UNLOCKED, Ordering::Release) == Self::CONTENDED {
+            self.wake();
+        }
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) != Self::UNLOCKED
+    }
+}
+
+/// A first-in, first-out [`RawMutex`]: waiting threads acquire the lock in the order they
+/// started waiting.
+///
+/// Under heavy contention, ordinary mutexes let the thread that just released the lock
+/// take it again before waiting threads wake up, so a busy producer can starve its
+/// consumers. This mutex is a ticket lock instead: every call to `lock` takes a ticket and
+/// waits for its turn. Fairness has a cost, since every unlock wakes all waiters to check
+/// their tickets, and a thread can never skip the queue even when that would be faster.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncCell;
+///
+/// let cell = SyncCell::new_fair(Vec::new());
+/// cell.with_mut(|vec| vec.push(1));
+/// assert_eq!(cell.get_cloned(), [1]);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct FairRawMutex {
+    tickets: Mutex<Tickets>,
+    turn: Condvar,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct Tickets {
+    // The ticket handed to the next thread that calls lock
+    next: u64,
+    // The ticket whose holder may own the lock
+    serving: u64,
+}
+
+// SAFETY: The tickets are only changed under the std mutex, and only the thread holding
+// the ticket being served returns from lock; unlock serves the next ticket.
+#[cfg(feature = "std")]
+unsafe impl RawMutex for FairRawMutex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: FairRawMutex = FairRawMutex {
+        tickets: Mutex::new(Tickets {
+            next: 0,
+            serving: 0,
+        }),
+        turn: Condvar::new(),
+    };
+    type GuardMarker = GuardNoSend;
+
+    fn lock(&self) {
+        let mut tickets = self.tickets.lock().unwrap_or_else(PoisonError::into_inner);
+        let ticket = tickets.next;
+        tickets.next = tickets.next.wrapping_add(1);
+        while tickets.serving != ticket {
+            tickets = self
+                .turn
+                .wait(tickets)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        let mut tickets = self.tickets.lock().unwrap_or_else(PoisonError::into_inner);
+        // Only succeeds if nobody holds or is waiting for the lock
+        if tickets.next == tickets.serving {
+            tickets.next = tickets.next.wrapping_add(1);
+            true
+        } else {
+            false
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        let mut tickets = self.tickets.lock().unwrap_or_else(PoisonError::into_inner);
+        tickets.serving = tickets.serving.wrapping_add(1);
+        drop(tickets);
+        self.turn.notify_all();
+    }
+
+    fn is_locked(&self) -> bool {
+        let tickets = self.tickets.lock().unwrap_or_else(PoisonError::into_inner);
+        tickets.next != tickets.serving
+    }
+}
+
+/// A [`RawMutex`] that spins until the lock is free.
+///
+/// Spinning only uses `core` atomics, so unlike [`StdRawMutex`] this needs no operating
+/// system support: it is meant for bare-metal and kernel-adjacent code, and it backs
+/// [`crate::SpinSyncCell`]. A waiting thread burns CPU for as long as the lock is held, so
+/// it is a poor choice where critical sections are long or threads can be preempted while
+/// holding the lock.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::raw_mutex::{RawMutex, SpinRawMutex};
+///
+/// let mutex = SpinRawMutex::INIT;
+/// mutex.lock();
+/// assert!(!mutex.try_lock());
+/// // SAFETY: The mutex was locked above
+/// unsafe { mutex.unlock() };
+/// assert!(mutex.try_lock());
+/// ```
+#[derive(Debug, Default)]
+pub struct SpinRawMutex {
+    locked: AtomicBool,
+}
+
+// SAFETY: The flag is only set by a successful compare-exchange from false, so at most one
+// caller holds the lock; Acquire/Release orderings order the protected data with it.
+unsafe impl RawMutex for SpinRawMutex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: SpinRawMutex = SpinRawMutex {
+        locked: AtomicBool::new(false),
+    };
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        while !self.try_lock() {
+            // Wait with plain loads, so waiters don't fight over the cache line
+            while self.locked.load(Ordering::Relaxed) {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+}
+
+/// A [`RawMutex`] that never blocks the browser main thread.
+///
+/// Browsers don't allow the main thread to block: `Atomics.wait`, which `std::sync` and
+/// `parking_lot` use for waiting on `wasm32` with threads, traps there. This lock is a
+/// compare-exchange on an atomic flag. A thread that finds it held spins briefly, and then
+/// keeps spinning if it is the main thread, or sleeps for increasing intervals if it is a
+/// web worker, where blocking is allowed. Without the `wasm-bindgen` feature the main thread
+/// can't be told apart from workers, so every thread keeps spinning. Unlocking is a plain store, so releasing the lock
+/// never has to wake anyone.
+///
+/// This is the default lock of [`crate::SyncCell`] on `wasm32` with the `atomics` target
+/// feature, except on Emscripten, which emulates blocking on the main thread. On other targets every thread may block and it simply backs off with sleeps.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::raw_mutex::{RawMutex, WasmRawMutex};
+///
+/// let mutex = WasmRawMutex::INIT;
+/// mutex.lock();
+/// assert!(!mutex.try_lock());
+/// // SAFETY: The mutex was locked above
+/// unsafe { mutex.unlock() };
+/// assert!(mutex.try_lock());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct WasmRawMutex {
+    locked: AtomicBool,
+}
+
+#[cfg(feature = "std")]
+impl WasmRawMutex {
+    /// How many times a waiting thread spins before it starts to sleep.
+    const SPINS: u32 = 100;
+    /// The longest a waiting thread sleeps before it checks the lock again.
+    const MAX_SLEEP: Duration = Duration::from_millis(1);
+}
+
+// SAFETY: The flag is only set by a successful compare-exchange from false, so at most one
+// caller holds the lock; Acquire/Release orderings order the protected data with it.
+#[cfg(feature = "std")]
+unsafe impl RawMutex for WasmRawMutex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: WasmRawMutex = WasmRawMutex {
+        locked: AtomicBool::new(false),
+    };
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        let mut spins = 0;
+        let mut sleep = Duration::from_micros(1);
+        while !self.try_lock() {
+            while self.locked.load(Ordering::Relaxed) {
+                if spins < Self::SPINS || crate::sys::can_block() != Some(true) {
+                    spins += 1;
+                    core::hint::spin_loop();
+                } else {
+                    crate::sys::thread::sleep(sleep);
+                    sleep = (sleep * 2).min(Self::MAX_SLEEP);
+                }
+            }
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+}
+
+// Every test here locks from several threads
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_std_raw_mutex() {
+        let mutex = StdRawMutex::INIT;
+        assert!(!mutex.is_locked());
+        assert!(mutex.try_lock());
+        assert!(mutex.is_locked());
+        assert!(!mutex.try_lock());
+        unsafe { mutex.unlock() };
+        mutex.lock();
+        assert!(mutex.is_locked());
+        unsafe { mutex.unlock() };
+    }
+
+    #[test]
+    fn test_std_raw_mutex_contended() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let mutex = Arc::new(lock_api::Mutex::<StdRawMutex, u32>::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || (0..1000).for_each(|_| *mutex.lock() += 1))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), 4000);
+    }
+
+    #[test]
+    fn test_std_raw_mutex_wakes_sleepers() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        assert_eq!(core::mem::size_of::<StdRawMutex>(), 1);
+        // Several mutexes share parking slots, so each must only be woken by its own unlock
+        let mutexes: Arc<Vec<_>> = Arc::new((0..8).map(|_| StdRawMutex::INIT).collect());
+        mutexes.iter().for_each(RawMutex::lock);
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let mutexes = Arc::clone(&mutexes);
+                thread::spawn(move || {
+                    mutexes[i].lock();
+                    // SAFETY: Locked above
+                    unsafe { mutexes[i].unlock() };
+                })
+            })
+            .collect();
+        // Long enough for the waiters to stop spinning and sleep
+        thread::sleep(Duration::from_millis(50));
+        for mutex in mutexes.iter() {
+            // SAFETY: Locked above
+            unsafe { mutex.unlock() };
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(mutexes.iter().all(|mutex| !mutex.is_locked()));
+    }
+
+    #[test]
+    fn test_spin_raw_mutex_contended() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let mutex = Arc::new(lock_api::Mutex::<SpinRawMutex, u32>::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || (0..1000).for_each(|_| *mutex.lock() += 1))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), 4000);
+    }
+
+    #[test]
+    fn test_wasm_raw_mutex_contended() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let mutex = Arc::new(lock_api::Mutex::<WasmRawMutex, u32>::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || (0..1000).for_each(|_| *mutex.lock() += 1))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), 4000);
+    }
+
+    #[test]
+    fn test_fair_raw_mutex_is_fifo() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let mutex = Arc::new(lock_api::Mutex::<FairRawMutex, Vec<u32>>::new(Vec::new()));
+        let waiting = |count| {
+            // Wait until `count` threads hold or have queued for the lock
+            while unsafe { mutex.raw() }.tickets.lock().unwrap().next < count {
+                thread::yield_now();
+            }
+        };
+
+        let guard = mutex.lock();
+        let handles: Vec<_> = (0..4)
+            .map(|id| {
+                let handle = {
+                    let mutex = Arc::clone(&mutex);
+                    thread::spawn(move || mutex.lock().push(id))
+                };
+                waiting(u64::from(id) + 2);
+                handle
+            })
+            .collect();
+        drop(guard);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), [0, 1, 2, 3]);
+    }
+}
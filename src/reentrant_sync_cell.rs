@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A re-entrant variant of [`crate::SyncCell`] for callback-heavy code.
+
+[`crate::SyncCell`] deadlocks (or, with the `deadlock-detection` feature, panics) when a
+closure passed to `with` calls `with` on the same cell again. That pattern is common in
+callback-driven code such as GUI toolkits, where a handler running inside one access
+triggers another handler that reads the same state.
+
+[`ReentrantSyncCell<T>`] uses a re-entrant mutex instead: the thread that holds the lock
+may lock it again, and every nested [`ReentrantSyncCell::with`] gets another shared `&T`.
+Mutable access through [`ReentrantSyncCell::with_mut`] is still exclusive, so it can't be
+nested with any other access to the same cell; like a `RefCell`, a conflicting nested
+access panics instead of deadlocking. For mutation inside nested callbacks, store a
+`RefCell` or `Cell` in the cell and mutate through `with`.
+
+# Examples
+
+```rust
+use send_cells::ReentrantSyncCell;
+use std::cell::RefCell;
+
+let cell = ReentrantSyncCell::new(RefCell::new(vec![1, 2]));
+
+cell.with(|outer| {
+    // A callback running inside the access can use the cell again
+    let len = cell.with(|inner| inner.borrow().len());
+    outer.borrow_mut().push(len);
+});
+assert_eq!(cell.with(|vec| vec.borrow().clone()), [1, 2, 2]);
+```
+*/
+
+use crate::raw_mutex::{DefaultRawMutex, RawMutex};
+use lock_api::GetThreadId;
+use std::fmt::{Debug, Formatter};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Identifies threads by the address of a thread-local, which is unique among live
+/// threads.
+struct CurrentThreadId;
+
+// SAFETY: Thread-locals of threads that are alive at the same time have distinct
+// addresses, and the address of a thread-local is never zero.
+unsafe impl GetThreadId for CurrentThreadId {
+    const INIT: CurrentThreadId = CurrentThreadId;
+
+    fn nonzero_thread_id(&self) -> NonZeroUsize {
+        thread_local! {
+            static KEY: u8 = const { 0 };
+        }
+        KEY.with(|key| NonZeroUsize::new(key as *const u8 as usize).expect("null thread-local"))
+    }
+}
+
+/// A cell that shares a value between threads behind a re-entrant mutex.
+///
+/// Like [`crate::SyncCell`], `ReentrantSyncCell<T>` is `Sync` whenever `T: Send` and gives
+/// access through closures that hold the lock while they run. Unlike `SyncCell`, the thread
+/// holding the lock may call [`Self::with`] again from inside the closure and gets another
+/// shared reference instead of a deadlock.
+///
+/// [`Self::with_mut`] needs exclusive access, so it panics if the current thread already
+/// has the cell locked, and any access to the cell from inside its closure panics.
+///
+/// The cell is not poisoned when a closure panics: the lock is released and the value is
+/// left as the closure left it.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::ReentrantSyncCell;
+/// use std::cell::Cell;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// // Cell is Send but not Sync
+/// let cell = Arc::new(ReentrantSyncCell::new(Cell::new(0)));
+///
+/// let cell_clone = Arc::clone(&cell);
+/// thread::spawn(move || {
+///     cell_clone.with(|outer| {
+///         outer.set(1);
+///         cell_clone.with(|inner| inner.set(inner.get() + 1));
+///     });
+/// }).join().unwrap();
+///
+/// assert_eq!(cell.with(|c| c.get()), 2);
+/// ```
+pub struct ReentrantSyncCell<T> {
+    mutex: lock_api::ReentrantMutex<DefaultRawMutex, CurrentThreadId, T>,
+    // Set while a with_mut closure runs; only read or written by the lock owner
+    mutating: AtomicBool,
+}
+
+/// Clears the `mutating` flag when a `with_mut` closure finishes, even if it unwinds.
+struct Mutating<'a>(&'a AtomicBool);
+
+impl Drop for Mutating<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+impl<T> ReentrantSyncCell<T> {
+    /// Creates a new `ReentrantSyncCell` wrapping the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let cell = ReentrantSyncCell::new(42);
+    /// assert_eq!(cell.with(|value| *value), 42);
+    /// ```
+    #[inline]
+    pub fn new(value: T) -> ReentrantSyncCell<T> {
+        ReentrantSyncCell {
+            mutex: lock_api::ReentrantMutex::from_raw(
+                DefaultRawMutex::INIT,
+                CurrentThreadId,
+                value,
+            ),
+            mutating: AtomicBool::new(false),
+        }
+    }
+
+    /// Accesses the wrapped value through a closure while holding the lock.
+    ///
+    /// The closure may call `with` on the same cell again; nested calls share the lock
+    /// and each receive a `&T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from inside a [`Self::with_mut`] closure on the same cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let cell = ReentrantSyncCell::new(vec![1, 2, 3]);
+    /// let total = cell.with(|outer| outer.len() + cell.with(|inner| inner.len()));
+    /// assert_eq!(total, 6);
+    /// ```
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let guard = self.mutex.lock();
+        if self.mutating.load(Ordering::Relaxed) {
+            panic!(
+                "ReentrantSyncCell::with called while the value is mutably borrowed by with_mut"
+            );
+        }
+        f(&guard)
+    }
+
+    /// Mutably accesses the wrapped value through a closure while holding the lock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread already has the cell locked, that is, if called from
+    /// inside a [`Self::with`] or `with_mut` closure on the same cell. Re-entering the cell
+    /// from inside the closure panics as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let cell = ReentrantSyncCell::new(vec![1, 2, 3]);
+    /// cell.with_mut(|vec| vec.push(4));
+    /// assert_eq!(cell.with(|vec| vec.len()), 4);
+    /// ```
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        if self.mutex.is_owned_by_current_thread() {
+            panic!(
+                "ReentrantSyncCell::with_mut called while the current thread already has the \
+                 cell locked"
+            );
+        }
+        let _guard = self.mutex.lock();
+        self.mutating.store(true, Ordering::Relaxed);
+        let _mutating = Mutating(&self.mutating);
+        //safe since this thread holds the lock without having re-entered it, and nested
+        //accesses panic while `mutating` is set, so this is the only reference to the value
+        f(unsafe { &mut *self.mutex.data_ptr() })
+    }
+
+    /// Returns `true` if the current thread has the cell locked, that is, if it is running
+    /// inside a [`Self::with`] or [`Self::with_mut`] closure on this cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let cell = ReentrantSyncCell::new(0);
+    /// assert!(!cell.is_held_by_current_thread());
+    /// cell.with(|_| assert!(cell.is_held_by_current_thread()));
+    /// ```
+    #[inline]
+    pub fn is_held_by_current_thread(&self) -> bool {
+        self.mutex.is_owned_by_current_thread()
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let mut cell = ReentrantSyncCell::new(1);
+    /// *cell.get_mut() = 2;
+    /// assert_eq!(cell.with(|value| *value), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.mutex.get_mut()
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ReentrantSyncCell;
+    ///
+    /// let cell = ReentrantSyncCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.mutex.into_inner()
+    }
+}
+
+impl<T: Debug> Debug for ReentrantSyncCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Succeeds on re-entry too, unless a with_mut closure is running
+        match self.mutex.try_lock() {
+            Some(guard) if !self.mutating.load(Ordering::Relaxed) => (*guard).fmt(f),
+            _ => f.write_str("<locked>"),
+        }
+    }
+}
+
+impl<T: Default> Default for ReentrantSyncCell<T> {
+    fn default() -> ReentrantSyncCell<T> {
+        ReentrantSyncCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for ReentrantSyncCell<T> {
+    fn from(value: T) -> Self {
+        ReentrantSyncCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_reentrant_with() {
+        let cell = ReentrantSyncCell::new(std::cell::Cell::new(1));
+        let result = cell.with(|outer| {
+            assert!(cell.is_held_by_current_thread());
+            assert_eq!(format!("{cell:?}"), "Cell { value: 1 }");
+            cell.with(|inner| inner.set(inner.get() + 1));
+            outer.get()
+        });
+        assert_eq!(result, 2);
+        assert!(!cell.is_held_by_current_thread());
+
+        cell.with_mut(|value| {
+            value.set(3);
+            assert_eq!(format!("{cell:?}"), "<locked>");
+        });
+        assert_eq!(cell.into_inner().get(), 3);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        // Cell is Send but not Sync
+        let cell = ReentrantSyncCell::new(std::cell::Cell::new(0));
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_conflicting_reentry_panics() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let cell = ReentrantSyncCell::new(1);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cell.with(|_| cell.with_mut(|value| *value += 1));
+        }));
+        assert!(result.is_err());
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            cell.with_mut(|_| cell.with(|value| *value));
+        }));
+        assert!(result.is_err());
+
+        // Both panics released the lock and left the value alone
+        assert!(!cell.is_held_by_current_thread());
+        cell.with_mut(|value| *value += 1);
+        assert_eq!(cell.with(|value| *value), 2);
+    }
+
+    #[test]
+    fn test_concurrent_access() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cell = Arc::new(ReentrantSyncCell::new(0u32));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        cell.with_mut(|v| *v += 1);
+                        cell.with(|outer| assert_eq!(*outer, cell.with(|inner| *inner)));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cell.with(|v| *v), 4000);
+    }
+}
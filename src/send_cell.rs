@@ -24,6 +24,12 @@ from the wrong thread.
 # Example
 
 ```rust
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
 use send_cells::SendCell;
 use std::rc::Rc;
 
@@ -45,6 +51,12 @@ requires_send(cell);
 [`SendFuture<T>`] provides the same thread safety guarantees for futures:
 
 ```rust
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
 use send_cells::SendCell;
 use std::rc::Rc;
 use std::future::Future;
@@ -66,13 +78,102 @@ requires_send_future(send_future);
 ```
 */
 
-use crate::sys::thread::ThreadId;
+use crate::sys::{Described, ThreadId};
 use crate::unsafe_send_cell::UnsafeSendCell;
-use std::fmt::{Debug, Formatter};
-use std::future::Future;
-use std::ops::{Deref, DerefMut};
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use alloc::boxed::Box;
+use core::alloc::Layout;
+use core::any::Any;
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt::{Debug, Formatter};
+use core::future::{Future, IntoFuture};
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut, Index, IndexMut};
+use core::panic::{Location, RefUnwindSafe, UnwindSafe};
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+#[cfg(feature = "backtrace")]
+use std::{backtrace::Backtrace, sync::Arc};
+
+/// Where a cell or future was created, for wrong-thread panic messages.
+///
+/// Displays as the source location; with the `backtrace` feature, [`Self::backtrace`]
+/// adds the stack at creation.
+#[derive(Clone, Debug)]
+struct Creation {
+    location: &'static Location<'static>,
+    // Shared, so that cells derived from a cell keep its backtrace cheaply
+    #[cfg(feature = "backtrace")]
+    backtrace: Arc<Backtrace>,
+}
+
+impl Creation {
+    /// Records the caller's location, and with the `backtrace` feature the current stack.
+    #[track_caller]
+    fn here() -> Creation {
+        Creation {
+            location: Location::caller(),
+            #[cfg(feature = "backtrace")]
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+
+    /// The creation backtrace as a suffix for panic messages.
+    #[cfg(feature = "backtrace")]
+    fn backtrace(&self) -> impl core::fmt::Display + '_ {
+        struct Suffix<'a>(&'a Backtrace);
+
+        impl core::fmt::Display for Suffix<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                write!(f, "\ncreation backtrace:\n{}", self.0)
+            }
+        }
+
+        Suffix(&self.backtrace)
+    }
+
+    /// Nothing, since backtraces are only captured with the `backtrace` feature.
+    #[cfg(not(feature = "backtrace"))]
+    fn backtrace(&self) -> &'static str {
+        ""
+    }
+}
+
+impl core::fmt::Display for Creation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.location, f)
+    }
+}
+
+/// What a [`SendCell`] does instead of panicking when dropped on the wrong thread.
+enum OnForeignDrop {
+    /// Leak the value.
+    Leak,
+    /// Hand the value, rebuilt into a cell, to an application handler. The pointer is to
+    /// the cell's `UnsafeSendCell<T>`, which the handler reads out.
+    Handler(Box<dyn FnOnce(NonNull<()>) + Send>),
+}
+
+impl OnForeignDrop {
+    /// The policy for a cell that holds a transformed value. A handler only accepts the
+    /// original type, so it is not carried over.
+    fn derived(this: &Option<Box<OnForeignDrop>>) -> Option<Box<OnForeignDrop>> {
+        match this.as_deref() {
+            Some(OnForeignDrop::Leak) => Some(Box::new(OnForeignDrop::Leak)),
+            _ => None,
+        }
+    }
+}
+
+// SAFETY: The handler is only ever called or dropped through exclusive access, so
+// sharing references to it between threads can't race.
+unsafe impl Sync for OnForeignDrop {}
+// The handler is only called while dropping the cell, never while a caught panic could
+// observe it half-run.
+impl UnwindSafe for OnForeignDrop {}
+impl RefUnwindSafe for OnForeignDrop {}
 
 /// A runtime-checked cell that allows sending non-Send types between threads.
 ///
@@ -89,6 +190,12 @@ use std::task::{Context, Poll};
 /// Basic usage with a non-Send type:
 ///
 /// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
 /// use send_cells::SendCell;
 /// use std::rc::Rc;
 ///
@@ -107,6 +214,12 @@ use std::task::{Context, Poll};
 /// Cloning/copying wrapped values:
 ///
 /// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
 /// use send_cells::SendCell;
 ///
 /// let cell = SendCell::new(42i32);
@@ -118,10 +231,24 @@ use std::task::{Context, Poll};
 /// # Panics
 ///
 /// All methods (except `*_unchecked` variants) will panic if called from a different
-/// thread than the one where the `SendCell` was created.
-pub struct SendCell<T> {
-    inner: Option<UnsafeSendCell<T>>,
+/// thread than the one where the `SendCell` was created. The panic message names the
+/// source location where the cell was created, the origin thread and the current thread.
+/// Dropping the cell on the wrong thread panics too, and leaks the value rather than
+/// dropping it there. During unwinding, where a second panic would abort the process, the
+/// drop only leaks the value, which the `tracing` feature reports as a `WARN` event. Cells
+/// created with [`SendCell::new_leak_on_foreign_drop`] always only leak it, and cells
+/// created with [`SendCell::new_with_drop_handler`] hand it to their handler.
+// repr(C) fixes the layout that `from_boxed_slice` allocates by hand. The value is the
+// last field so that the cell can hold unsized values.
+#[repr(C)]
+pub struct SendCell<T: ?Sized> {
     thread_id: ThreadId,
+    // Where the cell was created, for wrong-thread panic messages
+    created_at: Creation,
+    // None panics; boxed so that the common case costs one pointer
+    on_foreign_drop: Option<Box<OnForeignDrop>>,
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
 }
 
 impl<T> SendCell<T> {
@@ -134,6 +261,12 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::rc::Rc;
     ///
@@ -144,14 +277,267 @@ impl<T> SendCell<T> {
     /// println!("{}", cell.get());
     /// ```
     #[inline]
+    #[track_caller]
     pub fn new(t: T) -> SendCell<T> {
         SendCell {
             //safe because drop is verified
-            inner: Some(unsafe { UnsafeSendCell::new_unchecked(t) }),
-            thread_id: crate::sys::thread::current().id(),
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(t) }),
+            thread_id: crate::sys::current_id(),
+            created_at: Creation::here(),
+            on_foreign_drop: None,
+        }
+    }
+
+    /// Creates a new `SendCell` that leaks its value, instead of panicking, when dropped
+    /// on the wrong thread.
+    ///
+    /// Dropping a `SendCell` on a thread other than its origin normally panics. On shutdown
+    /// paths, where values end up dropped on whichever thread tears things down, leaking
+    /// them is usually the better outcome. A cell created this way leaks the value instead,
+    /// which the `tracing` feature reports as a `WARN` event. Every other access is checked
+    /// as usual, and dropping on the origin thread drops the value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    /// use std::thread;
+    ///
+    /// let data = Rc::new(42);
+    /// let cell = SendCell::new_leak_on_foreign_drop(Rc::clone(&data));
+    ///
+    /// // Dropped on another thread: the Rc is leaked, not dropped
+    /// thread::spawn(move || drop(cell)).join().unwrap();
+    /// assert_eq!(Rc::strong_count(&data), 2);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn new_leak_on_foreign_drop(t: T) -> SendCell<T> {
+        let mut cell = SendCell::new(t);
+        cell.on_foreign_drop = Some(Box::new(OnForeignDrop::Leak));
+        cell
+    }
+
+    /// Creates a new `SendCell` that hands its value to `handler`, instead of panicking,
+    /// when dropped on the wrong thread.
+    ///
+    /// The handler runs on the dropping thread and receives the value in a plain
+    /// `SendCell` bound to the origin thread, which it can send (for example through a
+    /// channel) back to the application's main loop for disposal. Every other access is
+    /// checked as usual, and dropping on the origin thread drops the value without calling
+    /// the handler.
+    ///
+    /// The handler only accepts this cell's type, so cells derived from this one with
+    /// [`Self::map`] or [`Self::downcast`], and futures created with
+    /// [`Self::into_future`], panic on a foreign drop instead.
+    ///
+    /// Handing the value over moves it out of the cell, so `T` must be [`Unpin`]: a cell
+    /// pinned with [`Self::as_pin_mut`] promises that its value never moves.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    /// use std::sync::mpsc;
+    /// use std::thread;
+    ///
+    /// let (disposal, disposals) = mpsc::channel();
+    /// let data = Rc::new(42);
+    /// let cell = SendCell::new_with_drop_handler(Rc::clone(&data), move |cell| {
+    ///     disposal.send(cell).unwrap();
+    /// });
+    ///
+    /// // Dropped on another thread: the value comes back through the channel
+    /// thread::spawn(move || drop(cell)).join().unwrap();
+    /// assert_eq!(Rc::strong_count(&data), 2);
+    ///
+    /// // The main loop drops it on the origin thread
+    /// drop(disposals.recv().unwrap());
+    /// assert_eq!(Rc::strong_count(&data), 1);
+    /// ```
+    ///
+    /// Values that may be pinned in place, such as most futures, are rejected:
+    ///
+    /// ```compile_fail
+    /// use send_cells::SendCell;
+    ///
+    /// let cell = SendCell::new_with_drop_handler(async {}, drop);
+    /// ```
+    #[track_caller]
+    pub fn new_with_drop_handler<F>(t: T, handler: F) -> SendCell<T>
+    where
+        T: Unpin + 'static,
+        F: FnOnce(SendCell<T>) + Send + 'static,
+    {
+        let mut cell = SendCell::new(t);
+        let thread_id = cell.thread_id;
+        let created_at = cell.created_at.clone();
+        cell.on_foreign_drop = Some(Box::new(OnForeignDrop::Handler(Box::new(
+            move |inner: NonNull<()>| {
+                // SAFETY: Drop passes a pointer to this cell's value and never uses it
+                // again, so it is read exactly once.
+                let inner = unsafe { inner.cast::<UnsafeSendCell<T>>().read() };
+                handler(SendCell {
+                    inner: ManuallyDrop::new(inner),
+                    thread_id,
+                    created_at,
+                    on_foreign_drop: None,
+                })
+            },
+        ))));
+        cell
+    }
+
+    /// Creates a new `SendCell` bound to a different thread than the current one.
+    ///
+    /// All subsequent checks enforce `thread_id` rather than the constructing thread. This
+    /// lets code running on a worker package a value destined for a known main or UI
+    /// thread. Note that the cell can then only be accessed, and (if `T` needs dropping)
+    /// dropped, on the target thread, so it must actually be delivered there.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to move the value from the current thread to
+    /// the thread identified by `thread_id`, which the value then belongs to. For types
+    /// that are not `Send` because they depend on thread-local state of their creating
+    /// thread, this is not the case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::cell::RefCell;
+    /// use std::thread;
+    ///
+    /// let main_thread = send_cells::sys::current_id();
+    ///
+    /// let cell = thread::spawn(move || {
+    ///     // SAFETY: A freshly created RefCell has no ties to this worker thread
+    ///     unsafe { SendCell::new_bound_to(RefCell::new(vec![1, 2, 3]), main_thread) }
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// assert_eq!(cell.get().borrow().len(), 3);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub unsafe fn new_bound_to(t: T, thread_id: ThreadId) -> SendCell<T> {
+        SendCell {
+            //safe because the caller vouches for moving the value to `thread_id`,
+            //and drop is verified against it
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(t) }),
+            thread_id,
+            created_at: Creation::here(),
+            on_foreign_drop: None,
         }
     }
 
+    /// Creates a new `SendCell` bound to the thread that owns the window `hwnd`.
+    ///
+    /// Window handles, and COM objects created for a window, may only be used on the
+    /// window's thread, which is not necessarily the thread wrapping them. This is
+    /// [`Self::new_bound_to`] with the thread looked up by
+    /// [`crate::sys::thread::window_thread_id`], so accesses are checked against the UI
+    /// thread instead.
+    ///
+    /// Returns `None` if `hwnd` is not a window of this process, or if its thread has not
+    /// called [`crate::sys::current_id`] yet (directly or by creating a cell).
+    ///
+    /// Only available on Windows with the `windows` feature.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`Self::new_bound_to`]: it must be safe to move the value from the
+    /// current thread to the window's thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    /// use windows_sys::Win32::Foundation::HWND;
+    ///
+    /// struct Renderer {
+    ///     hwnd: HWND,
+    /// }
+    ///
+    /// fn attach_renderer(hwnd: HWND) -> Option<SendCell<Renderer>> {
+    ///     // SAFETY: A Renderer only holds the handle, which belongs to the window's thread
+    ///     unsafe { SendCell::new_bound_to_window(Renderer { hwnd }, hwnd) }
+    /// }
+    ///
+    /// // A null handle is never a window
+    /// assert!(attach_renderer(std::ptr::null_mut()).is_none());
+    /// ```
+    #[cfg(all(windows, feature = "windows"))]
+    #[inline]
+    #[track_caller]
+    pub unsafe fn new_bound_to_window(
+        t: T,
+        hwnd: windows_sys::Win32::Foundation::HWND,
+    ) -> Option<SendCell<T>> {
+        let thread_id = crate::sys::thread::window_thread_id(hwnd)?;
+        // SAFETY: The caller vouches for moving the value to the window's thread
+        Some(unsafe { SendCell::new_bound_to(t, thread_id) })
+    }
+
+    /// Creates a new pinned, heap-allocated `SendCell` wrapping the given value.
+    ///
+    /// This is the common way to wrap self-referential or otherwise address-sensitive
+    /// non-Send values, such as FFI objects that must not move. The value is accessed
+    /// through [`Self::as_pin_ref`] and [`Self::as_pin_mut`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::marker::PhantomPinned;
+    /// use std::rc::Rc;
+    ///
+    /// struct Handle {
+    ///     _local: Rc<()>,
+    ///     _pinned: PhantomPinned,
+    /// }
+    ///
+    /// let cell = SendCell::pin(Handle { _local: Rc::new(()), _pinned: PhantomPinned });
+    ///
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(cell);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn pin(t: T) -> Pin<Box<SendCell<T>>> {
+        Box::pin(SendCell::new(t))
+    }
+}
+
+impl<T: ?Sized> SendCell<T> {
     /// Unsafely accesses the underlying value without thread checking.
     ///
     /// # Safety
@@ -167,6 +553,12 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     ///
     /// let cell = SendCell::new(42);
@@ -177,7 +569,7 @@ impl<T> SendCell<T> {
     /// ```
     #[inline]
     pub unsafe fn get_unchecked(&self) -> &T {
-        unsafe { self.inner.as_ref().expect("gone").get() }
+        unsafe { self.inner.get() }
     }
     /// Accesses the underlying value with runtime thread checking.
     ///
@@ -192,6 +584,12 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::collections::HashMap;
     ///
@@ -205,12 +603,9 @@ impl<T> SendCell<T> {
     /// ```
     #[inline]
     pub fn get(&self) -> &T {
-        assert_eq!(
-            self.thread_id,
-            crate::sys::thread::current().id(),
-            "Access SendCell<{}> from incorrect thread",
-            std::any::type_name::<T>()
-        );
+        #[cfg(feature = "tracing")]
+        crate::instrument::cell_access("get", core::any::type_name::<T>(), self.thread_id);
+        self.assert_thread("Access");
         //safe with assertion
         unsafe { self.get_unchecked() }
     }
@@ -230,6 +625,12 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     ///
     /// let mut cell = SendCell::new(42);
@@ -242,7 +643,7 @@ impl<T> SendCell<T> {
     /// ```
     #[inline]
     pub unsafe fn get_unchecked_mut(&mut self) -> &mut T {
-        unsafe { &mut *self.inner.as_mut().expect("gone").get_mut() }
+        unsafe { self.inner.get_mut() }
     }
 
     /// Accesses the underlying value mutably with runtime thread checking.
@@ -258,6 +659,12 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::collections::HashMap;
     ///
@@ -270,46 +677,106 @@ impl<T> SendCell<T> {
     /// ```
     #[inline]
     pub fn get_mut(&mut self) -> &mut T {
-        assert_eq!(
-            self.thread_id,
-            crate::sys::thread::current().id(),
-            "Access SendCell<{}> from incorrect thread",
-            std::any::type_name::<T>()
-        );
+        #[cfg(feature = "tracing")]
+        crate::instrument::cell_access("get_mut", core::any::type_name::<T>(), self.thread_id);
+        self.assert_thread("Access");
         unsafe { self.get_unchecked_mut() }
     }
 
-    /// Unsafely consumes the cell and returns the wrapped value without thread checking.
+    /// Panics unless called on the origin thread, naming both threads.
+    #[inline]
+    fn assert_thread(&self, operation: &str) {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "{operation} SendCell<{}> created at {} from incorrect thread (origin thread {}, \
+                 current thread {}){}",
+                core::any::type_name::<T>(),
+                self.created_at,
+                Described(self.thread_id),
+                Described(current),
+                self.created_at.backtrace()
+            ));
+        }
+    }
+
+    /// Projects a pinned shared reference to the cell onto the wrapped value, with runtime
+    /// thread checking.
     ///
-    /// # Safety
+    /// `SendCell` pins its contents structurally: the wrapped value is never moved out of
+    /// a cell that is pinned, so a pinned cell can hand out a pinned reference to it.
     ///
-    /// The caller must ensure that:
-    /// - It is safe to take ownership of the value on the current thread
-    /// - The value can be safely dropped on the current thread
-    /// - No other references to the value exist
+    /// # Panics
     ///
-    /// This method bypasses the runtime thread check and may lead to undefined
-    /// behavior if the wrapped type is not actually safe to move between threads.
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
     ///
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
+    /// use std::marker::PhantomPinned;
+    /// use std::pin::pin;
     ///
-    /// let cell = SendCell::new(42);
+    /// let cell = pin!(SendCell::new((42, PhantomPinned)));
+    /// let value = cell.as_ref().as_pin_ref();
+    /// assert_eq!(value.0, 42);
+    /// ```
+    #[inline]
+    pub fn as_pin_ref(self: Pin<&Self>) -> Pin<&T> {
+        // SAFETY: The wrapped value is pinned structurally; SendCell never moves it out
+        // while pinned, and get() performs the thread check.
+        unsafe { self.map_unchecked(|cell| cell.get()) }
+    }
+
+    /// Projects a pinned mutable reference to the cell onto the wrapped value, with
+    /// runtime thread checking.
     ///
-    /// // SAFETY: We're on the same thread, so this is safe
-    /// let value = unsafe { cell.into_unchecked_inner() };
-    /// assert_eq!(value, 42);
+    /// This lets a `!Unpin` value (such as a future or an FFI object that must not move)
+    /// be used in place inside a `SendCell`, without writing `Pin::new_unchecked` by hand.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::task::{Context, Poll, Waker};
+    ///
+    /// let mut cell = pin!(SendCell::new(async { 42 }));
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(cell.as_mut().as_pin_mut().poll(&mut cx), Poll::Ready(42));
     /// ```
     #[inline]
-    pub unsafe fn into_unchecked_inner(mut self) -> T {
-        unsafe { self.inner.take().expect("gone").into_inner() }
+    pub fn as_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: The wrapped value is pinned structurally; SendCell never moves it out
+        // while pinned (all moving methods need an unpinned `&mut self` or `self`), and
+        // get_mut() performs the thread check.
+        unsafe { self.map_unchecked_mut(|cell| cell.get_mut()) }
     }
-    /// Consumes the cell and returns the wrapped value with runtime thread checking.
+
+    /// Accesses the underlying value through a closure, with runtime thread checking.
     ///
-    /// This is the safe way to extract the wrapped value from the cell. The method
-    /// will verify that the current thread matches the thread where the cell was created.
+    /// This mirrors [`crate::SyncCell::with`], so code can be written the same way (or
+    /// generically) over either cell type. The thread is checked before the closure is
+    /// called.
     ///
     /// # Panics
     ///
@@ -319,107 +786,783 @@ impl<T> SendCell<T> {
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::rc::Rc;
     ///
-    /// let data = Rc::new("Hello, world!");
-    /// let cell = SendCell::new(data);
+    /// let cell = SendCell::new(Rc::new(vec![1, 2, 3]));
+    /// let sum: i32 = cell.with(|vec| vec.iter().sum());
+    /// assert_eq!(sum, 6);
+    /// ```
+    #[inline]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(self.get())
+    }
+
+    /// Accesses the underlying value mutably through a closure, with runtime thread checking.
     ///
-    /// // Extract the original value
-    /// let recovered_data = cell.into_inner();
-    /// assert_eq!(*recovered_data, "Hello, world!");
+    /// This mirrors [`crate::SyncCell::with_mut`]. Scoping the mutation to a closure also
+    /// gives the cell a single place to act before and after each access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut cell = SendCell::new(HashMap::new());
+    /// cell.with_mut(|map| map.insert("key", "value"));
+    /// assert_eq!(cell.with(|map| map.len()), 1);
     /// ```
     #[inline]
-    pub fn into_inner(self) -> T {
-        assert_eq!(self.thread_id, crate::sys::thread::current().id());
-        unsafe { self.into_unchecked_inner() }
+    pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(self.get_mut())
     }
+}
 
-    /// Creates a new cell with a different value, preserving the thread affinity.
+impl<T> SendCell<T> {
+    /// Replaces the wrapped value, returning the old one.
     ///
-    /// This creates a new `SendCell` that will be checked against the same thread
-    /// as the original cell. This is useful for implementing clone/copy operations
-    /// or transforming the wrapped value while maintaining thread safety.
+    /// The cell stays bound to its original thread.
     ///
-    /// # Safety
+    /// # Panics
     ///
-    /// The caller must ensure that:
-    /// - The new value is safe to use on the same thread as the original cell
-    /// - The new value can be safely dropped on the original thread
-    /// - Any invariants expected by the new value are maintained
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
     ///
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::rc::Rc;
     ///
-    /// let original = SendCell::new(Rc::new(42));
+    /// let mut cell = SendCell::new(Rc::new("old"));
+    /// let old = cell.replace(Rc::new("new"));
+    /// assert_eq!(*old, "old");
+    /// assert_eq!(**cell.get(), "new");
+    /// ```
+    #[inline]
+    pub fn replace(&mut self, value: T) -> T {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Sets the wrapped value, dropping the old one.
     ///
-    /// // Create a new cell with a String on the same thread
-    /// let derived = unsafe {
-    ///     original.preserving_cell_thread("Hello".to_string())
-    /// };
+    /// The cell stays bound to its original thread.
     ///
-    /// assert_eq!(original.get().as_ref(), &42);
-    /// assert_eq!(derived.get(), "Hello");
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    ///
+    /// let mut cell = SendCell::new(1);
+    /// cell.set(2);
+    /// assert_eq!(*cell.get(), 2);
     /// ```
     #[inline]
-    pub unsafe fn preserving_cell_thread<U>(&self, new: U) -> SendCell<U> {
-        unsafe {
-            SendCell {
-                inner: Some(UnsafeSendCell::new_unchecked(new)),
-                thread_id: self.thread_id,
-            }
-        }
+    pub fn set(&mut self, value: T) {
+        *self.get_mut() = value;
     }
 
-    /// Copies the wrapped value, creating a new cell on the same thread.
+    /// Takes the wrapped value, leaving `T::default()` in its place.
     ///
-    /// This method is safe for types that implement `Copy` because copying
-    /// such types doesn't involve custom code that could violate thread safety.
-    /// The new cell will have the same thread affinity as the original.
+    /// The cell stays alive and bound to its original thread. For `Option` payloads this
+    /// moves the value out and leaves `None`, like [`Option::take`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
     ///
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
+    /// use std::rc::Rc;
     ///
-    /// let original = SendCell::new(42i32);
-    /// let copied = original.copying();
-    ///
-    /// assert_eq!(*original.get(), *copied.get());
+    /// let mut cell = SendCell::new(Some(Rc::new(42)));
+    /// assert_eq!(cell.take().as_deref(), Some(&42));
+    /// assert_eq!(cell.take(), None);
     ///
-    /// // They are independent cells
-    /// std::mem::drop(original);
-    /// assert_eq!(*copied.get(), 42);
+    /// let mut cell = SendCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.take(), vec![1, 2, 3]);
+    /// assert!(cell.get().is_empty());
     /// ```
-    pub fn copying(&self) -> Self
+    #[inline]
+    pub fn take(&mut self) -> T
     where
-        T: Copy,
+        T: Default,
     {
-        unsafe { self.preserving_cell_thread(*self.get_unchecked()) }
+        core::mem::take(self.get_mut())
     }
-}
 
-impl<T: Future> SendCell<T> {
-    /// Converts the cell into a future that implements Send with runtime thread checking.
+    /// Swaps the wrapped values of two cells.
     ///
-    /// This method consumes the `SendCell` and returns a [`SendFuture`] that implements
-    /// `Send` and can be moved between threads. However, the future will panic if polled
-    /// from a different thread than the one where the original `SendCell` was created.
-    ///
-    /// Unlike [`crate::UnsafeSendCell::into_future()`], this provides memory safety
-    /// through runtime checks.
+    /// Both cells must be accessible from the current thread; each keeps its own thread
+    /// binding, which (since both were checked) is the current thread. This is useful for
+    /// double-buffering non-Send state.
     ///
     /// # Panics
     ///
-    /// The returned future will panic if polled from a different thread than the one
-    /// where this `SendCell` was created.
+    /// Panics if either cell was created on a different thread than the current one.
     ///
     /// # Examples
     ///
     /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let mut front = SendCell::new(Rc::new("front"));
+    /// let mut back = SendCell::new(Rc::new("back"));
+    /// front.swap(&mut back);
+    /// assert_eq!(**front.get(), "back");
+    /// assert_eq!(**back.get(), "front");
+    /// ```
+    #[inline]
+    pub fn swap(&mut self, other: &mut SendCell<T>) {
+        core::mem::swap(self.get_mut(), other.get_mut());
+    }
+
+    /// Unsafely consumes the cell and returns the wrapped value without thread checking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - It is safe to take ownership of the value on the current thread
+    /// - The value can be safely dropped on the current thread
+    /// - No other references to the value exist
+    ///
+    /// This method bypasses the runtime thread check and may lead to undefined
+    /// behavior if the wrapped type is not actually safe to move between threads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    ///
+    /// let cell = SendCell::new(42);
+    ///
+    /// // SAFETY: We're on the same thread, so this is safe
+    /// let value = unsafe { cell.into_unchecked_inner() };
+    /// assert_eq!(value, 42);
+    /// ```
+    #[inline]
+    pub unsafe fn into_unchecked_inner(self) -> T {
+        unsafe { self.into_parts().0.into_inner() }
+    }
+    /// Consumes the cell and returns the wrapped value with runtime thread checking.
+    ///
+    /// This is the safe way to extract the wrapped value from the cell. The method
+    /// will verify that the current thread matches the thread where the cell was created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let data = Rc::new("Hello, world!");
+    /// let cell = SendCell::new(data);
+    ///
+    /// // Extract the original value
+    /// let recovered_data = cell.into_inner();
+    /// assert_eq!(*recovered_data, "Hello, world!");
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        #[cfg(feature = "tracing")]
+        crate::instrument::cell_access("into_inner", core::any::type_name::<T>(), self.thread_id);
+        self.assert_thread("Access");
+        unsafe { self.into_unchecked_inner() }
+    }
+
+    /// Consumes the cell, returning the wrapped value and its thread affinity.
+    ///
+    /// Used by the wrapper types in other modules, which take over the thread checks.
+    pub(crate) fn into_parts(self) -> (UnsafeSendCell<T>, ThreadId) {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so each field is taken or dropped
+        // exactly once. The metadata is dropped, since it can own allocations.
+        unsafe {
+            core::ptr::drop_in_place(&mut this.created_at);
+            core::ptr::drop_in_place(&mut this.on_foreign_drop);
+            (ManuallyDrop::take(&mut this.inner), this.thread_id)
+        }
+    }
+
+    /// Creates a new cell with a different value, preserving the thread affinity.
+    ///
+    /// This creates a new `SendCell` that will be checked against the same thread
+    /// as the original cell. This is useful for implementing clone/copy operations
+    /// or transforming the wrapped value while maintaining thread safety.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - The new value is safe to use on the same thread as the original cell
+    /// - The new value can be safely dropped on the original thread
+    /// - Any invariants expected by the new value are maintained
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let original = SendCell::new(Rc::new(42));
+    ///
+    /// // Create a new cell with a String on the same thread
+    /// let derived = unsafe {
+    ///     original.preserving_cell_thread("Hello".to_string())
+    /// };
+    ///
+    /// assert_eq!(original.get().as_ref(), &42);
+    /// assert_eq!(derived.get(), "Hello");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub unsafe fn preserving_cell_thread<U>(&self, new: U) -> SendCell<U> {
+        unsafe {
+            SendCell {
+                inner: ManuallyDrop::new(UnsafeSendCell::new_unchecked(new)),
+                thread_id: self.thread_id,
+                created_at: Creation::here(),
+                on_foreign_drop: None,
+            }
+        }
+    }
+
+    /// Copies the wrapped value, creating a new cell on the same thread.
+    ///
+    /// This method is safe for types that implement `Copy` because copying
+    /// such types doesn't involve custom code that could violate thread safety.
+    /// The new cell will have the same thread affinity as the original.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    ///
+    /// let original = SendCell::new(42i32);
+    /// let copied = original.copying();
+    ///
+    /// assert_eq!(*original.get(), *copied.get());
+    ///
+    /// // They are independent cells
+    /// std::mem::drop(original);
+    /// assert_eq!(*copied.get(), 42);
+    /// ```
+    #[track_caller]
+    pub fn copying(&self) -> Self
+    where
+        T: Copy,
+    {
+        unsafe { self.preserving_cell_thread(*self.get_unchecked()) }
+    }
+
+    /// Clones the wrapped value, creating a new cell on the same thread.
+    ///
+    /// Unlike [`Self::copying`], cloning may run arbitrary code, so the thread is checked
+    /// before `T::clone` is called. The new cell has the same thread affinity as the
+    /// original. This is also what the `Clone` implementation does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let original = SendCell::new(Rc::new(42));
+    /// let cloned = original.clone_value();
+    /// assert!(Rc::ptr_eq(original.get(), cloned.get()));
+    /// ```
+    #[track_caller]
+    pub fn clone_value(&self) -> Self
+    where
+        T: Clone,
+    {
+        let value = self.get().clone();
+        // SAFETY: get() verified we are on the cell's thread, where the clone was made.
+        unsafe { self.preserving_cell_thread(value) }
+    }
+
+    /// Consumes the cell, returning an opaque pointer that can round-trip through FFI.
+    ///
+    /// The cell (including its recorded thread) is moved to the heap, and the pointer can
+    /// be passed through a C callback's `user_data` argument. Use [`Self::from_raw`] to
+    /// reconstruct the cell, with its original thread affinity, when the callback runs.
+    ///
+    /// The value is not accessed, so this method does not check the thread. If the
+    /// pointer is never passed to [`Self::from_raw`], the cell is leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let user_data = SendCell::new(Rc::new(42)).into_raw();
+    ///
+    /// // ...later, in the callback:
+    /// // SAFETY: user_data came from into_raw with the same type, and is used once
+    /// let cell = unsafe { SendCell::<Rc<i32>>::from_raw(user_data) };
+    /// assert_eq!(**cell.get(), 42);
+    /// ```
+    #[inline]
+    pub fn into_raw(self) -> *mut () {
+        Box::into_raw(Box::new(self)).cast()
+    }
+
+    /// Reconstructs a cell from a pointer returned by [`Self::into_raw`].
+    ///
+    /// The reconstructed cell keeps the thread affinity it had when it was converted, so
+    /// accessing it from the wrong thread still panics.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - `ptr` was returned by [`Self::into_raw`] on a `SendCell<T>` of this exact `T`
+    /// - `ptr` is passed to `from_raw` at most once
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    ///
+    /// let ptr = SendCell::new(String::from("user data")).into_raw();
+    /// // SAFETY: ptr came from into_raw on a SendCell<String>, and is used once
+    /// let cell = unsafe { SendCell::<String>::from_raw(ptr) };
+    /// assert_eq!(cell.get(), "user data");
+    /// ```
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut ()) -> SendCell<T> {
+        // SAFETY: The caller guarantees ptr came from into_raw for this T, so it is a
+        // uniquely owned Box<SendCell<T>>.
+        *unsafe { Box::from_raw(ptr.cast::<SendCell<T>>()) }
+    }
+
+    /// Consumes the cell and leaks the wrapped value, returning a `'static` reference to it.
+    ///
+    /// This is intended for values that legitimately live for the rest of the program on
+    /// the cell's thread. The value is never dropped, so the drop check does not apply.
+    /// The returned reference can't leave the current thread unless `T` is `Send`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::cell::RefCell;
+    ///
+    /// let cell = SendCell::new(RefCell::new(Vec::new()));
+    /// let registry: &'static mut RefCell<Vec<&str>> = cell.leak();
+    /// registry.borrow_mut().push("main");
+    /// ```
+    pub fn leak(self) -> &'static mut T
+    where
+        T: 'static,
+    {
+        Box::leak(Box::new(self.into_inner()))
+    }
+
+    /// Consumes the cell and transforms the wrapped value, preserving the thread affinity.
+    ///
+    /// This is the safe alternative to combining [`Self::into_inner`] with
+    /// [`Self::preserving_cell_thread`]: the thread is checked, the closure runs on the
+    /// cell's thread, and the result is bound to that same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let cell = SendCell::new(Rc::new(21));
+    /// let doubled = cell.map(|rc| Rc::new(*rc * 2));
+    /// assert_eq!(**doubled.get(), 42);
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SendCell<U> {
+        // SAFETY: () has no ties to any thread
+        let template = unsafe { self.rebind(()) };
+        let value = self.into_inner();
+        // SAFETY: into_inner verified that we are on the cell's thread, which is where
+        // the closure ran and where the new value is bound.
+        unsafe { template.rebind(f(value)) }
+    }
+}
+
+impl<T: ?Sized> SendCell<T> {
+    /// Wraps `value` in a cell that shares this cell's thread, creation site and drop
+    /// policy, for cells that are the same cell transformed.
+    ///
+    /// # Safety
+    ///
+    /// It must be safe to move `value` to this cell's thread.
+    unsafe fn rebind<U>(&self, value: U) -> SendCell<U> {
+        SendCell {
+            // SAFETY: The caller vouches for the value, and drop is verified
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(value) }),
+            thread_id: self.thread_id,
+            created_at: self.created_at.clone(),
+            on_foreign_drop: OnForeignDrop::derived(&self.on_foreign_drop),
+        }
+    }
+}
+
+impl<T> SendCell<Option<T>> {
+    /// Returns the wrapped value, inserting the result of `f` first if it is `None`.
+    ///
+    /// This performs a single thread check, for lazily populated thread-affine slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let mut slot: SendCell<Option<Rc<String>>> = SendCell::new(None);
+    /// let value = slot.get_or_insert_with(|| Rc::new("created".to_string()));
+    /// assert_eq!(value.as_str(), "created");
+    ///
+    /// // The closure is not called again once the slot is populated
+    /// let value = slot.get_or_insert_with(|| unreachable!());
+    /// assert_eq!(value.as_str(), "created");
+    /// ```
+    #[inline]
+    pub fn get_or_insert_with(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.get_mut().get_or_insert_with(f)
+    }
+}
+
+impl<T> SendCell<[T]> {
+    /// Moves the elements of a boxed slice into a boxed `SendCell<[T]>` bound to the
+    /// current thread.
+    ///
+    /// Unsized values cannot be passed to [`SendCell::new`], and a
+    /// `SendCell<Box<[T]>>` adds a second allocation and indirection. This constructor
+    /// stores the elements inline in the cell's own allocation instead.
+    ///
+    /// Trait objects need no special constructor: a `Box<SendCell<T>>` coerces to
+    /// `Box<SendCell<dyn Trait>>` like any other boxed value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let slice: Box<[Rc<i32>]> = vec![Rc::new(1), Rc::new(2)].into_boxed_slice();
+    /// let cell: Box<SendCell<[Rc<i32>]>> = SendCell::from_boxed_slice(slice);
+    /// assert_eq!(cell.get().len(), 2);
+    ///
+    /// // Trait objects through unsizing coercion
+    /// let cell: Box<SendCell<dyn std::fmt::Debug>> = Box::new(SendCell::new(Rc::new(3)));
+    /// assert_eq!(format!("{:?}", cell), "3");
+    /// ```
+    #[track_caller]
+    pub fn from_boxed_slice(slice: Box<[T]>) -> Box<SendCell<[T]>> {
+        let len = slice.len();
+        // Mirrors the repr(C) layout of SendCell<[T]>
+        let (layout, creation_offset) = Layout::new::<ThreadId>()
+            .extend(Layout::new::<Creation>())
+            .expect("slice too large");
+        let (layout, policy_offset) = layout
+            .extend(Layout::new::<Option<Box<OnForeignDrop>>>())
+            .expect("slice too large");
+        let (layout, offset) = layout
+            .extend(Layout::array::<T>(len).expect("slice too large"))
+            .expect("slice too large");
+        let layout = layout.pad_to_align();
+        // SAFETY: The layout is not zero-sized, since it contains a ThreadId.
+        let ptr = unsafe { alloc::alloc::alloc(layout) };
+        if ptr.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
+        }
+        // SAFETY: `ptr` is a fresh allocation with the layout of a SendCell<[T]> of `len`
+        // elements. The elements are moved out of `slice`, whose allocation is then freed
+        // without dropping them. The resulting pointer carries `len` as its metadata.
+        unsafe {
+            ptr.cast::<ThreadId>().write(crate::sys::current_id());
+            ptr.add(creation_offset)
+                .cast::<Creation>()
+                .write(Creation::here());
+            ptr.add(policy_offset)
+                .cast::<Option<Box<OnForeignDrop>>>()
+                .write(None);
+            let elements = Box::into_raw(slice) as *mut [ManuallyDrop<T>];
+            core::ptr::copy_nonoverlapping(elements as *const T, ptr.add(offset).cast::<T>(), len);
+            drop(Box::from_raw(elements));
+            Box::from_raw(
+                core::ptr::slice_from_raw_parts_mut(ptr.cast::<T>(), len) as *mut SendCell<[T]>
+            )
+        }
+    }
+}
+
+impl SendCell<Box<dyn Any>> {
+    /// Returns a reference to the wrapped value if it is of type `U`.
+    ///
+    /// This combines the thread check with `<dyn Any>::downcast_ref`, for heterogeneous
+    /// storage of thread-affine values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::any::Any;
+    /// use std::rc::Rc;
+    ///
+    /// let cell: SendCell<Box<dyn Any>> = SendCell::new(Box::new(Rc::new(42)));
+    /// assert_eq!(cell.downcast_ref::<Rc<i32>>().map(|rc| **rc), Some(42));
+    /// assert!(cell.downcast_ref::<String>().is_none());
+    /// ```
+    #[inline]
+    pub fn downcast_ref<U: Any>(&self) -> Option<&U> {
+        self.get().downcast_ref()
+    }
+
+    /// Returns a mutable reference to the wrapped value if it is of type `U`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::any::Any;
+    ///
+    /// let mut cell: SendCell<Box<dyn Any>> = SendCell::new(Box::new(vec![1]));
+    /// cell.downcast_mut::<Vec<i32>>().unwrap().push(2);
+    /// assert_eq!(cell.downcast_ref::<Vec<i32>>(), Some(&vec![1, 2]));
+    /// ```
+    #[inline]
+    pub fn downcast_mut<U: Any>(&mut self) -> Option<&mut U> {
+        self.get_mut().downcast_mut()
+    }
+
+    /// Attempts to downcast the wrapped value to type `U`, preserving the thread affinity.
+    ///
+    /// Returns the original cell if the value is not of type `U`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendCell`
+    /// was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::any::Any;
+    /// use std::rc::Rc;
+    ///
+    /// let cell: SendCell<Box<dyn Any>> = SendCell::new(Box::new(Rc::new("hello")));
+    /// let cell = cell.downcast::<u32>().unwrap_err();
+    /// let typed = cell.downcast::<Rc<&str>>().unwrap();
+    /// assert_eq!(**typed.get().as_ref(), "hello");
+    /// ```
+    pub fn downcast<U: Any>(self) -> Result<SendCell<Box<U>>, Self> {
+        // SAFETY: () has no ties to any thread
+        let template = unsafe { self.rebind(()) };
+        // SAFETY: into_inner verifies we are on the cell's thread, so the new cells created
+        // here have the same thread affinity.
+        match self.into_inner().downcast::<U>() {
+            Ok(value) => Ok(unsafe { template.rebind(value) }),
+            Err(value) => Err(unsafe { template.rebind(value) }),
+        }
+    }
+}
+
+impl<T: Future> SendCell<T> {
+    /// Converts the cell into a future that implements Send with runtime thread checking.
+    ///
+    /// This method consumes the `SendCell` and returns a [`SendFuture`] that implements
+    /// `Send` and can be moved between threads. However, the future will panic if polled
+    /// from a different thread than the one where the original `SendCell` was created.
+    ///
+    /// Unlike [`crate::UnsafeSendCell::into_future()`], this provides memory safety
+    /// through runtime checks.
+    ///
+    /// `SendCell` also implements [`IntoFuture`] this way, so a cell holding a future can
+    /// be awaited directly with `cell.await`.
+    ///
+    /// # Panics
+    ///
+    /// The returned future will panic if polled from a different thread than the one
+    /// where this `SendCell` was created. The panic message includes the location of the
+    /// `into_future` call, since by then the backtrace usually only shows executor
+    /// internals.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
     /// use send_cells::SendCell;
     /// use std::rc::Rc;
     ///
@@ -429,157 +1572,1332 @@ impl<T: Future> SendCell<T> {
     ///     42
     /// }
     ///
-    /// let future = non_send_async();
-    /// let cell = SendCell::new(future);
-    /// let send_future = cell.into_future();
+    /// let future = non_send_async();
+    /// let cell = SendCell::new(future);
+    /// let send_future = cell.into_future();
+    ///
+    /// // The future now implements Send
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(send_future);
+    /// ```
+    #[track_caller]
+    pub fn into_future(self) -> SendFuture<T> {
+        let leak_on_foreign_drop =
+            matches!(self.on_foreign_drop.as_deref(), Some(OnForeignDrop::Leak));
+        let (inner, thread_id) = self.into_parts();
+        SendFuture {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+            created_at: Creation::here(),
+            leak_on_foreign_drop,
+        }
+    }
+
+    /// Converts the cell into a [`SendFuture`] over the future pinned in a `Box`.
+    ///
+    /// [`Self::into_future`] pins the future inside the `SendFuture`, so it can't be moved
+    /// out again once polled unless it is [`Unpin`]. Boxing it first keeps the pinned
+    /// future at a fixed heap address instead: the returned `SendFuture` is itself
+    /// [`Unpin`], and [`SendFuture::into_inner`] recovers the `Pin<Box<T>>` at any time.
+    ///
+    /// # Panics
+    ///
+    /// The returned future will panic if polled from a different thread than the one
+    /// where this `SendCell` was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Waker};
+    ///
+    /// let local = Rc::new(42);
+    /// let mut send_future = SendCell::new(async move {
+    ///     std::future::pending::<()>().await;
+    ///     *local
+    /// })
+    /// .into_future_pinned();
+    ///
+    /// let mut context = Context::from_waker(Waker::noop());
+    /// assert!(Pin::new(&mut send_future).poll(&mut context).is_pending());
+    ///
+    /// // Recovering a polled future is fine, since it stays pinned in its box
+    /// let future = send_future.into_inner();
+    /// # drop(future);
+    /// ```
+    #[track_caller]
+    pub fn into_future_pinned(self) -> SendFuture<Pin<Box<T>>> {
+        let leak_on_foreign_drop =
+            matches!(self.on_foreign_drop.as_deref(), Some(OnForeignDrop::Leak));
+        let (inner, thread_id) = self.into_parts();
+        // SAFETY: The future is only moved into a box, which stays bound to the same thread;
+        // it is not accessed here.
+        let pinned = unsafe { UnsafeSendCell::new_unchecked(Box::pin(inner.into_inner())) };
+        SendFuture {
+            inner: ManuallyDrop::new(pinned),
+            thread_id,
+            created_at: Creation::here(),
+            leak_on_foreign_drop,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> SendCell<T> {
+    /// Converts the cell into a sink that implements Send with runtime thread checking.
+    ///
+    /// This method consumes the `SendCell` and returns a [`SendSink`] that implements
+    /// `Send` and can be handed to middleware requiring `Send` sinks. However, the sink
+    /// will panic if driven from a different thread than the one where the original
+    /// `SendCell` was created.
+    ///
+    /// This method is only available with the `futures` feature.
+    ///
+    /// # Panics
+    ///
+    /// The returned sink will panic if any of its `Sink` methods are called from a
+    /// different thread than the one where this `SendCell` was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    /// use std::pin::Pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll};
+    ///
+    /// // A sink that is not Send
+    /// struct LocalSink(Rc<std::cell::RefCell<Vec<i32>>>);
+    ///
+    /// impl futures_sink::Sink<i32> for LocalSink {
+    ///     type Error = ();
+    ///     fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+    ///         Poll::Ready(Ok(()))
+    ///     }
+    ///     fn start_send(self: Pin<&mut Self>, item: i32) -> Result<(), ()> {
+    ///         self.0.borrow_mut().push(item);
+    ///         Ok(())
+    ///     }
+    ///     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+    ///         Poll::Ready(Ok(()))
+    ///     }
+    ///     fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+    ///         Poll::Ready(Ok(()))
+    ///     }
+    /// }
+    ///
+    /// let cell = SendCell::new(LocalSink(Default::default()));
+    /// let send_sink = cell.into_sink();
+    ///
+    /// // The sink now implements Send
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(send_sink);
+    /// ```
+    pub fn into_sink(self) -> SendSink<T> {
+        let (inner, thread_id) = self.into_parts();
+        SendSink {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+        }
+    }
+
+    /// Converts the cell into a stream that implements Send with runtime thread checking.
+    ///
+    /// This is the stream counterpart of [`Self::into_future`]: it consumes the `SendCell`
+    /// and returns a [`SendStream`] that implements `Send` and can be handed to code
+    /// requiring `Send` streams. However, the stream will panic if polled from a different
+    /// thread than the one where the original `SendCell` was created.
+    ///
+    /// This method is only available with the `futures` feature.
+    ///
+    /// # Panics
+    ///
+    /// The returned stream will panic if polled from a different thread than the one where
+    /// this `SendCell` was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    /// use std::pin::Pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll};
+    ///
+    /// // A stream that is not Send
+    /// struct LocalStream(Rc<i32>);
+    ///
+    /// impl futures_core::Stream for LocalStream {
+    ///     type Item = i32;
+    ///     fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<i32>> {
+    ///         Poll::Ready(Some(*self.0))
+    ///     }
+    /// }
+    ///
+    /// let cell = SendCell::new(LocalStream(Rc::new(42)));
+    /// let send_stream = cell.into_stream();
+    ///
+    /// // The stream now implements Send
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(send_stream);
+    /// ```
+    pub fn into_stream(self) -> SendStream<T>
+    where
+        T: futures_core::Stream,
+    {
+        let (inner, thread_id) = self.into_parts();
+        SendStream {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for SendCell<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        crate::instrument::cell_access("drop", core::any::type_name::<T>(), self.thread_id);
+        if core::mem::needs_drop::<T>() {
+            // The value is never dropped here, since that would be unsound
+            if crate::instrument::checked(self.thread_id != crate::sys::current_id()) {
+                match self.on_foreign_drop.take().map(|policy| *policy) {
+                    Some(OnForeignDrop::Handler(handler)) => {
+                        // The handler takes ownership of the value
+                        return handler(NonNull::from(&mut *self.inner).cast());
+                    }
+                    Some(OnForeignDrop::Leak) => {}
+                    // Panicking while already unwinding would abort the process
+                    #[cfg(feature = "std")]
+                    None if std::thread::panicking() => {}
+                    None => self.assert_thread("Drop"),
+                }
+                #[cfg(feature = "tracing")]
+                crate::instrument::leaked(
+                    "SendCell",
+                    core::any::type_name::<T>(),
+                    Some(self.created_at.location),
+                    self.thread_id,
+                );
+                return;
+            }
+        }
+        // SAFETY: We are on the origin thread (or T has no drop glue), and `inner` is
+        // never used again.
+        unsafe { ManuallyDrop::drop(&mut self.inner) }
+    }
+}
+
+// Trait implementations that delegate to the wrapped value
+// All of these perform runtime thread checking through get() and get_mut()
+impl<T: Debug + ?Sized> Debug for SendCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: core::fmt::Display + ?Sized> core::fmt::Display for SendCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+// Serialization reads the value through get(); deserialization binds the new cell
+// to the deserializing thread, like SendCell::new
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for SendCell<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SendCell<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(SendCell::new)
+    }
+}
+
+// Conversions to and from `fragile::Fragile`. Both types are bound to the thread that
+// created them, and both unwrap only on that thread, so re-wrapping the value on the
+// current thread preserves its affinity.
+#[cfg(feature = "fragile")]
+impl<T> From<fragile::Fragile<T>> for SendCell<T> {
+    /// Converts a `Fragile` into a `SendCell` bound to the same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where the `Fragile` was
+    /// created.
+    #[track_caller]
+    fn from(fragile: fragile::Fragile<T>) -> Self {
+        SendCell::new(fragile.into_inner())
+    }
+}
+
+#[cfg(feature = "fragile")]
+impl<T> From<SendCell<T>> for fragile::Fragile<T> {
+    /// Converts a `SendCell` into a `Fragile` bound to the same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where the `SendCell` was
+    /// created.
+    fn from(cell: SendCell<T>) -> Self {
+        fragile::Fragile::new(cell.into_inner())
+    }
+}
+
+// Conversions to and from `send_wrapper::SendWrapper`, which has the same thread
+// affinity model as `SendCell`.
+#[cfg(feature = "send_wrapper")]
+impl<T> From<send_wrapper::SendWrapper<T>> for SendCell<T> {
+    /// Converts a `SendWrapper` into a `SendCell` bound to the same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where the `SendWrapper` was
+    /// created.
+    #[track_caller]
+    fn from(wrapper: send_wrapper::SendWrapper<T>) -> Self {
+        SendCell::new(wrapper.take())
+    }
+}
+
+#[cfg(feature = "send_wrapper")]
+impl<T> From<SendCell<T>> for send_wrapper::SendWrapper<T> {
+    /// Converts a `SendCell` into a `SendWrapper` bound to the same thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where the `SendCell` was
+    /// created.
+    fn from(cell: SendCell<T>) -> Self {
+        send_wrapper::SendWrapper::new(cell.into_inner())
+    }
+}
+
+// Errors wrapped in a cell can still be reported on the origin thread
+impl<E: core::error::Error + ?Sized> core::error::Error for SendCell<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.get().source()
+    }
+
+    #[allow(deprecated)]
+    fn description(&self) -> &str {
+        self.get().description()
+    }
+}
+
+impl<T: Clone> Clone for SendCell<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.clone_value()
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for SendCell<T> {
+    fn as_ref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: ?Sized> AsMut<T> for SendCell<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+// Borrow is consistent with the delegated Eq, Ord and Hash implementations
+impl<T: ?Sized> Borrow<T> for SendCell<T> {
+    fn borrow(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: ?Sized> BorrowMut<T> for SendCell<T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T: ?Sized> Deref for SendCell<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: ?Sized> DerefMut for SendCell<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.get_mut()
+    }
+}
+
+impl<T: Index<I> + ?Sized, I> Index<I> for SendCell<T> {
+    type Output = T::Output;
+    fn index(&self, index: I) -> &Self::Output {
+        &self.get()[index]
+    }
+}
+
+impl<T: IndexMut<I> + ?Sized, I> IndexMut<I> for SendCell<T> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.get_mut()[index]
+    }
+}
+
+// Allows `cell.await`, equivalent to `cell.into_future().await`
+impl<T: Future> IntoFuture for SendCell<T> {
+    type Output = T::Output;
+    type IntoFuture = SendFuture<T>;
+
+    #[track_caller]
+    fn into_future(self) -> Self::IntoFuture {
+        SendCell::into_future(self)
+    }
+}
+
+// Blocking I/O traits - every call is checked through get_mut()
+#[cfg(feature = "std")]
+impl<T: std::io::Read + ?Sized> std::io::Read for SendCell<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.get_mut().read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        self.get_mut().read_vectored(bufs)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: std::io::BufRead + ?Sized> std::io::BufRead for SendCell<T> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.get_mut().fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.get_mut().consume(amt)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: std::io::Write + ?Sized> std::io::Write for SendCell<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.get_mut().write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.get_mut().write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.get_mut().flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: std::io::Seek + ?Sized> std::io::Seek for SendCell<T> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.get_mut().seek(pos)
+    }
+}
+
+// Iterating by value keeps the thread affinity in the resulting SendIter
+impl<T: IntoIterator> IntoIterator for SendCell<T> {
+    type Item = T::Item;
+    type IntoIter = SendIter<T::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let thread_id = self.thread_id;
+        let iter = self.into_inner().into_iter();
+        SendIter {
+            // SAFETY: into_inner verified we are on the cell's thread, and the iterator
+            // stays bound to that thread.
+            inner: ManuallyDrop::new(unsafe { UnsafeSendCell::new_unchecked(iter) }),
+            thread_id,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SendCell<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.get().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SendCell<T>
+where
+    &'a mut T: IntoIterator,
+{
+    type Item = <&'a mut T as IntoIterator>::Item;
+    type IntoIter = <&'a mut T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.get_mut().into_iter()
+    }
+}
+
+// Comparison traits - all use thread-checked access through get()
+impl<T: PartialEq + ?Sized> PartialEq for SendCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Eq + ?Sized> Eq for SendCell<T> {}
+
+impl<T: PartialOrd + ?Sized> PartialOrd for SendCell<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.get().partial_cmp(other.get())
+    }
+}
+
+impl<T: Ord + ?Sized> Ord for SendCell<T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.get().cmp(other.get())
+    }
+}
+
+impl<T: Hash + ?Sized> Hash for SendCell<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get().hash(state)
+    }
+}
+
+// Additional trait implementations
+impl<T: Default> Default for SendCell<T> {
+    #[track_caller]
+    fn default() -> SendCell<T> {
+        SendCell::new(Default::default())
+    }
+}
+impl<T> From<T> for SendCell<T> {
+    #[track_caller]
+    fn from(value: T) -> Self {
+        SendCell::new(value)
+    }
+}
+
+/// A future wrapper that implements Send with runtime thread checking.
+///
+/// `SendFuture<T>` wraps a future of type `T` and provides a `Send` implementation
+/// with runtime thread checking. The future remembers the thread it was created on
+/// and panics if polled from any other thread.
+///
+/// This wrapper allows non-Send futures to be used in contexts that require Send futures
+/// (such as being spawned on thread pools), while maintaining memory safety through
+/// runtime checks. Unlike [`crate::UnsafeSendFuture`], this provides safe cross-thread
+/// usage by panicking if accessed from the wrong thread.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
+/// use send_cells::SendCell;
+/// use std::rc::Rc;
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::task::{Context, Poll};
+///
+/// // A future that is not Send
+/// struct NonSendFuture {
+///     data: Rc<i32>,
+/// }
+///
+/// impl Future for NonSendFuture {
+///     type Output = i32;
+///     
+///     fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+///         Poll::Ready(*self.data)
+///     }
+/// }
+///
+/// let future = NonSendFuture { data: Rc::new(42) };
+/// let cell = SendCell::new(future);
+/// let send_future = cell.into_future();
+///
+/// // Now it can be used in Send contexts
+/// fn requires_send_future<F: Future + Send>(_: F) {}
+/// requires_send_future(send_future);
+/// ```
+///
+/// # Panics
+///
+/// The `poll` method will panic if called from a different thread than the one
+/// where the original `SendCell` was created. Like [`SendCell`], dropping the future on
+/// the wrong thread will also panic, and the wrapped future is leaked rather than dropped
+/// there, except during unwinding, where it is only leaked. Use
+/// [`SendFuture::leak_on_foreign_drop`] to leak it without panicking.
+#[derive(Debug)]
+pub struct SendFuture<T> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+    // Where the future was created, for wrong-thread panic messages
+    created_at: Creation,
+    leak_on_foreign_drop: bool,
+}
+
+// SAFETY: SendFuture implements Send by providing runtime thread checking.
+// The wrapped future may not be Send, but we ensure safety by panicking
+// if poll() is called from the wrong thread.
+unsafe impl<T> Send for SendFuture<T> {}
+
+impl<T> SendFuture<T> {
+    /// Unsafely consumes the wrapper and returns the wrapped future without thread checking.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - It is safe to take ownership of the future on the current thread
+    /// - The future can be safely dropped on the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    ///
+    /// let send_future = SendCell::new(async { 42 }).into_future();
+    ///
+    /// // SAFETY: We're on the same thread, so this is safe
+    /// let future = unsafe { send_future.into_unchecked_inner() };
+    /// ```
+    #[inline]
+    pub unsafe fn into_unchecked_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so the future is taken exactly once
+        // and the creation site, which can own a backtrace, dropped exactly once.
+        unsafe {
+            core::ptr::drop_in_place(&mut this.created_at);
+            ManuallyDrop::take(&mut this.inner).into_inner()
+        }
+    }
+
+    /// Makes dropping the future on the wrong thread leak it instead of panicking.
+    ///
+    /// By default, dropping a `SendFuture` on a thread other than its origin panics, like
+    /// [`SendCell`]. That is the right call for catching bugs, but on shutdown paths, where
+    /// an executor drops unfinished tasks on whichever thread it likes, leaking the future
+    /// is usually preferable. Dropping on the origin thread is unaffected.
     ///
-    /// // The future now implements Send
-    /// fn assert_send<T: Send>(_: T) {}
-    /// assert_send(send_future);
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    /// use std::thread;
+    ///
+    /// let local = Rc::new(42);
+    /// let send_future = SendCell::new(async move { *local })
+    ///     .into_future()
+    ///     .leak_on_foreign_drop();
+    ///
+    /// // Dropped on another thread: the future (and its Rc) is leaked, not dropped
+    /// thread::spawn(move || drop(send_future)).join().unwrap();
     /// ```
-    pub fn into_future(mut self) -> SendFuture<T> {
-        SendFuture {
-            inner: self.inner.take().expect("inner value missing"),
-            thread_id: self.thread_id,
+    #[inline]
+    pub fn leak_on_foreign_drop(mut self) -> Self {
+        self.leak_on_foreign_drop = true;
+        self
+    }
+
+    /// Converts the future into one that reports a wrong-thread poll as an error instead
+    /// of panicking.
+    ///
+    /// The returned [`TrySendFuture`] resolves to `Ok(output)` when polled to completion on
+    /// the origin thread, and to `Err(WrongThreadError)` as soon as it is polled from any
+    /// other thread, so a scheduling mistake becomes an ordinary task failure rather than a
+    /// panic unwinding through the executor. For the same reason, dropping it on the wrong
+    /// thread leaks the wrapped future, as with [`Self::leak_on_foreign_drop`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll, Waker};
+    /// use std::thread;
+    ///
+    /// let local = Rc::new(42);
+    /// let future = SendCell::new(async move { *local }).into_future().or_error();
+    ///
+    /// // Polled by the wrong executor thread: an error, not a panic
+    /// let result = thread::spawn(move || {
+    ///     let mut context = Context::from_waker(Waker::noop());
+    ///     match pin!(future).poll(&mut context) {
+    ///         Poll::Ready(result) => result.map_err(|error| error.to_string()),
+    ///         Poll::Pending => unreachable!(),
+    ///     }
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// assert!(result.is_err());
+    /// ```
+    #[inline]
+    pub fn or_error(self) -> TrySendFuture<T> {
+        TrySendFuture {
+            inner: self.leak_on_foreign_drop(),
         }
     }
+
+    /// Consumes the wrapper and returns the wrapped future with runtime thread checking.
+    ///
+    /// This recovers the original future, for example to hand it back to a local
+    /// executor after it could not be spawned on a remote one. A future that has already
+    /// been polled can only be recovered if it is [`Unpin`], since polling pins it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where the original
+    /// `SendCell` was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(42);
+    /// let send_future = SendCell::new(async move { *local }).into_future();
+    ///
+    /// // Spawning elsewhere failed; take the !Send future back
+    /// let future = send_future.into_inner();
+    /// # drop(future);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.assert_thread(crate::sys::current_id(), "unwrapped");
+        unsafe { self.into_unchecked_inner() }
+    }
+
+    /// Consumes the wrapper, returning the wrapped future and its thread affinity.
+    ///
+    /// Used by the wrapper types in other modules, which take over the thread checks.
+    #[cfg(feature = "std")]
+    pub(crate) fn into_parts(self) -> (UnsafeSendCell<T>, ThreadId) {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so the future is taken exactly once
+        // and the creation site, which can own a backtrace, dropped exactly once.
+        unsafe {
+            core::ptr::drop_in_place(&mut this.created_at);
+            (ManuallyDrop::take(&mut this.inner), this.thread_id)
+        }
+    }
+
+    /// Panics unless `current` is the origin thread, naming where the future was created.
+    #[inline]
+    fn assert_thread(&self, current: ThreadId, action: &str) {
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendFuture<{}> created at {} {action} from incorrect thread (origin thread {}, \
+                 current thread {}){}",
+                core::any::type_name::<T>(),
+                self.created_at,
+                Described(self.thread_id),
+                Described(current),
+                self.created_at.backtrace()
+            ));
+        }
+    }
+}
+
+impl<F: Future + ?Sized> SendFuture<Pin<Box<F>>> {
+    /// Wraps a future that is already pinned in a `Box`, bound to the current thread.
+    ///
+    /// This is the natural constructor for futures that arrive pinned, such as a
+    /// `Pin<Box<dyn Future>>` handed over by FFI or a boxed self-referential generator.
+    /// The future is never moved; the `SendFuture` only polls it through the `Box`.
+    ///
+    /// # Panics
+    ///
+    /// The returned future will panic if polled from a different thread than the current
+    /// one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(not(feature = "std"))] {
+    /// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    /// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    /// #     // SAFETY: Every thread takes a new number
+    /// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+    /// # }
+    /// use send_cells::SendFuture;
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(42);
+    /// let boxed: Pin<Box<dyn Future<Output = i32>>> = Box::pin(async move { *local });
+    /// let send_future = SendFuture::from_pin(boxed);
+    ///
+    /// fn requires_send<T: Send>(_: T) {}
+    /// requires_send(send_future);
+    /// ```
+    #[track_caller]
+    pub fn from_pin(future: Pin<Box<F>>) -> SendFuture<Pin<Box<F>>> {
+        SendCell::new(future).into_future()
+    }
+}
+
+impl<T: Future> Future for SendFuture<T> {
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let current = crate::sys::current_id();
+        // Entered before the check, so a wrong-thread panic shows up inside the span
+        #[cfg(feature = "tracing")]
+        let _span = crate::instrument::PollSpan::enter(
+            core::any::type_name::<T>(),
+            self.thread_id,
+            current,
+        );
+        // Runtime thread check - panic if called from wrong thread
+        self.assert_thread(current, "polled");
+
+        // SAFETY: After the thread check, we can safely access the inner future. It is
+        // projected like UnsafeSendFuture does, with a single reborrow of the pinned
+        // reference, so no pointer outlives the borrow it was derived from. We never move
+        // the future out of its pinned location.
+        let inner = unsafe { self.map_unchecked_mut(|future| future.inner.get_mut()) };
+        inner.poll(cx)
+    }
 }
 
-impl<T> Drop for SendCell<T> {
+impl<T> Drop for SendFuture<T> {
     fn drop(&mut self) {
-        if std::mem::needs_drop::<T>() {
-            assert_eq!(
-                self.thread_id,
-                crate::sys::thread::current().id(),
-                "Drop SendCell<{}> from incorrect thread",
-                std::any::type_name::<T>()
+        if core::mem::needs_drop::<T>()
+            && crate::instrument::checked(self.thread_id != crate::sys::current_id())
+        {
+            // The future is leaked in every case, since dropping it here would be unsound.
+            // Panicking while already unwinding would abort the process.
+            #[cfg(feature = "std")]
+            let leak = self.leak_on_foreign_drop || std::thread::panicking();
+            #[cfg(not(feature = "std"))]
+            let leak = self.leak_on_foreign_drop;
+            if leak {
+                #[cfg(feature = "tracing")]
+                crate::instrument::leaked(
+                    "SendFuture",
+                    core::any::type_name::<T>(),
+                    Some(self.created_at.location),
+                    self.thread_id,
+                );
+                return;
+            }
+            crate::sys::violation(format_args!(
+                "Drop SendFuture<{}> created at {} from incorrect thread (origin thread {}, \
+                 current thread {}){}",
+                core::any::type_name::<T>(),
+                self.created_at,
+                Described(self.thread_id),
+                Described(crate::sys::current_id()),
+                self.created_at.backtrace()
+            ));
+        }
+        // SAFETY: We are on the origin thread (or T has no drop glue), and `inner` is
+        // never used again.
+        unsafe { ManuallyDrop::drop(&mut self.inner) }
+    }
+}
+
+/// Drops the value of a thread-checked wrapper like [`SendSink`], named `wrapper`, if this
+/// is the thread the value belongs to.
+///
+/// On any other thread the value is leaked, since dropping it there would be unsound, and
+/// the violation is reported, except while the thread is already unwinding, where a second
+/// panic would abort the process.
+///
+/// # Safety
+///
+/// `inner` must not be used again.
+pub(crate) unsafe fn drop_on_origin<T>(
+    inner: &mut ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+    wrapper: &'static str,
+) {
+    if core::mem::needs_drop::<T>()
+        && crate::instrument::checked(thread_id != crate::sys::current_id())
+    {
+        #[cfg(feature = "std")]
+        let unwinding = std::thread::panicking();
+        #[cfg(not(feature = "std"))]
+        let unwinding = false;
+        if unwinding {
+            #[cfg(feature = "tracing")]
+            crate::instrument::leaked(wrapper, core::any::type_name::<T>(), None, thread_id);
+            return;
+        }
+        crate::sys::violation(format_args!(
+            "Drop {wrapper}<{}> from incorrect thread (origin thread {}, current thread {})",
+            core::any::type_name::<T>(),
+            Described(thread_id),
+            Described(crate::sys::current_id())
+        ));
+    }
+    // SAFETY: We are on the origin thread (or T has no drop glue), and the caller never
+    // uses `inner` again.
+    unsafe { ManuallyDrop::drop(inner) }
+}
+
+// Lets a SendFuture be used in `select!` loops without `.fuse()`. The check reads the
+// wrapped future, so it is thread-checked like `poll`.
+#[cfg(feature = "futures")]
+impl<T: futures_core::future::FusedFuture> futures_core::future::FusedFuture for SendFuture<T> {
+    fn is_terminated(&self) -> bool {
+        self.assert_thread(crate::sys::current_id(), "accessed");
+        // SAFETY: We are on the origin thread.
+        unsafe { self.inner.get() }.is_terminated()
+    }
+}
+
+/// The error returned when a [`TrySendFuture`] is polled from a thread other than the one
+/// that created it.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
+/// use send_cells::{SendCell, WrongThreadError};
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+/// use std::thread;
+///
+/// let future = SendCell::new(async { 42 }).into_future().or_error();
+/// let origin = send_cells::sys::current_id();
+///
+/// let error: WrongThreadError = thread::spawn(move || {
+///     let mut context = Context::from_waker(Waker::noop());
+///     match pin!(future).poll(&mut context) {
+///         Poll::Ready(Err(error)) => error,
+///         _ => unreachable!(),
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(error.origin_thread(), origin);
+/// assert_ne!(error.current_thread(), origin);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongThreadError {
+    origin: ThreadId,
+    current: ThreadId,
+}
+
+impl WrongThreadError {
+    /// Returns the thread the value is bound to.
+    #[inline]
+    pub fn origin_thread(&self) -> ThreadId {
+        self.origin
+    }
+
+    /// Returns the thread that tried to access the value.
+    #[inline]
+    pub fn current_thread(&self) -> ThreadId {
+        self.current
+    }
+}
+
+impl core::fmt::Display for WrongThreadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "accessed from incorrect thread (origin thread {}, current thread {})",
+            Described(self.origin),
+            Described(self.current)
+        )
+    }
+}
+
+impl core::error::Error for WrongThreadError {}
+
+/// A [`SendFuture`] whose output reports wrong-thread polls as errors.
+///
+/// Created with [`SendFuture::or_error`]. It resolves to `Ok` with the wrapped future's
+/// output on the origin thread, and to `Err(WrongThreadError)` if polled from any other
+/// thread, without polling the wrapped future there. Dropping it on the wrong thread leaks
+/// the wrapped future instead of panicking.
+#[derive(Debug)]
+pub struct TrySendFuture<T> {
+    inner: SendFuture<T>,
+}
+
+impl<T> TrySendFuture<T> {
+    /// Consumes the wrapper and returns the underlying [`SendFuture`].
+    ///
+    /// The `SendFuture` still leaks the wrapped future when dropped on the wrong thread.
+    #[inline]
+    pub fn into_send_future(self) -> SendFuture<T> {
+        self.inner
+    }
+}
+
+impl<T: Future> Future for TrySendFuture<T> {
+    type Output = Result<T::Output, WrongThreadError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.inner.thread_id != current) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                future = core::any::type_name::<T>(),
+                origin = ?self.inner.thread_id,
+                thread = ?current,
+                "SendFuture polled from incorrect thread"
             );
+            return Poll::Ready(Err(WrongThreadError {
+                origin: self.inner.thread_id,
+                current,
+            }));
         }
+        // SAFETY: `inner` is structurally pinned; it is never moved out of a pinned
+        // TrySendFuture.
+        let inner = unsafe { self.map_unchecked_mut(|this| &mut this.inner) };
+        inner.poll(cx).map(Ok)
     }
 }
 
-// Trait implementations that delegate to the wrapped value
-// All of these perform runtime thread checking through get() and get_mut()
-impl<T: Debug> Debug for SendCell<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        self.get().fmt(f)
+/// A sink wrapper that implements Send with runtime thread checking.
+///
+/// `SendSink<T>` wraps a [`futures_sink::Sink`] of type `T` and provides a `Send`
+/// implementation with runtime thread checking. The sink remembers the thread it was
+/// created on and panics if `poll_ready`, `start_send`, `poll_flush` or `poll_close`
+/// is called from any other thread.
+///
+/// This allows thread-local sinks to be handed to middleware that requires `Send`
+/// sinks, while maintaining memory safety through runtime checks. Like
+/// [`SendCell`], dropping the sink on the wrong thread will also panic, and the sink is
+/// leaked rather than dropped there; during unwinding it is only leaked.
+///
+/// A `SendSink` is created with [`SendCell::into_sink`]. This type is only available
+/// with the `futures` feature.
+///
+/// # Panics
+///
+/// All `Sink` methods, as well as `Drop`, will panic if called from a different thread
+/// than the one where the original `SendCell` was created.
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub struct SendSink<T> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: SendSink implements Send by providing runtime thread checking.
+// The wrapped sink may not be Send, but we ensure safety by panicking
+// if it is driven or dropped on the wrong thread.
+#[cfg(feature = "futures")]
+unsafe impl<T> Send for SendSink<T> {}
+
+#[cfg(feature = "futures")]
+impl<T> SendSink<T> {
+    /// Checks the current thread and projects the pin to the wrapped sink.
+    fn checked_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendSink<{}> used from incorrect thread (origin thread {}, current thread {})",
+                core::any::type_name::<T>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: After the thread check, we can safely access the inner sink.
+        // We never move the inner value out of a pinned SendSink.
+        unsafe {
+            let self_mut = self.get_unchecked_mut();
+            Pin::new_unchecked(self_mut.inner.get_mut())
+        }
     }
 }
 
-impl<T> AsRef<T> for SendCell<T> {
-    fn as_ref(&self) -> &T {
-        self.get()
+#[cfg(feature = "futures")]
+impl<T: futures_sink::Sink<Item>, Item> futures_sink::Sink<Item> for SendSink<T> {
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.checked_pin_mut().poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        self.checked_pin_mut().start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.checked_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.checked_pin_mut().poll_close(cx)
     }
 }
 
-impl<T> AsMut<T> for SendCell<T> {
-    fn as_mut(&mut self) -> &mut T {
-        self.get_mut()
+#[cfg(feature = "futures")]
+impl<T> Drop for SendSink<T> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendSink") }
     }
 }
 
-impl<T> Deref for SendCell<T> {
-    type Target = T;
-    fn deref(&self) -> &Self::Target {
-        self.get()
+/// A stream wrapper that implements Send with runtime thread checking.
+///
+/// `SendStream<T>` wraps a [`futures_core::Stream`] of type `T` and provides a `Send`
+/// implementation with runtime thread checking. It is the stream counterpart of
+/// [`SendFuture`]: the stream remembers the thread it was created on and panics if
+/// polled from any other thread. Like [`SendCell`], dropping the stream on the wrong
+/// thread will also panic, and the stream is leaked rather than dropped there; during
+/// unwinding it is only leaked.
+///
+/// `SendStream` forwards `FusedStream` when the wrapped stream implements it.
+///
+/// A `SendStream` is created with [`SendCell::into_stream`]. This type is only available
+/// with the `futures` feature.
+///
+/// # Panics
+///
+/// `poll_next`, `size_hint` and `Drop` will panic if called from a different thread than
+/// the one where the original `SendCell` was created.
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub struct SendStream<T> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: SendStream implements Send by providing runtime thread checking.
+// The wrapped stream may not be Send, but we ensure safety by panicking
+// if it is polled or dropped on the wrong thread.
+#[cfg(feature = "futures")]
+unsafe impl<T> Send for SendStream<T> {}
+
+#[cfg(feature = "futures")]
+impl<T> SendStream<T> {
+    fn assert_thread(&self) {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendStream<{}> used from incorrect thread (origin thread {}, current thread {})",
+                core::any::type_name::<T>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+    }
+
+    /// Checks the current thread and projects the pin to the wrapped stream.
+    fn checked_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        self.assert_thread();
+        // SAFETY: After the thread check, we can safely access the inner stream.
+        // We never move the inner value out of a pinned SendStream.
+        unsafe {
+            let self_mut = self.get_unchecked_mut();
+            Pin::new_unchecked(self_mut.inner.get_mut())
+        }
+    }
+
+    /// Checks the current thread and returns the wrapped stream.
+    fn checked_ref(&self) -> &T {
+        self.assert_thread();
+        // SAFETY: After the thread check, we can safely access the inner stream.
+        unsafe { self.inner.get() }
     }
 }
 
-impl<T> DerefMut for SendCell<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.get_mut()
+#[cfg(feature = "futures")]
+impl<T: futures_core::Stream> futures_core::Stream for SendStream<T> {
+    type Item = T::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.checked_pin_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.checked_ref().size_hint()
     }
 }
 
-// Additional trait implementations
-// For comparison traits (Eq, Hash, etc.), we rely on Deref to the underlying type
-impl<T: Default> Default for SendCell<T> {
-    fn default() -> SendCell<T> {
-        SendCell::new(Default::default())
+#[cfg(feature = "futures")]
+impl<T: futures_core::stream::FusedStream> futures_core::stream::FusedStream for SendStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.checked_ref().is_terminated()
     }
 }
-impl<T> From<T> for SendCell<T> {
-    fn from(value: T) -> Self {
-        SendCell::new(value)
+
+#[cfg(feature = "futures")]
+impl<T> Drop for SendStream<T> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendStream") }
     }
 }
 
-/// A future wrapper that implements Send with runtime thread checking.
+/// An iterator wrapper that implements Send with runtime thread checking.
 ///
-/// `SendFuture<T>` wraps a future of type `T` and provides a `Send` implementation
-/// with runtime thread checking. The future remembers the thread it was created on
-/// and panics if polled from any other thread.
+/// `SendIter<I>` wraps an iterator of type `I` and provides a `Send` implementation
+/// with runtime thread checking. It is returned when iterating over a [`SendCell`] by
+/// value, so the iterator keeps the cell's thread affinity and can itself be moved
+/// through `Send` boundaries. Every call to `next` (and `next_back`) checks the thread.
 ///
-/// This wrapper allows non-Send futures to be used in contexts that require Send futures
-/// (such as being spawned on thread pools), while maintaining memory safety through
-/// runtime checks. Unlike [`crate::UnsafeSendFuture`], this provides safe cross-thread
-/// usage by panicking if accessed from the wrong thread.
+/// `SendIter` forwards `DoubleEndedIterator`, `ExactSizeIterator` and `FusedIterator`
+/// when the wrapped iterator implements them.
+///
+/// A `SendCell` holding an iterator is driven the same way: `SendCell<I: Iterator>` does
+/// not implement `Iterator` itself, because that would conflict with iterating the cell
+/// by value, but `cell.into_iter()` (or a `for` loop) yields a `SendIter<I>` over it.
 ///
 /// # Examples
 ///
 /// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
 /// use send_cells::SendCell;
 /// use std::rc::Rc;
-/// use std::future::Future;
-/// use std::pin::Pin;
-/// use std::task::{Context, Poll};
 ///
-/// // A future that is not Send
-/// struct NonSendFuture {
-///     data: Rc<i32>,
-/// }
-///
-/// impl Future for NonSendFuture {
-///     type Output = i32;
-///     
-///     fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
-///         Poll::Ready(*self.data)
-///     }
+/// let cell = SendCell::new(vec![Rc::new(1), Rc::new(2)]);
+/// let mut sum = 0;
+/// for value in cell {
+///     sum += *value;
 /// }
-///
-/// let future = NonSendFuture { data: Rc::new(42) };
-/// let cell = SendCell::new(future);
-/// let send_future = cell.into_future();
-///
-/// // Now it can be used in Send contexts
-/// fn requires_send_future<F: Future + Send>(_: F) {}
-/// requires_send_future(send_future);
+/// assert_eq!(sum, 3);
 /// ```
 ///
 /// # Panics
 ///
-/// The `poll` method will panic if called from a different thread than the one
-/// where the original `SendCell` was created.
+/// Advancing the iterator, as well as `Drop`, will panic if done from a different thread
+/// than the one where the original `SendCell` was created. A wrong-thread drop leaks the
+/// wrapped iterator rather than dropping it there; during unwinding it is only leaked.
 #[derive(Debug)]
-pub struct SendFuture<T> {
-    inner: UnsafeSendCell<T>,
+pub struct SendIter<I> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<I>>,
     thread_id: ThreadId,
 }
 
-// SAFETY: SendFuture implements Send by providing runtime thread checking.
-// The wrapped future may not be Send, but we ensure safety by panicking
-// if poll() is called from the wrong thread.
-unsafe impl<T> Send for SendFuture<T> {}
+// SAFETY: SendIter implements Send by providing runtime thread checking.
+// The wrapped iterator may not be Send, but we ensure safety by panicking
+// if it is advanced or dropped on the wrong thread.
+unsafe impl<I> Send for SendIter<I> {}
+
+impl<I> SendIter<I> {
+    /// Checks the current thread and returns a reference to the wrapped iterator.
+    fn checked_ref(&self) -> &I {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendIter<{}> used from incorrect thread (origin thread {}, current thread {})",
+                core::any::type_name::<I>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: Thread verified above.
+        unsafe { self.inner.get() }
+    }
+
+    /// Checks the current thread and returns a mutable reference to the wrapped iterator.
+    fn checked_mut(&mut self) -> &mut I {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendIter<{}> used from incorrect thread (origin thread {}, current thread {})",
+                core::any::type_name::<I>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: Thread verified above.
+        unsafe { self.inner.get_mut() }
+    }
+}
+
+impl<I: Iterator> Iterator for SendIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.checked_mut().next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.checked_ref().size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for SendIter<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.checked_mut().next_back()
+    }
+}
 
-impl<T: Future> Future for SendFuture<T> {
-    type Output = T::Output;
+impl<I: ExactSizeIterator> ExactSizeIterator for SendIter<I> {
+    fn len(&self) -> usize {
+        self.checked_ref().len()
+    }
+}
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        // Runtime thread check - panic if called from wrong thread
-        assert_eq!(
-            self.thread_id,
-            crate::sys::thread::current().id(),
-            "SendFuture<{}> polled from incorrect thread",
-            std::any::type_name::<T>()
-        );
+impl<I: FusedIterator> FusedIterator for SendIter<I> {}
 
-        // SAFETY: After the thread check, we can safely access the inner future
-        // using the same technique as UnsafeSendFuture
-        let inner = unsafe {
-            let self_mut = self.get_unchecked_mut();
-            Pin::new_unchecked(self_mut.inner.get_mut())
-        };
-        inner.poll(cx)
+impl<I> Drop for SendIter<I> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendIter") }
     }
 }
 
@@ -620,7 +2938,706 @@ mod tests {
 
     // Helper function to verify a type implements Send
     fn assert_send<T: Send>(_: &T) {}
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_with() {
+        let cell = SendCell::new(Rc::new(vec![1, 2, 3]));
+        assert_eq!(cell.with(|vec| vec.len()), 3);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_with_mut() {
+        let mut cell = SendCell::new(vec![1, 2, 3]);
+        let len = cell.with_mut(|vec| {
+            vec.push(4);
+            vec.len()
+        });
+        assert_eq!(len, 4);
+        assert_eq!(cell.into_inner(), vec![1, 2, 3, 4]);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_replace_and_set() {
+        let mut cell = SendCell::new(Rc::new(1));
+        assert_eq!(*cell.replace(Rc::new(2)), 1);
+        cell.set(Rc::new(3));
+        assert_eq!(**cell.get(), 3);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_take() {
+        let mut cell = SendCell::new(Some(Rc::new(1)));
+        assert_eq!(cell.take().map(|rc| *rc), Some(1));
+        assert!(cell.get().is_none());
+
+        let mut cell = SendCell::new(String::from("value"));
+        assert_eq!(cell.take(), "value");
+        assert_eq!(cell.get(), "");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_swap() {
+        let mut a = SendCell::new(Rc::new(1));
+        let mut b = SendCell::new(Rc::new(2));
+        a.swap(&mut b);
+        assert_eq!((**a.get(), **b.get()), (2, 1));
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_swap_cross_thread_panic() {
+        use crate::sys::thread;
+
+        // i32 has no drop glue, so the foreign cell may be dropped here
+        let mut foreign = thread::spawn(|| SendCell::new(1)).join().unwrap();
+        let mut local = SendCell::new(2);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            local.swap(&mut foreign);
+        }));
+        assert!(result.is_err());
+        assert_eq!(*local.get(), 2);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_clone() {
+        let original = SendCell::new(Rc::new(42));
+        let cloned = original.clone();
+        assert!(Rc::ptr_eq(original.get(), cloned.get()));
+        assert_eq!(Rc::strong_count(original.get()), 2);
+        assert_eq!(**original.clone_value().get(), 42);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    // The creation backtrace is lazily resolved, but never part of the hash
+    #[cfg_attr(feature = "backtrace", allow(clippy::mutable_key_type))]
+    fn test_eq_and_hash() {
+        use std::collections::HashSet;
+
+        let a = SendCell::new(Rc::new(1));
+        let b = SendCell::new(Rc::new(1));
+        assert_eq!(a, b);
+        assert_ne!(a, SendCell::new(Rc::new(2)));
+
+        let mut set = HashSet::new();
+        set.insert(a);
+        assert!(set.contains(&b));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_ordering() {
+        let mut cells = [
+            SendCell::new(Rc::new(3)),
+            SendCell::new(Rc::new(1)),
+            SendCell::new(Rc::new(2)),
+        ];
+        cells.sort();
+        let values: Vec<i32> = cells.iter().map(|cell| **cell.get()).collect();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert!(cells[0] < cells[1]);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_display() {
+        let cell = SendCell::new(Rc::new("hello"));
+        assert_eq!(cell.to_string(), "hello");
+        assert_eq!(format!("{:>7}", SendCell::new(42)), "     42");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_error() {
+        use std::error::Error;
+
+        #[derive(Debug)]
+        struct LocalError {
+            _local: Rc<()>,
+            source: std::fmt::Error,
+        }
+
+        impl std::fmt::Display for LocalError {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str("local error")
+            }
+        }
+
+        impl Error for LocalError {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.source)
+            }
+        }
+
+        let cell = SendCell::new(LocalError {
+            _local: Rc::new(()),
+            source: std::fmt::Error,
+        });
+        let error: &dyn Error = &cell;
+        assert_eq!(error.to_string(), "local error");
+        assert!(error.source().unwrap().is::<std::fmt::Error>());
+    }
+
+    #[cfg(feature = "serde")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_serde_round_trip() {
+        let cell = SendCell::new(std::cell::RefCell::new(vec![1, 2, 3]));
+        let json = serde_json::to_string(&cell).unwrap();
+        assert_eq!(json, "[1,2,3]");
+
+        let restored: SendCell<std::cell::RefCell<Vec<i32>>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, cell);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let cell = SendCell::new(42);
+        let result = thread::spawn(move || serde_json::to_string(&cell)).join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when serializing SendCell from incorrect thread"
+        );
+    }
+
+    #[cfg(feature = "fragile")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_fragile_round_trip() {
+        let fragile = fragile::Fragile::new(Rc::new(42));
+        let cell: SendCell<Rc<i32>> = fragile.into();
+        assert_eq!(**cell.get(), 42);
+
+        let fragile: fragile::Fragile<Rc<i32>> = cell.into();
+        assert_eq!(**fragile.get(), 42);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "fragile")]
+    #[test]
+    fn test_fragile_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let cell = SendCell::new(std::cell::Cell::new(42));
+        let result = thread::spawn(move || {
+            let _: fragile::Fragile<std::cell::Cell<i32>> = cell.into();
+        })
+        .join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when converting SendCell from incorrect thread"
+        );
+    }
+
+    #[cfg(feature = "send_wrapper")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_wrapper_round_trip() {
+        let wrapper = send_wrapper::SendWrapper::new(Rc::new(42));
+        let cell: SendCell<Rc<i32>> = wrapper.into();
+        assert_eq!(**cell.get(), 42);
+
+        let wrapper = send_wrapper::SendWrapper::from(cell);
+        assert!(wrapper.valid());
+        assert_eq!(**wrapper, 42);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "send_wrapper")]
+    #[test]
+    fn test_send_wrapper_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let wrapper = send_wrapper::SendWrapper::new(std::cell::Cell::new(42));
+        let result = thread::spawn(move || {
+            let _: SendCell<std::cell::Cell<i32>> = wrapper.into();
+        })
+        .join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when converting SendWrapper from incorrect thread"
+        );
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_index() {
+        use std::collections::HashMap;
+
+        let mut cell = SendCell::new(vec![Rc::new(1), Rc::new(2)]);
+        assert_eq!(*cell[1], 2);
+        cell[0] = Rc::new(10);
+        assert_eq!(*cell[0], 10);
+        assert_eq!(cell[..].len(), 2);
+
+        let map = SendCell::new(HashMap::from([("key", Rc::new("value"))]));
+        assert_eq!(*map["key"], "value");
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_io_delegation() {
+        use std::io::{BufRead, Cursor, Read, Seek, SeekFrom, Write};
+
+        // Generic I/O code accepts the cell directly
+        fn write_greeting(mut writer: impl Write) {
+            writer.write_all(b"hello\nworld\n").unwrap();
+            writer.flush().unwrap();
+        }
+
+        let mut cell = SendCell::new(Cursor::new(Vec::new()));
+        write_greeting(&mut cell);
+
+        cell.seek(SeekFrom::Start(0)).unwrap();
+        let mut line = String::new();
+        cell.read_line(&mut line).unwrap();
+        assert_eq!(line, "hello\n");
+
+        let mut rest = String::new();
+        cell.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "world\n");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    // The creation backtrace is lazily resolved, but never part of the hash
+    #[cfg_attr(feature = "backtrace", allow(clippy::mutable_key_type))]
+    fn test_borrow() {
+        use std::borrow::BorrowMut;
+        use std::collections::HashSet;
+
+        // Cells used as keys can be looked up by the wrapped value
+        let mut set = HashSet::new();
+        set.insert(SendCell::new(String::from("key")));
+        assert!(set.contains(&String::from("key")));
+
+        let mut cell = SendCell::new(vec![1]);
+        BorrowMut::<Vec<i32>>::borrow_mut(&mut cell).push(2);
+        assert_eq!(*cell.get(), vec![1, 2]);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_downcast() {
+        let mut cells: Vec<SendCell<Box<dyn Any>>> = vec![
+            SendCell::new(Box::new(Rc::new(1))),
+            SendCell::new(Box::new(String::from("two"))),
+        ];
+
+        assert_eq!(cells[0].downcast_ref::<Rc<i32>>().map(|rc| **rc), Some(1));
+        assert!(cells[0].downcast_ref::<String>().is_none());
+        cells[1].downcast_mut::<String>().unwrap().push('!');
+
+        let second = cells.pop().unwrap();
+        let second = second.downcast::<Rc<i32>>().unwrap_err();
+        assert_eq!(**second.downcast::<String>().unwrap().get(), "two!");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_into_iterator() {
+        let mut cell = SendCell::new(vec![Rc::new(1), Rc::new(2), Rc::new(3)]);
+
+        let mut sum = 0;
+        for value in &cell {
+            sum += **value;
+        }
+        assert_eq!(sum, 6);
+
+        for value in &mut cell {
+            *value = Rc::new(**value * 10);
+        }
+
+        let iter = cell.into_iter();
+        assert_send(&iter);
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.map(|rc| *rc).collect::<Vec<_>>(), vec![10, 20, 30]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_iter_moved_between_threads() {
+        use crate::sys::thread;
+
+        struct LocalIter {
+            remaining: Rc<std::cell::Cell<u32>>,
+        }
+        impl Iterator for LocalIter {
+            type Item = u32;
+            fn next(&mut self) -> Option<u32> {
+                let remaining = self.remaining.get();
+                self.remaining.set(remaining.checked_sub(1)?);
+                Some(remaining)
+            }
+        }
+
+        let mut iter = SendCell::new(LocalIter {
+            remaining: Rc::new(std::cell::Cell::new(3)),
+        })
+        .into_iter();
+        assert_eq!(iter.next(), Some(3));
+
+        // The iterator can pass through another thread, as long as it is driven here
+        let mut iter = thread::spawn(move || iter).join().unwrap();
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_iter_double_ended_exact_size() {
+        let mut iter = SendCell::new(vec![1, 2, 3, 4]).into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.rev().collect::<Vec<_>>(), vec![3, 2]);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_iter_cross_thread_panic() {
+        use crate::sys::thread;
+
+        // Range has no drop glue, so the iterator may be dropped on the other thread
+        let mut iter = SendCell::new(0..3).into_iter();
+        let result = thread::spawn(move || iter.next()).join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when advancing SendIter from incorrect thread"
+        );
+        let message = result.unwrap_err().downcast::<String>().unwrap();
+        let origin = crate::sys::current_id().as_u64();
+        assert!(
+            message.contains(&format!("(origin thread {origin}")),
+            "{message}"
+        );
+        assert!(message.contains(", current thread "), "{message}");
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_iter_cross_thread_drop() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the remaining items there
+        let marker = Rc::new(());
+        let iter = SendCell::new(vec![Rc::clone(&marker)]).into_iter();
+        assert!(thread::spawn(move || drop(iter)).join().is_err());
+        assert_eq!(Rc::strong_count(&marker), 2);
+
+        // Dropped while unwinding, a second panic would abort, so it is only leaked. A
+        // partly consumed double-ended iterator leaks the items it still holds.
+        let mut iter = SendCell::new(vec![Rc::clone(&marker), Rc::clone(&marker)]).into_iter();
+        drop(iter.next_back());
+        let result = thread::spawn(move || {
+            let _iter = iter;
+            panic!("original panic");
+        })
+        .join();
+        assert_eq!(
+            *result.unwrap_err().downcast::<&str>().unwrap(),
+            "original panic"
+        );
+        assert_eq!(Rc::strong_count(&marker), 3);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_leak() {
+        let marker = Rc::new(());
+        let leaked: &'static mut Rc<()> = SendCell::new(Rc::clone(&marker)).leak();
+        assert_eq!(Rc::strong_count(leaked), 2);
+        assert_eq!(Rc::strong_count(&marker), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_leak_on_foreign_drop() {
+        use crate::sys::thread;
+
+        let marker = Rc::new(());
+        let cell = SendCell::new_leak_on_foreign_drop(Rc::clone(&marker));
+        // The policy carries over to cells derived from the cell
+        let cell = cell.map(|rc| rc);
+        thread::spawn(move || drop(cell)).join().unwrap();
+        assert_eq!(Rc::strong_count(&marker), 2);
+
+        // Dropping on the origin thread drops the value
+        drop(SendCell::new_leak_on_foreign_drop(Rc::clone(&marker)));
+        assert_eq!(Rc::strong_count(&marker), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_drop_handler() {
+        use crate::sys::thread;
+        use std::sync::mpsc;
+
+        let (sender, receiver) = mpsc::channel();
+        let marker = Rc::new(());
+        let cell = SendCell::new_with_drop_handler(Rc::clone(&marker), move |cell| {
+            sender.send(cell).unwrap();
+        });
+        thread::spawn(move || drop(cell)).join().unwrap();
+        assert_eq!(Rc::strong_count(&marker), 2);
+
+        // The handed-back cell is bound to this thread and drops normally here
+        let returned = receiver.recv().unwrap();
+        assert!(Rc::ptr_eq(returned.get(), &marker));
+        drop(returned);
+        assert_eq!(Rc::strong_count(&marker), 1);
+
+        // Dropping on the origin thread drops the value without calling the handler
+        let cell = SendCell::new_with_drop_handler(Rc::clone(&marker), |_| unreachable!());
+        drop(cell);
+        assert_eq!(Rc::strong_count(&marker), 1);
+
+        // Consuming the cell drops the unused handler
+        let (sender, receiver) = mpsc::channel::<SendCell<Rc<()>>>();
+        let cell = SendCell::new_with_drop_handler(Rc::clone(&marker), move |cell| {
+            sender.send(cell).unwrap();
+        });
+        drop(cell.into_inner());
+        assert!(receiver.recv().is_err());
+
+        // A pinned cell hands its value over too, which is fine since the value is Unpin
+        let (sender, receiver) = mpsc::channel();
+        let mut cell = Box::pin(SendCell::new_with_drop_handler(
+            Rc::clone(&marker),
+            move |cell| {
+                sender.send(cell).unwrap();
+            },
+        ));
+        assert!(Rc::ptr_eq(&cell.as_mut().as_pin_mut(), &marker));
+        thread::spawn(move || drop(cell)).join().unwrap();
+        drop(receiver.recv().unwrap());
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_foreign_drop_panic_leaks_value() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the value on the wrong thread
+        let marker = Rc::new(());
+        let cell = SendCell::new(Rc::clone(&marker));
+        assert!(thread::spawn(move || drop(cell)).join().is_err());
+        assert_eq!(Rc::strong_count(&marker), 2);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_foreign_drop_while_panicking_leaks_value() {
+        use crate::sys::thread;
+
+        let marker = Rc::new(());
+        let cell = SendCell::new(Rc::clone(&marker));
+        let future = SendCell::new(std::future::ready(Rc::clone(&marker))).into_future();
+        // Dropped while unwinding, a second panic would abort the test process
+        let result = thread::spawn(move || {
+            let _cell = cell;
+            let _future = future;
+            panic!("original panic");
+        })
+        .join();
+        let message = result.unwrap_err().downcast::<&str>().unwrap();
+        assert_eq!(*message, "original panic");
+        assert_eq!(Rc::strong_count(&marker), 3);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_raw_round_trip_preserves_thread() {
+        use crate::sys::thread;
+
+        let cell = SendCell::new(Rc::new(42));
+        let ptr = unsafe { SendCell::<Rc<i32>>::from_raw(cell.into_raw()) }.into_raw();
+        assert_eq!(**unsafe { SendCell::<Rc<i32>>::from_raw(ptr) }.get(), 42);
+
+        // A foreign cell that went through a raw pointer is still bound to its thread.
+        // i32 has no drop glue, so the cell may be dropped here.
+        // The pointer crosses threads as a pointer, not an integer, to keep its provenance
+        let foreign = thread::spawn(|| unsafe {
+            crate::UnsafeSendCell::new_unchecked(SendCell::new(1).into_raw())
+        })
+        .join()
+        .unwrap();
+        let foreign = unsafe { SendCell::<i32>::from_raw(foreign.into_inner()) };
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| *foreign.get()));
+        assert!(result.is_err());
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut slot = SendCell::new(None);
+        let mut calls = 0;
+        for _ in 0..2 {
+            let value = slot.get_or_insert_with(|| {
+                calls += 1;
+                Rc::new(42)
+            });
+            assert_eq!(**value, 42);
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_unsized() {
+        let marker = Rc::new(());
+        let slice: Box<[Rc<()>]> = vec![Rc::clone(&marker); 3].into_boxed_slice();
+        let mut cell = SendCell::from_boxed_slice(slice);
+        assert_eq!(cell.get().len(), 3);
+        assert_eq!(Rc::strong_count(&marker), 4);
+        cell.get_mut()[0] = Rc::new(());
+        assert_eq!(Rc::strong_count(&marker), 3);
+        drop(cell);
+        assert_eq!(Rc::strong_count(&marker), 1);
+
+        let empty: Box<SendCell<[Rc<()>]>> = SendCell::from_boxed_slice(Box::new([]));
+        assert!(empty.is_empty());
+
+        let cell: Box<SendCell<dyn std::fmt::Display>> = Box::new(SendCell::new(Rc::new(42)));
+        assert_eq!(cell.to_string(), "42");
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unsized_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let cell: Box<SendCell<[std::cell::Cell<i32>]>> =
+            SendCell::from_boxed_slice(Box::new([std::cell::Cell::new(1)]));
+        let result = thread::spawn(move || cell.get()[0].get()).join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when accessing SendCell<[T]> from incorrect thread"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_new_bound_to() {
+        use crate::sys::thread;
+
+        let main_thread = crate::sys::current_id();
+        let cell = thread::spawn(move || {
+            let cell = unsafe { SendCell::new_bound_to(std::cell::Cell::new(1), main_thread) };
+            // The constructing thread is not the bound thread
+            let access =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| cell.get().get()));
+            assert!(access.is_err());
+            cell
+        })
+        .join()
+        .unwrap();
+        assert_eq!(cell.get().get(), 1);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_map() {
+        let cell = SendCell::new(Rc::new(21));
+        let mapped = cell.map(|rc| rc.to_string());
+        assert_eq!(mapped.get(), "21");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_send_cell_into_future_is_send() {
         // Create a non-Send future
@@ -632,11 +3649,64 @@ mod tests {
         // Convert to a Send future
         let send_future = cell.into_future();
 
-        // Verify the resulting future is Send
-        assert_send(&send_future);
+        // Verify the resulting future is Send
+        assert_send(&send_future);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_cell_await() {
+        let waker = {
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(
+                |_| RawWaker::new(std::ptr::null(), &VTABLE),
+                |_| {},
+                |_| {},
+                |_| {},
+            );
+            unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+        };
+        let mut context = Context::from_waker(&waker);
+
+        let cell = SendCell::new(NonSendFuture::new(42));
+        let mut outer = Box::pin(async move { cell.await });
+        assert_send(&outer);
+        assert_eq!(outer.as_mut().poll(&mut context), Poll::Pending);
+        assert_eq!(outer.as_mut().poll(&mut context), Poll::Ready(42));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_pin_projection() {
+        let waker = {
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(
+                |_| RawWaker::new(std::ptr::null(), &VTABLE),
+                |_| {},
+                |_| {},
+                |_| {},
+            );
+            unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+        };
+        let mut context = Context::from_waker(&waker);
+
+        let local = Rc::new(7);
+        let mut cell = SendCell::pin(async move { *local });
+        assert_eq!(
+            cell.as_mut().as_pin_mut().poll(&mut context),
+            Poll::Ready(7)
+        );
+        let _: Pin<&_> = cell.as_ref().as_pin_ref();
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_send_future_functionality() {
         // Create a no-op waker for testing
@@ -673,6 +3743,7 @@ mod tests {
     //no unwind on wasm!
     #[test]
     //at the moment, threads don't work in node: https://github.com/wasm-bindgen/wasm-bindgen/issues/4534
+    #[cfg(feature = "std")]
     fn test_send_future_cross_thread_panic() {
         use crate::sys::thread;
         use std::sync::{Arc, Mutex};
@@ -711,4 +3782,745 @@ mod tests {
             "Expected thread to panic when polling SendFuture from incorrect thread"
         );
     }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_future_into_inner() {
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut context = Context::from_waker(&waker);
+
+        let mut send_future = SendCell::new(NonSendFuture::new(42)).into_future();
+        assert_eq!(Pin::new(&mut send_future).poll(&mut context), Poll::Pending);
+
+        // The recovered future continues where the wrapper left off
+        let mut future = send_future.into_inner();
+        assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Ready(42));
+
+        let send_future = SendCell::new(NonSendFuture::new(42)).into_future();
+        let future = unsafe { send_future.into_unchecked_inner() };
+        assert!(!future.ready);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_future_pinned() {
+        let mut context = Context::from_waker(Waker::noop());
+
+        let local = Rc::new(());
+        let boxed: Pin<Box<dyn Future<Output = ()>>> = Box::pin(async move {
+            let _ = &local;
+        });
+        let mut send_future = SendFuture::from_pin(boxed);
+        assert_eq!(
+            Pin::new(&mut send_future).poll(&mut context),
+            Poll::Ready(())
+        );
+
+        // A polled future can be recovered, since it stays in its box
+        let mut send_future = SendCell::new(NonSendFuture::new(42)).into_future_pinned();
+        assert_eq!(Pin::new(&mut send_future).poll(&mut context), Poll::Pending);
+        let mut future = send_future.into_inner();
+        assert_eq!(future.as_mut().poll(&mut context), Poll::Ready(42));
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_future_into_inner_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let send_future = SendCell::new(std::future::ready(42)).into_future();
+        let handle = thread::spawn(move || {
+            drop(send_future.into_inner());
+        });
+        assert!(handle.join().is_err());
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_future_cross_thread_drop() {
+        use crate::sys::thread;
+
+        let local = Rc::new(42);
+
+        // Dropping on another thread panics without dropping the Rc there
+        let send_future = SendCell::new(NonSendFuture {
+            _data: Rc::clone(&local),
+            ready: false,
+        })
+        .into_future();
+        assert!(thread::spawn(move || drop(send_future)).join().is_err());
+        assert_eq!(Rc::strong_count(&local), 2);
+
+        // With the opt-out, it is leaked without a panic
+        let send_future = SendCell::new(NonSendFuture {
+            _data: Rc::clone(&local),
+            ready: false,
+        })
+        .into_future()
+        .leak_on_foreign_drop();
+        thread::spawn(move || drop(send_future)).join().unwrap();
+        assert_eq!(Rc::strong_count(&local), 3);
+
+        // Dropping on the origin thread drops the future as usual
+        let send_future = SendCell::new(NonSendFuture {
+            _data: Rc::clone(&local),
+            ready: false,
+        })
+        .into_future()
+        .leak_on_foreign_drop();
+        drop(send_future);
+        assert_eq!(Rc::strong_count(&local), 3);
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_future_fused() {
+        use futures_core::future::FusedFuture;
+
+        // Yields its value once, then reports itself terminated
+        struct Once(Option<Rc<i32>>);
+        impl Future for Once {
+            type Output = i32;
+            fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<i32> {
+                Poll::Ready(*self.0.take().expect("polled after completion"))
+            }
+        }
+        impl FusedFuture for Once {
+            fn is_terminated(&self) -> bool {
+                self.0.is_none()
+            }
+        }
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut context = Context::from_waker(&waker);
+
+        let mut send_future = SendCell::new(Once(Some(Rc::new(42)))).into_future();
+        assert!(!send_future.is_terminated());
+        assert_eq!(
+            Pin::new(&mut send_future).poll(&mut context),
+            Poll::Ready(42)
+        );
+        assert!(send_future.is_terminated());
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_send_future_panic_names_creation_site() {
+        use crate::sys::thread;
+
+        let cell = SendCell::new(std::future::ready(42));
+        let created_at = format!("created at {}:{}:", file!(), line!() + 1);
+        let send_future = cell.into_future();
+
+        let panic = thread::spawn(move || {
+            let mut send_future = send_future;
+            let mut context = Context::from_waker(Waker::noop());
+            let _ = Pin::new(&mut send_future).poll(&mut context);
+        })
+        .join()
+        .unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains(&created_at), "{message}");
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_panic_names_creation_site() {
+        use crate::sys::thread;
+
+        let created_at = format!("created at {}:{}:", file!(), line!() + 1);
+        let cell = SendCell::new(Rc::new(42));
+        let derived_at = format!("created at {}:{}:", file!(), line!() + 1);
+        let derived = cell.clone();
+        // Mapping keeps the original creation site
+        let cell = cell.map(|rc| rc);
+
+        let messages = thread::spawn(move || {
+            let panic = |cell: &SendCell<Rc<i32>>| {
+                let panic = std::panic::catch_unwind(|| **cell.get()).unwrap_err();
+                *panic.downcast::<String>().unwrap()
+            };
+            let messages = (panic(&cell), panic(&derived));
+            std::mem::forget((cell, derived));
+            messages
+        })
+        .join()
+        .unwrap();
+        assert!(messages.0.contains(&created_at), "{}", messages.0);
+        assert!(messages.1.contains(&derived_at), "{}", messages.1);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn test_panic_includes_creation_backtrace() {
+        use crate::sys::thread;
+
+        let cell = SendCell::new(Rc::new(42));
+        let message = thread::spawn(move || {
+            let panic = std::panic::catch_unwind(|| **cell.get()).unwrap_err();
+            std::mem::forget(cell);
+            *panic.downcast::<String>().unwrap()
+        })
+        .join()
+        .unwrap();
+        assert!(message.contains("\ncreation backtrace:\n"), "{message}");
+        assert!(
+            message.contains("test_panic_includes_creation_backtrace"),
+            "{message}"
+        );
+    }
+
+    // Every other wrong-thread test aborts with this feature, so run it on its own:
+    // cargo test --features abort-on-violation test_violation_aborts
+    #[cfg(feature = "std")]
+    #[cfg(feature = "abort-on-violation")]
+    #[test]
+    fn test_violation_aborts() {
+        const CHILD: &str = "SEND_CELLS_ABORT_CHILD";
+        if std::env::var_os(CHILD).is_some() {
+            let cell = SendCell::new(Rc::new(42));
+            let _ = crate::sys::thread::spawn(move || **cell.get()).join();
+            unreachable!("the violation should have aborted the process");
+        }
+
+        // Run this test again in a child process, which should abort
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "send_cell::tests::test_violation_aborts",
+                "--nocapture",
+            ])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("from incorrect thread"), "{stderr}");
+        assert!(!stderr.contains("panicked"), "{stderr}");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_panic_names_both_threads() {
+        use crate::sys::thread;
+        use std::panic::AssertUnwindSafe;
+
+        let cell = SendCell::new(std::cell::Cell::new(1));
+        let origin = format!("origin thread {}", crate::sys::current_id().as_u64());
+
+        let (message, current) = thread::Builder::new()
+            .name("intruder".into())
+            .spawn(move || {
+                let current = crate::sys::current_id().as_u64();
+                let panic = std::panic::catch_unwind(AssertUnwindSafe(|| cell.get().get()));
+                let message = *panic.unwrap_err().downcast::<String>().unwrap();
+                (message, format!("current thread {current} \"intruder\""))
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(message.contains(&origin), "{message}");
+        assert!(message.contains(&current), "{message}");
+        // The test harness names its threads after the test
+        if let Some(name) = thread::current().name() {
+            assert!(message.contains(&format!("{origin} {name:?}")), "{message}");
+        }
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_try_send_future() {
+        let mut context = Context::from_waker(Waker::noop());
+        let mut future = SendCell::new(NonSendFuture::new(42))
+            .into_future()
+            .or_error();
+        assert_send(&future);
+        assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Pending);
+        assert_eq!(
+            Pin::new(&mut future).poll(&mut context),
+            Poll::Ready(Ok(42))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_try_send_future_cross_thread() {
+        use crate::sys::thread;
+
+        let local = Rc::new(42);
+        let future = SendCell::new(NonSendFuture {
+            _data: Rc::clone(&local),
+            ready: false,
+        })
+        .into_future()
+        .or_error();
+        let origin = crate::sys::current_id();
+
+        // Neither the poll nor the drop on the wrong thread panics
+        let error = thread::spawn(move || {
+            let mut future = future;
+            let mut context = Context::from_waker(Waker::noop());
+            match Pin::new(&mut future).poll(&mut context) {
+                Poll::Ready(Err(error)) => error,
+                _ => panic!("expected a WrongThreadError"),
+            }
+        })
+        .join()
+        .unwrap();
+        assert_eq!(error.origin_thread(), origin);
+        assert_ne!(error.current_thread(), origin);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "accessed from incorrect thread (origin thread {}, current thread {})",
+                crate::sys::Described(origin),
+                crate::sys::Described(error.current_thread())
+            )
+        );
+        // The future was leaked, not dropped on the other thread
+        assert_eq!(Rc::strong_count(&local), 2);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_poll_tracing() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        // Records span names and the names of fields recorded on them later
+        #[derive(Clone, Default)]
+        struct Recording(Arc<Mutex<Vec<String>>>);
+        impl Subscriber for Recording {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let fields = span
+                    .metadata()
+                    .fields()
+                    .iter()
+                    .map(|f| f.name())
+                    .collect::<Vec<_>>();
+                let entry = format!("{} {}", span.metadata().name(), fields.join(","));
+                self.0.lock().unwrap().push(entry);
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, values: &Record<'_>) {
+                struct Names(Vec<&'static str>);
+                impl Visit for Names {
+                    fn record_debug(&mut self, field: &Field, _: &dyn Debug) {
+                        self.0.push(field.name());
+                    }
+                }
+                let mut names = Names(Vec::new());
+                values.record(&mut names);
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("recorded {}", names.0.join(",")));
+            }
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recording = Recording::default();
+        tracing::subscriber::with_default(recording.clone(), || {
+            let mut context = Context::from_waker(Waker::noop());
+            let mut future = SendCell::new(NonSendFuture::new(42)).into_future();
+            assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Pending);
+        });
+
+        let recorded = recording.0.lock().unwrap().clone();
+        assert_eq!(
+            recorded,
+            [
+                "SendFuture poll future,origin,thread,duration",
+                "recorded duration"
+            ]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_check_stats() {
+        use crate::sys::thread;
+
+        let before = crate::check_stats();
+        let cell = SendCell::new_leak_on_foreign_drop(Rc::new(42));
+        assert_eq!(**cell.get(), 42);
+        // A caught violation that leaks rather than panics still counts
+        thread::spawn(move || drop(cell)).join().unwrap();
+        let after = crate::check_stats();
+
+        // Other tests check cells concurrently, so the counters can only be bounded
+        assert!(after.checks >= before.checks + 2);
+        assert!(after.violations > before.violations);
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_access_tracing() {
+        use crate::sys::thread;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        // Records the method and cross_thread fields of each event
+        #[derive(Clone, Default)]
+        struct Recording(Arc<Mutex<Vec<String>>>);
+        impl Subscriber for Recording {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                #[derive(Default)]
+                struct Fields(String);
+                impl Visit for Fields {
+                    fn record_str(&mut self, field: &Field, value: &str) {
+                        if field.name() == "method" {
+                            self.0.push_str(value);
+                        }
+                    }
+                    fn record_bool(&mut self, field: &Field, value: bool) {
+                        if field.name() == "cross_thread" && value {
+                            self.0.push_str(" cross_thread");
+                        }
+                    }
+                    fn record_debug(&mut self, _: &Field, _: &dyn Debug) {}
+                }
+                let mut fields = Fields::default();
+                if *event.metadata().level() == tracing::Level::WARN {
+                    fields.0.push_str("leaked");
+                }
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0);
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recording = Recording::default();
+        let cell = tracing::subscriber::with_default(recording.clone(), || {
+            let mut cell = SendCell::new(42);
+            assert_eq!(*cell.get(), 42);
+            *cell.get_mut() += 1;
+            cell
+        });
+        // i32 can be dropped anywhere, but the drop still shows up as cross-thread
+        let subscriber = recording.clone();
+        thread::spawn(move || tracing::subscriber::with_default(subscriber, || drop(cell)))
+            .join()
+            .unwrap();
+
+        // A leaked value is reported as a warning
+        let cell = SendCell::new_leak_on_foreign_drop(String::from("leaked"));
+        let subscriber = recording.clone();
+        thread::spawn(move || tracing::subscriber::with_default(subscriber, || drop(cell)))
+            .join()
+            .unwrap();
+
+        let recorded = recording.0.lock().unwrap().clone();
+        assert_eq!(
+            recorded,
+            [
+                "get",
+                "get_mut",
+                "drop cross_thread",
+                "drop cross_thread",
+                "leaked"
+            ]
+        );
+    }
+
+    // A sink that is NOT Send because it contains Rc<T>
+    #[cfg(feature = "futures")]
+    struct NonSendSink {
+        items: Rc<std::cell::RefCell<Vec<i32>>>,
+    }
+
+    #[cfg(feature = "futures")]
+    impl futures_sink::Sink<i32> for NonSendSink {
+        type Error = ();
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: i32) -> Result<(), ()> {
+            std::cell::RefCell::borrow_mut(&self.items).push(item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sink_functionality() {
+        use futures_sink::Sink;
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        let waker = unsafe { Waker::from_raw(raw_waker) };
+        let mut context = Context::from_waker(&waker);
+
+        let items = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let cell = SendCell::new(NonSendSink {
+            items: items.clone(),
+        });
+        let mut send_sink = cell.into_sink();
+        assert_send(&send_sink);
+
+        let mut pinned = Pin::new(&mut send_sink);
+        assert_eq!(
+            pinned.as_mut().poll_ready(&mut context),
+            Poll::Ready(Ok(()))
+        );
+        pinned.as_mut().start_send(1).unwrap();
+        pinned.as_mut().start_send(2).unwrap();
+        assert_eq!(
+            pinned.as_mut().poll_flush(&mut context),
+            Poll::Ready(Ok(()))
+        );
+        assert_eq!(
+            pinned.as_mut().poll_close(&mut context),
+            Poll::Ready(Ok(()))
+        );
+        assert_eq!(*std::cell::RefCell::borrow(&items), vec![1, 2]);
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_send_sink_cross_thread_panic() {
+        use crate::sys::thread;
+        use futures_sink::Sink;
+        use std::sync::{Arc, Mutex};
+
+        let cell = SendCell::new(NonSendSink {
+            items: Rc::new(std::cell::RefCell::new(Vec::new())),
+        });
+        let sink_mutex = Arc::new(Mutex::new(cell.into_sink()));
+        let sink_clone = Arc::clone(&sink_mutex);
+
+        let handle = thread::spawn(move || {
+            let mut sink_guard = sink_clone.lock().unwrap();
+            let pinned = Pin::new(&mut *sink_guard);
+            let _ = pinned.start_send(1);
+        });
+
+        let result = handle.join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when using SendSink from incorrect thread"
+        );
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_send_sink_cross_thread_drop() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the sink on the wrong thread
+        let items = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = SendCell::new(NonSendSink {
+            items: Rc::clone(&items),
+        })
+        .into_sink();
+        assert!(thread::spawn(move || drop(sink)).join().is_err());
+        assert_eq!(Rc::strong_count(&items), 2);
+
+        // Dropped while unwinding, a second panic would abort, so the sink is only leaked
+        let sink = SendCell::new(NonSendSink {
+            items: Rc::clone(&items),
+        })
+        .into_sink();
+        let result = thread::spawn(move || {
+            let _sink = sink;
+            panic!("original panic");
+        })
+        .join();
+        assert_eq!(
+            *result.unwrap_err().downcast::<&str>().unwrap(),
+            "original panic"
+        );
+        assert_eq!(Rc::strong_count(&items), 3);
+    }
+
+    // A stream that is NOT Send because it contains Rc<T>
+    #[cfg(feature = "futures")]
+    struct NonSendStream {
+        remaining: Rc<std::cell::Cell<i32>>,
+    }
+
+    #[cfg(feature = "futures")]
+    impl futures_core::Stream for NonSendStream {
+        type Item = i32;
+
+        fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<i32>> {
+            let remaining = self.remaining.get();
+            if remaining == 0 {
+                Poll::Ready(None)
+            } else {
+                self.remaining.set(remaining - 1);
+                Poll::Ready(Some(remaining))
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let remaining = self.remaining.get() as usize;
+            (remaining, Some(remaining))
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_stream_functionality() {
+        use futures_core::Stream;
+
+        let mut context = Context::from_waker(Waker::noop());
+        let cell = SendCell::new(NonSendStream {
+            remaining: Rc::new(std::cell::Cell::new(2)),
+        });
+        let mut send_stream = cell.into_stream();
+        assert_send(&send_stream);
+        assert_eq!(send_stream.size_hint(), (2, Some(2)));
+
+        let mut pinned = Pin::new(&mut send_stream);
+        assert_eq!(
+            pinned.as_mut().poll_next(&mut context),
+            Poll::Ready(Some(2))
+        );
+        assert_eq!(
+            pinned.as_mut().poll_next(&mut context),
+            Poll::Ready(Some(1))
+        );
+        assert_eq!(pinned.as_mut().poll_next(&mut context), Poll::Ready(None));
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_send_stream_cross_thread_panic() {
+        use crate::sys::thread;
+        use futures_core::Stream;
+        use std::sync::{Arc, Mutex};
+
+        let cell = SendCell::new(NonSendStream {
+            remaining: Rc::new(std::cell::Cell::new(2)),
+        });
+        let stream_mutex = Arc::new(Mutex::new(cell.into_stream()));
+        let stream_clone = Arc::clone(&stream_mutex);
+
+        let handle = thread::spawn(move || {
+            let mut stream_guard = stream_clone.lock().unwrap();
+            let pinned = Pin::new(&mut *stream_guard);
+            let _ = pinned.poll_next(&mut Context::from_waker(Waker::noop()));
+        });
+
+        let result = handle.join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when using SendStream from incorrect thread"
+        );
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_send_stream_cross_thread_drop() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the stream on the wrong thread
+        let remaining = Rc::new(std::cell::Cell::new(2));
+        let stream = SendCell::new(NonSendStream {
+            remaining: Rc::clone(&remaining),
+        })
+        .into_stream();
+        assert!(thread::spawn(move || drop(stream)).join().is_err());
+        assert_eq!(Rc::strong_count(&remaining), 2);
+
+        // Dropped while unwinding, a second panic would abort, so the stream is only leaked
+        let stream = SendCell::new(NonSendStream {
+            remaining: Rc::clone(&remaining),
+        })
+        .into_stream();
+        let result = thread::spawn(move || {
+            let _stream = stream;
+            panic!("original panic");
+        })
+        .join();
+        assert_eq!(
+            *result.unwrap_err().downcast::<&str>().unwrap(),
+            "original panic"
+        );
+        assert_eq!(Rc::strong_count(&remaining), 3);
+    }
 }
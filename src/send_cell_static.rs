@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A runtime-checked cell for non-Send values stored in `static` items.
+
+[`crate::SendCell::new`] records the current thread, which can only be done at runtime,
+so a `SendCell` cannot be the initializer of a `static`. This module provides
+[`SendCellStatic<T>`], which has a `const` constructor taking an initialization function.
+The value is created lazily by the first thread that accesses it, and the cell is bound
+to that thread from then on:
+
+- The first call to [`SendCellStatic::get`] runs the initializer on the calling thread
+  and remembers that thread.
+- Later calls from the same thread return the value.
+- Calls from any other thread panic, exactly like [`crate::SendCell::get`].
+
+This is useful for per-program singletons that must live on one particular thread,
+such as a handle to a UI toolkit that may only be used from the main thread.
+
+# Examples
+
+```rust
+use send_cells::SendCellStatic;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+static REGISTRY: SendCellStatic<Rc<RefCell<Vec<&'static str>>>> =
+    SendCellStatic::new(|| Rc::new(RefCell::new(Vec::new())));
+
+// The first access creates the value and binds it to this thread
+REGISTRY.get().borrow_mut().push("widget");
+assert_eq!(REGISTRY.get().borrow().len(), 1);
+```
+*/
+
+use crate::send_cell::SendCell;
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+/// A lazily initialized, runtime-checked cell that can be used in `static` position.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SendCellStatic;
+/// use std::cell::Cell;
+///
+/// static COUNTER: SendCellStatic<Cell<u32>> = SendCellStatic::new(|| Cell::new(0));
+///
+/// COUNTER.get().set(COUNTER.get().get() + 1);
+/// assert_eq!(COUNTER.get().get(), 1);
+/// ```
+///
+/// # Panics
+///
+/// Accessing the value panics if called from a different thread than the one that first
+/// accessed it.
+pub struct SendCellStatic<T> {
+    cell: OnceLock<SendCell<T>>,
+    init: fn() -> T,
+}
+
+impl<T> SendCellStatic<T> {
+    /// Creates a new `SendCellStatic` that will be initialized with `init`.
+    ///
+    /// No value is created and no thread is recorded until the first access.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCellStatic;
+    /// use std::rc::Rc;
+    ///
+    /// static VALUE: SendCellStatic<Rc<str>> = SendCellStatic::new(|| Rc::from("hello"));
+    /// assert_eq!(&**VALUE.get(), "hello");
+    /// ```
+    #[inline]
+    pub const fn new(init: fn() -> T) -> SendCellStatic<T> {
+        SendCellStatic {
+            cell: OnceLock::new(),
+            init,
+        }
+    }
+
+    /// Accesses the value, initializing it and binding it to the current thread on first use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value was already initialized by a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCellStatic;
+    ///
+    /// static NAMES: SendCellStatic<Vec<String>> =
+    ///     SendCellStatic::new(|| vec!["a".to_string(), "b".to_string()]);
+    /// assert_eq!(NAMES.get().len(), 2);
+    /// ```
+    #[inline]
+    pub fn get(&self) -> &T {
+        self.cell.get_or_init(|| SendCell::new((self.init)())).get()
+    }
+
+    /// Accesses the value if it has already been initialized.
+    ///
+    /// Returns `None` without initializing if no thread has accessed the value yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value was initialized by a different thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCellStatic;
+    ///
+    /// static VALUE: SendCellStatic<u32> = SendCellStatic::new(|| 42);
+    /// assert_eq!(VALUE.get_if_initialized(), None);
+    /// VALUE.get();
+    /// assert_eq!(VALUE.get_if_initialized(), Some(&42));
+    /// ```
+    #[inline]
+    pub fn get_if_initialized(&self) -> Option<&T> {
+        self.cell.get().map(SendCell::get)
+    }
+}
+
+// SAFETY: The value is only ever created and accessed through SendCell::get, which panics
+// on any thread other than the one that created it. Other threads can only observe
+// whether initialization has happened, never the value itself.
+unsafe impl<T> Sync for SendCellStatic<T> {}
+
+impl<T: Debug> Debug for SendCellStatic<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.get_if_initialized() {
+            Some(value) => value.fmt(f),
+            None => f.write_str("<uninitialized>"),
+        }
+    }
+}
+
+impl<T> Deref for SendCellStatic<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_lazy_initialization() {
+        static VALUE: SendCellStatic<Rc<i32>> = SendCellStatic::new(|| Rc::new(42));
+
+        assert_eq!(VALUE.get_if_initialized(), None);
+        assert_eq!(format!("{:?}", VALUE), "<uninitialized>");
+        assert_eq!(**VALUE.get(), 42);
+        assert_eq!(**VALUE, 42);
+        assert_eq!(VALUE.get_if_initialized().map(|rc| **rc), Some(42));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_is_sync() {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let cell: SendCellStatic<Rc<i32>> = SendCellStatic::new(|| Rc::new(42));
+        assert_sync(&cell);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_cross_thread_panic() {
+        use crate::sys::thread;
+
+        static VALUE: SendCellStatic<std::cell::Cell<i32>> =
+            SendCellStatic::new(|| std::cell::Cell::new(42));
+
+        assert_eq!(VALUE.get().get(), 42);
+        let result = thread::spawn(|| VALUE.get().get()).join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when accessing SendCellStatic from incorrect thread"
+        );
+    }
+
+    #[test]
+    fn test_first_accessor_binds() {
+        use crate::sys::thread;
+
+        static VALUE: SendCellStatic<std::cell::Cell<i32>> =
+            SendCellStatic::new(|| std::cell::Cell::new(0));
+
+        // Initialized and used entirely on another thread
+        let result = thread::spawn(|| {
+            VALUE.get().set(1);
+            VALUE.get().get()
+        })
+        .join();
+        assert_eq!(result.unwrap(), 1);
+    }
+}
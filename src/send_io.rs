@@ -0,0 +1,571 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+Runtime-checked wrappers for sending non-Send asynchronous I/O objects across thread boundaries.
+
+This module provides [`SendAsyncRead<T>`] and [`SendAsyncWrite<T>`], which wrap non-Send
+readers and writers so they can be used with codecs, servers and other APIs that require
+`Send` I/O objects. Like [`crate::SendFuture`], each wrapper remembers the thread it was
+created on and panics if it is polled (or dropped) from any other thread. A wrong-thread
+drop leaks the wrapped object rather than dropping it there, and during unwinding only
+leaks it.
+
+The wrappers implement the I/O traits of whichever ecosystems are enabled:
+
+- With the `futures-io` feature: `futures_io::AsyncRead`, `futures_io::AsyncBufRead`
+  and `futures_io::AsyncWrite`
+- With the `tokio-io` feature: `tokio::io::AsyncRead`, `tokio::io::AsyncBufRead`
+  and `tokio::io::AsyncWrite`
+
+This module is only available when at least one of these features is enabled.
+
+# Example
+
+```rust
+use send_cells::SendCell;
+use std::rc::Rc;
+
+// A reader that is not Send
+struct LocalReader {
+    _local: Rc<()>,
+}
+
+let cell = SendCell::new(LocalReader { _local: Rc::new(()) });
+let reader = cell.into_async_read();
+
+// The wrapper implements Send, and forwards the I/O traits of the inner reader
+fn requires_send<T: Send>(_: T) {}
+requires_send(reader);
+```
+*/
+
+use crate::SendCell;
+use crate::send_cell::drop_on_origin;
+use crate::sys::{Described, ThreadId};
+use crate::unsafe_send_cell::UnsafeSendCell;
+use std::io;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+impl<T> SendCell<T> {
+    /// Converts the cell into an asynchronous reader that implements Send with runtime
+    /// thread checking.
+    ///
+    /// This method consumes the `SendCell` and returns a [`SendAsyncRead`] that forwards
+    /// the asynchronous read traits of `T`. The reader will panic if polled from a
+    /// different thread than the one where the original `SendCell` was created.
+    ///
+    /// This method is only available with the `futures-io` or `tokio-io` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    ///
+    /// let cell = SendCell::new(std::rc::Rc::new(()));
+    /// let reader = cell.into_async_read();
+    ///
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(reader);
+    /// ```
+    pub fn into_async_read(self) -> SendAsyncRead<T> {
+        let (inner, thread_id) = self.into_parts();
+        SendAsyncRead {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+        }
+    }
+
+    /// Converts the cell into an asynchronous writer that implements Send with runtime
+    /// thread checking.
+    ///
+    /// This method consumes the `SendCell` and returns a [`SendAsyncWrite`] that forwards
+    /// the asynchronous write traits of `T`. The writer will panic if polled from a
+    /// different thread than the one where the original `SendCell` was created.
+    ///
+    /// This method is only available with the `futures-io` or `tokio-io` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    ///
+    /// let cell = SendCell::new(std::rc::Rc::new(()));
+    /// let writer = cell.into_async_write();
+    ///
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(writer);
+    /// ```
+    pub fn into_async_write(self) -> SendAsyncWrite<T> {
+        let (inner, thread_id) = self.into_parts();
+        SendAsyncWrite {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+        }
+    }
+}
+
+/// An asynchronous reader wrapper that implements Send with runtime thread checking.
+///
+/// `SendAsyncRead<T>` wraps a reader of type `T` and provides a `Send` implementation
+/// with runtime thread checking. It forwards `AsyncRead` and `AsyncBufRead` from the
+/// enabled I/O ecosystems (`futures-io` and/or `tokio-io`), checking the thread on
+/// every call.
+///
+/// A `SendAsyncRead` is created with [`SendCell::into_async_read`].
+///
+/// # Panics
+///
+/// All I/O methods, as well as `Drop`, will panic if called from a different thread
+/// than the one where the original `SendCell` was created.
+#[derive(Debug)]
+pub struct SendAsyncRead<T> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: SendAsyncRead implements Send by providing runtime thread checking.
+// The wrapped reader may not be Send, but we ensure safety by panicking
+// if it is polled or dropped on the wrong thread.
+unsafe impl<T> Send for SendAsyncRead<T> {}
+
+impl<T> SendAsyncRead<T> {
+    /// Checks the current thread and projects the pin to the wrapped reader.
+    fn checked_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendAsyncRead<{}> polled from incorrect thread (origin thread {}, \
+                 current thread {})",
+                std::any::type_name::<T>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: After the thread check, we can safely access the inner reader.
+        // We never move the inner value out of a pinned SendAsyncRead.
+        unsafe {
+            let self_mut = self.get_unchecked_mut();
+            Pin::new_unchecked(self_mut.inner.get_mut())
+        }
+    }
+}
+
+impl<T> Drop for SendAsyncRead<T> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendAsyncRead") }
+    }
+}
+
+/// An asynchronous writer wrapper that implements Send with runtime thread checking.
+///
+/// `SendAsyncWrite<T>` wraps a writer of type `T` and provides a `Send` implementation
+/// with runtime thread checking. It forwards `AsyncWrite` from the enabled I/O
+/// ecosystems (`futures-io` and/or `tokio-io`), checking the thread on every call.
+///
+/// A `SendAsyncWrite` is created with [`SendCell::into_async_write`].
+///
+/// # Panics
+///
+/// All I/O methods, as well as `Drop`, will panic if called from a different thread
+/// than the one where the original `SendCell` was created.
+#[derive(Debug)]
+pub struct SendAsyncWrite<T> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<T>>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: SendAsyncWrite implements Send by providing runtime thread checking.
+// The wrapped writer may not be Send, but we ensure safety by panicking
+// if it is polled or dropped on the wrong thread.
+unsafe impl<T> Send for SendAsyncWrite<T> {}
+
+impl<T> SendAsyncWrite<T> {
+    /// Checks the current thread and returns a reference to the wrapped writer.
+    #[cfg(feature = "tokio-io")]
+    fn checked_ref(&self) -> &T {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendAsyncWrite<{}> used from incorrect thread (origin thread {}, \
+                 current thread {})",
+                std::any::type_name::<T>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: Thread verified above.
+        unsafe { self.inner.get() }
+    }
+
+    /// Checks the current thread and projects the pin to the wrapped writer.
+    fn checked_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendAsyncWrite<{}> polled from incorrect thread (origin thread {}, \
+                 current thread {})",
+                std::any::type_name::<T>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: After the thread check, we can safely access the inner writer.
+        // We never move the inner value out of a pinned SendAsyncWrite.
+        unsafe {
+            let self_mut = self.get_unchecked_mut();
+            Pin::new_unchecked(self_mut.inner.get_mut())
+        }
+    }
+}
+
+impl<T> Drop for SendAsyncWrite<T> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendAsyncWrite") }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T: futures_io::AsyncRead> futures_io::AsyncRead for SendAsyncRead<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_read(cx, buf)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_read_vectored(cx, bufs)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T: futures_io::AsyncBufRead> futures_io::AsyncBufRead for SendAsyncRead<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.checked_pin_mut().poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.checked_pin_mut().consume(amt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T: futures_io::AsyncWrite> futures_io::AsyncWrite for SendAsyncWrite<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_write_vectored(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.checked_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.checked_pin_mut().poll_close(cx)
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl<T: tokio::io::AsyncRead> tokio::io::AsyncRead for SendAsyncRead<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.checked_pin_mut().poll_read(cx, buf)
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl<T: tokio::io::AsyncBufRead> tokio::io::AsyncBufRead for SendAsyncRead<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.checked_pin_mut().poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.checked_pin_mut().consume(amt)
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl<T: tokio::io::AsyncWrite> tokio::io::AsyncWrite for SendAsyncWrite<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.checked_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.checked_pin_mut().poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.checked_pin_mut().poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.checked_ref().is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+
+    // An in-memory I/O object that is NOT Send because it contains Rc<T>
+    struct NonSendIo {
+        data: Rc<RefCell<Vec<u8>>>,
+        position: usize,
+    }
+
+    impl NonSendIo {
+        fn new(data: &[u8]) -> Self {
+            Self {
+                data: Rc::new(RefCell::new(data.to_vec())),
+                position: 0,
+            }
+        }
+
+        fn read_into(&mut self, buf: &mut [u8]) -> usize {
+            let data = self.data.borrow();
+            let n = buf.len().min(data.len() - self.position);
+            buf[..n].copy_from_slice(&data[self.position..self.position + n]);
+            self.position += n;
+            n
+        }
+    }
+
+    #[cfg(feature = "futures-io")]
+    impl futures_io::AsyncRead for NonSendIo {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(Ok(self.get_mut().read_into(buf)))
+        }
+    }
+
+    #[cfg(feature = "futures-io")]
+    impl futures_io::AsyncWrite for NonSendIo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.data.borrow_mut().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "tokio-io")]
+    impl tokio::io::AsyncRead for NonSendIo {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let n = self.get_mut().read_into(buf.initialize_unfilled());
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "tokio-io")]
+    impl tokio::io::AsyncWrite for NonSendIo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.data.borrow_mut().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn noop_waker() -> Waker {
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        unsafe { Waker::from_raw(raw_waker) }
+    }
+
+    // Helper function to verify a type implements Send
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_futures_io_functionality() {
+        use futures_io::{AsyncRead, AsyncWrite};
+
+        let waker = noop_waker();
+        let mut context = Context::from_waker(&waker);
+
+        let mut reader = SendCell::new(NonSendIo::new(b"hello")).into_async_read();
+        assert_send(&reader);
+        let mut buf = [0u8; 8];
+        let read = Pin::new(&mut reader).poll_read(&mut context, &mut buf);
+        assert!(matches!(read, Poll::Ready(Ok(5))));
+        assert_eq!(&buf[..5], b"hello");
+
+        let io = NonSendIo::new(b"");
+        let data = io.data.clone();
+        let mut writer = SendCell::new(io).into_async_write();
+        assert_send(&writer);
+        let written = Pin::new(&mut writer).poll_write(&mut context, b"world");
+        assert!(matches!(written, Poll::Ready(Ok(5))));
+        assert!(matches!(
+            Pin::new(&mut writer).poll_close(&mut context),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(&*data.borrow(), b"world");
+    }
+
+    #[cfg(feature = "tokio-io")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_tokio_io_functionality() {
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        let waker = noop_waker();
+        let mut context = Context::from_waker(&waker);
+
+        let mut reader = SendCell::new(NonSendIo::new(b"hello")).into_async_read();
+        assert_send(&reader);
+        let mut storage = [0u8; 8];
+        let mut buf = ReadBuf::new(&mut storage);
+        let read = Pin::new(&mut reader).poll_read(&mut context, &mut buf);
+        assert!(matches!(read, Poll::Ready(Ok(()))));
+        assert_eq!(buf.filled(), b"hello");
+
+        let io = NonSendIo::new(b"");
+        let data = io.data.clone();
+        let mut writer = SendCell::new(io).into_async_write();
+        assert!(!writer.is_write_vectored());
+        let written = Pin::new(&mut writer).poll_write(&mut context, b"world");
+        assert!(matches!(written, Poll::Ready(Ok(5))));
+        assert!(matches!(
+            Pin::new(&mut writer).poll_shutdown(&mut context),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(&*data.borrow(), b"world");
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn test_read_cross_thread_panic() {
+        use crate::sys::thread;
+        use futures_io::AsyncRead;
+        use std::sync::{Arc, Mutex};
+
+        let reader = SendCell::new(NonSendIo::new(b"hello")).into_async_read();
+        let reader_mutex = Arc::new(Mutex::new(reader));
+        let reader_clone = Arc::clone(&reader_mutex);
+
+        let handle = thread::spawn(move || {
+            let waker = noop_waker();
+            let mut context = Context::from_waker(&waker);
+            let mut reader_guard = reader_clone.lock().unwrap();
+            let mut buf = [0u8; 8];
+            let _ = Pin::new(&mut *reader_guard).poll_read(&mut context, &mut buf);
+        });
+
+        let result = handle.join();
+        assert!(
+            result.is_err(),
+            "Expected thread to panic when polling SendAsyncRead from incorrect thread"
+        );
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_cross_thread_drop() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the I/O object on the wrong thread
+        let io = NonSendIo::new(b"hello");
+        let data = Rc::clone(&io.data);
+        let reader = SendCell::new(io).into_async_read();
+        assert!(thread::spawn(move || drop(reader)).join().is_err());
+        assert_eq!(Rc::strong_count(&data), 2);
+
+        // Dropped while unwinding, a second panic would abort, so it is only leaked
+        let writer = SendCell::new(NonSendIo {
+            data: Rc::clone(&data),
+            position: 0,
+        })
+        .into_async_write();
+        let result = thread::spawn(move || {
+            let _writer = writer;
+            panic!("original panic");
+        })
+        .join();
+        assert_eq!(
+            *result.unwrap_err().downcast::<&str>().unwrap(),
+            "original panic"
+        );
+        assert_eq!(Rc::strong_count(&data), 3);
+    }
+}
@@ -0,0 +1,286 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A runtime-checked wrapper for using non-Send `tower` services in Send-bounded stacks.
+
+This module provides [`SendService<S>`], which wraps a `tower::Service` that is not
+`Send` (for example, one holding a JavaScript or COM handle) so it can be mounted in
+middleware stacks and servers that require `Send` services. Like [`crate::SendFuture`],
+the wrapper remembers the thread it was created on and panics if `poll_ready`, `call`,
+or `Drop` run on any other thread. A wrong-thread drop leaks the service rather than
+dropping it there, and during unwinding only leaks it.
+
+The futures returned by the wrapped service are wrapped in [`crate::SendFuture`], so
+they too are `Send` and are checked when polled.
+
+This module is only available with the `tower` feature.
+
+# Example
+
+```rust
+use send_cells::SendCell;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+// A service that is not Send
+struct LocalService {
+    _handle: Rc<()>,
+}
+
+impl tower_service::Service<u32> for LocalService {
+    type Response = u32;
+    type Error = ();
+    type Future = std::future::Ready<Result<u32, ()>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: u32) -> Self::Future {
+        std::future::ready(Ok(request * 2))
+    }
+}
+
+let service = SendCell::new(LocalService { _handle: Rc::new(()) }).into_service();
+
+// The wrapper is Send, so it satisfies Send-bounded middleware
+fn requires_send<T: Send>(_: T) {}
+requires_send(service);
+```
+*/
+
+use crate::SendCell;
+use crate::send_cell::{SendFuture, drop_on_origin};
+use crate::sys::{Described, ThreadId};
+use crate::unsafe_send_cell::UnsafeSendCell;
+use std::mem::ManuallyDrop;
+use std::task::{Context, Poll};
+
+impl<T> SendCell<T> {
+    /// Converts the cell into a `tower` service that implements Send with runtime thread
+    /// checking.
+    ///
+    /// This method consumes the `SendCell` and returns a [`SendService`] that forwards
+    /// `tower_service::Service` to `T`. The service will panic if used from a different
+    /// thread than the one where the original `SendCell` was created.
+    ///
+    /// This method is only available with the `tower` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    ///
+    /// let cell = SendCell::new(std::rc::Rc::new(()));
+    /// let service = cell.into_service();
+    ///
+    /// fn assert_send<T: Send>(_: T) {}
+    /// assert_send(service);
+    /// ```
+    pub fn into_service(self) -> SendService<T> {
+        let (inner, thread_id) = self.into_parts();
+        SendService {
+            inner: ManuallyDrop::new(inner),
+            thread_id,
+        }
+    }
+}
+
+/// A `tower` service wrapper that implements Send with runtime thread checking.
+///
+/// `SendService<S>` wraps a service of type `S` and provides a `Send` implementation
+/// with runtime thread checking. It implements `tower_service::Service` by forwarding
+/// to `S`, checking the thread on every call, and wraps the response futures in
+/// [`SendFuture`].
+///
+/// A `SendService` is created with [`SendCell::into_service`].
+///
+/// # Panics
+///
+/// `poll_ready`, `call`, polling the returned futures, and `Drop` will panic if called
+/// from a different thread than the one where the original `SendCell` was created.
+#[derive(Debug)]
+pub struct SendService<S> {
+    // Never dropped on the wrong thread; see the Drop impl
+    inner: ManuallyDrop<UnsafeSendCell<S>>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: SendService implements Send by providing runtime thread checking.
+// The wrapped service may not be Send, but we ensure safety by panicking
+// if it is used or dropped on the wrong thread.
+unsafe impl<S> Send for SendService<S> {}
+
+impl<S> SendService<S> {
+    /// Checks the current thread and returns a mutable reference to the wrapped service.
+    fn checked_mut(&mut self) -> &mut S {
+        let current = crate::sys::current_id();
+        if crate::instrument::checked(self.thread_id != current) {
+            crate::sys::violation(format_args!(
+                "SendService<{}> used from incorrect thread (origin thread {}, current thread {})",
+                std::any::type_name::<S>(),
+                Described(self.thread_id),
+                Described(current)
+            ));
+        }
+        // SAFETY: Thread verified above.
+        unsafe { self.inner.get_mut() }
+    }
+}
+
+impl<S: tower_service::Service<Request>, Request> tower_service::Service<Request>
+    for SendService<S>
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = SendFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.checked_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The future is created on the checked thread, so it is bound to the same thread
+        SendCell::new(self.checked_mut().call(request)).into_future()
+    }
+}
+
+impl<S> Drop for SendService<S> {
+    fn drop(&mut self) {
+        // SAFETY: `inner` is never used again.
+        unsafe { drop_on_origin(&mut self.inner, self.thread_id, "SendService") }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{RawWaker, RawWakerVTable, Waker};
+    use tower_service::Service;
+
+    // A service that is NOT Send because it contains Rc<T>
+    struct NonSendService {
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl Service<u32> for NonSendService {
+        type Response = u32;
+        type Error = ();
+        type Future = Pin<Box<dyn Future<Output = Result<u32, ()>>>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: u32) -> Self::Future {
+            self.calls.set(self.calls.get() + 1);
+            let calls = Rc::clone(&self.calls);
+            Box::pin(async move { Ok(request + calls.get()) })
+        }
+    }
+
+    // Helper function to create a no-op waker for testing
+    fn noop_waker() -> Waker {
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        unsafe { Waker::from_raw(raw_waker) }
+    }
+
+    // Helper function to verify a type implements Send
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_service_functionality() {
+        let calls = Rc::new(Cell::new(0));
+        let mut service = SendCell::new(NonSendService {
+            calls: Rc::clone(&calls),
+        })
+        .into_service();
+        assert_send(&service);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(service.poll_ready(&mut cx), Poll::Ready(Ok(())));
+
+        let mut future = service.call(10);
+        assert_send(&future);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(Ok(11)));
+        assert_eq!(calls.get(), 1);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_send_service_cross_thread_panic() {
+        use crate::sys::thread;
+
+        // A service without drop glue, so only the call itself is checked
+        struct ZeroService;
+        impl Service<()> for ZeroService {
+            type Response = ();
+            type Error = ();
+            type Future = std::future::Ready<Result<(), ()>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _request: ()) -> Self::Future {
+                std::future::ready(Ok(()))
+            }
+        }
+
+        let mut service = SendCell::new(ZeroService).into_service();
+        let handle = thread::spawn(move || {
+            let waker = noop_waker();
+            let mut cx = Context::from_waker(&waker);
+            let _ = service.poll_ready(&mut cx);
+        });
+        assert!(
+            handle.join().is_err(),
+            "Expected thread to panic when using SendService from incorrect thread"
+        );
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_send_service_cross_thread_drop() {
+        use crate::sys::thread;
+
+        // The wrong-thread panic in Drop must not drop the service on the wrong thread
+        let calls = Rc::new(Cell::new(0));
+        let service = SendCell::new(NonSendService {
+            calls: Rc::clone(&calls),
+        })
+        .into_service();
+        assert!(thread::spawn(move || drop(service)).join().is_err());
+        assert_eq!(Rc::strong_count(&calls), 2);
+
+        // Dropped while unwinding, a second panic would abort, so it is only leaked
+        let service = SendCell::new(NonSendService {
+            calls: Rc::clone(&calls),
+        })
+        .into_service();
+        let result = thread::spawn(move || {
+            let _service = service;
+            panic!("original panic");
+        })
+        .join();
+        assert_eq!(
+            *result.unwrap_err().downcast::<&str>().unwrap(),
+            "original panic"
+        );
+        assert_eq!(Rc::strong_count(&calls), 3);
+    }
+}
@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A [`Waker`] that can be used from any thread, forwarding wakes to a non-Send callback.
+
+A [`std::task::Waker`] must be `Send` and `Sync`, but callback-driven runtimes often have
+a natural wake-up action that is not: re-scheduling a task on a single-threaded executor,
+posting to a UI event loop through an `Rc` handle, or calling into a JavaScript promise.
+[`SendWaker<F>`] bridges the two:
+
+- [`SendWaker::waker`] returns an ordinary `Waker` that may be cloned, sent, and woken
+  from any thread. Waking it just sends a message through a channel.
+- On the thread that created the `SendWaker`, [`SendWaker::run_pending`] or
+  [`SendWaker::wait`] receives those messages and calls the callback.
+
+The callback itself never leaves its origin thread; like [`crate::SendCell`], the
+`SendWaker` can be moved to another thread but panics if used there.
+
+This pairs naturally with [`crate::SendFuture`]: a non-Send future can be polled on its
+origin thread with a `Waker` that other threads are free to wake.
+
+# Examples
+
+```rust
+use send_cells::SendWaker;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::thread;
+
+let polls = Rc::new(Cell::new(0));
+let send_waker = SendWaker::new({
+    let polls = Rc::clone(&polls);
+    move || polls.set(polls.get() + 1)
+});
+
+// The Waker can be woken from another thread...
+let waker = send_waker.waker();
+thread::spawn(move || waker.wake()).join().unwrap();
+
+// ...and the callback runs back on this thread
+assert!(send_waker.run_pending());
+assert_eq!(polls.get(), 1);
+```
+*/
+
+use crate::send_cell::SendCell;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::task::{Wake, Waker};
+
+/// Forwards wakes of a `Send` [`Waker`] to a callback on the thread that created it.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// Wakes are coalesced: however many times the `Waker` was woken since the callback last
+/// ran, the callback runs once. This matches the semantics of [`Waker::wake`], where
+/// waking a task that is already scheduled has no further effect.
+///
+/// # Panics
+///
+/// [`Self::run_pending`] and [`Self::wait`] panic if called from a different thread than
+/// the one where the `SendWaker` was created, as does dropping it there if the callback
+/// needs to be dropped. The `Waker`s it hands out can be used from any thread.
+pub struct SendWaker<F> {
+    callback: SendCell<F>,
+    receiver: Receiver<()>,
+    waker: Waker,
+}
+
+/// The `Send + Sync` half of a [`SendWaker`], backing the handed-out `Waker`s.
+struct WakeSender {
+    sender: Sender<()>,
+}
+
+impl Wake for WakeSender {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // If the SendWaker is gone there is nothing to wake, so a send error is ignored
+        let _ = self.sender.send(());
+    }
+}
+
+impl<F: Fn()> SendWaker<F> {
+    /// Creates a new `SendWaker` that calls `callback` on the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendWaker;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(());
+    /// let send_waker = SendWaker::new(move || {
+    ///     let _ = &local; // Non-Send state captured by the callback
+    /// });
+    /// send_waker.waker().wake();
+    /// assert!(send_waker.run_pending());
+    /// ```
+    pub fn new(callback: F) -> SendWaker<F> {
+        let (sender, receiver) = channel();
+        SendWaker {
+            callback: SendCell::new(callback),
+            receiver,
+            waker: Waker::from(Arc::new(WakeSender { sender })),
+        }
+    }
+
+    /// Returns a `Waker` that forwards wakes to this `SendWaker`.
+    ///
+    /// The `Waker` can be used from any thread, and remains valid (but does nothing) after
+    /// the `SendWaker` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendWaker;
+    /// use std::task::Context;
+    ///
+    /// let send_waker = SendWaker::new(|| {});
+    /// let waker = send_waker.waker();
+    /// let cx = Context::from_waker(&waker);
+    /// ```
+    #[inline]
+    pub fn waker(&self) -> Waker {
+        self.waker.clone()
+    }
+
+    /// Calls the callback if the `Waker` was woken since the last call, without blocking.
+    ///
+    /// Returns whether the callback was called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendWaker` was
+    /// created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendWaker;
+    ///
+    /// let send_waker = SendWaker::new(|| println!("woken"));
+    /// assert!(!send_waker.run_pending());
+    ///
+    /// send_waker.waker().wake_by_ref();
+    /// send_waker.waker().wake_by_ref();
+    /// // Both wakes are handled by a single call
+    /// assert!(send_waker.run_pending());
+    /// assert!(!send_waker.run_pending());
+    /// ```
+    pub fn run_pending(&self) -> bool {
+        let callback = self.callback.get();
+        if self.receiver.try_iter().count() == 0 {
+            return false;
+        }
+        callback();
+        true
+    }
+
+    /// Blocks the current thread until the `Waker` is woken, then calls the callback.
+    ///
+    /// Returns immediately if a wake is already pending.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a different thread than the one where this `SendWaker` was
+    /// created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendWaker;
+    /// use std::thread;
+    ///
+    /// let send_waker = SendWaker::new(|| {});
+    /// let waker = send_waker.waker();
+    /// thread::spawn(move || waker.wake());
+    ///
+    /// // Returns once the other thread has woken us
+    /// send_waker.wait();
+    /// ```
+    pub fn wait(&self) {
+        let callback = self.callback.get();
+        // The SendWaker holds a Waker, so the sending side is never disconnected
+        self.receiver.recv().expect("wake channel disconnected");
+        // Coalesce any wakes that arrived in the meantime
+        self.receiver.try_iter().for_each(drop);
+        callback();
+    }
+}
+
+impl<F> Debug for SendWaker<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendWaker").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_wakes_are_coalesced() {
+        let calls = Rc::new(Cell::new(0));
+        let send_waker = SendWaker::new({
+            let calls = Rc::clone(&calls);
+            move || calls.set(calls.get() + 1)
+        });
+        assert!(!send_waker.run_pending());
+
+        let waker = send_waker.waker();
+        waker.wake_by_ref();
+        send_waker.waker().wake();
+        assert!(send_waker.run_pending());
+        assert_eq!(calls.get(), 1);
+        assert!(!send_waker.run_pending());
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_waker_outlives_send_waker() {
+        let send_waker = SendWaker::new(|| {});
+        let waker = send_waker.waker();
+        drop(send_waker);
+        // Waking after the SendWaker is gone is a no-op
+        waker.wake();
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let local = Rc::new(());
+        let send_waker = SendWaker::new(move || {
+            let _ = &local;
+        });
+        assert_send(&send_waker);
+    }
+
+    #[test]
+    fn test_wake_from_other_thread() {
+        use crate::sys::thread;
+
+        let calls = Rc::new(Cell::new(0));
+        let send_waker = SendWaker::new({
+            let calls = Rc::clone(&calls);
+            move || calls.set(calls.get() + 1)
+        });
+
+        let waker = send_waker.waker();
+        let handle = thread::spawn(move || waker.wake());
+        send_waker.wait();
+        handle.join().unwrap();
+        assert_eq!(calls.get(), 1);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_cross_thread_panic() {
+        use crate::sys::thread;
+
+        let send_waker = SendWaker::new(|| {});
+        send_waker.waker().wake();
+        let result = thread::spawn(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                send_waker.run_pending();
+            }));
+            // The callback has no drop glue, so dropping here is allowed
+            drop(send_waker);
+            result
+        })
+        .join()
+        .unwrap();
+        assert!(
+            result.is_err(),
+            "Expected panic when running SendWaker callbacks from incorrect thread"
+        );
+    }
+}
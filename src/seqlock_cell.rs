@@ -0,0 +1,408 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A sequence-lock cell for sharing small `Copy` values between threads.
+
+This module provides [`SeqlockCell<T>`], which allows any number of threads to read a
+`Copy` value without taking a lock, while writers get exclusive access. It is intended
+for small, frequently read state such as telemetry counters, configuration snapshots
+or positions, where [`crate::SyncCell`]'s mutex would make readers contend with each
+other.
+
+# How It Works
+
+The cell stores a sequence number next to the value:
+
+- A writer makes the sequence number odd, copies the new value in, and makes it even
+  again.
+- A reader records the sequence number, copies the value out, and checks the sequence
+  number again. If a write happened in between (or was in progress), the copy is
+  discarded and the read is retried.
+
+Readers never write to shared memory, so they never contend with each other and never
+block a writer. A reader only retries when it overlaps a write.
+
+Under Miri, which reports the discarded copies as data races, readers take the writer
+lock instead.
+
+# Comparison with SyncCell
+
+| Type | Readers | Writers | Access |
+|------|---------|---------|--------|
+| [`crate::SyncCell`] | Serialized by a mutex | Serialized by a mutex | Any `T` through closures |
+| [`SeqlockCell`] | Lock-free, never block each other | Exclusive | `T: Copy` by value |
+
+# Examples
+
+```rust
+use send_cells::SeqlockCell;
+use std::sync::Arc;
+use std::thread;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position {
+    x: f64,
+    y: f64,
+}
+
+let position = Arc::new(SeqlockCell::new(Position { x: 0.0, y: 0.0 }));
+
+let writer = Arc::clone(&position);
+thread::spawn(move || {
+    writer.set(Position { x: 1.0, y: 2.0 });
+}).join().unwrap();
+
+// Readers always observe a complete value, never half of one write and half of another
+assert_eq!(position.get(), Position { x: 1.0, y: 2.0 });
+```
+*/
+
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+#[cfg(not(miri))]
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering, fence};
+
+/// A cell offering lock-free reads and exclusive writes of a `Copy` value.
+///
+/// `SeqlockCell<T>` wraps a value of type `T` and protects it with a sequence lock.
+/// Reads copy the value out without locking and retry if they overlap a write;
+/// writes are exclusive with respect to other writes.
+///
+/// Because readers may retry, values should be small: a read copies the whole value
+/// each attempt.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SeqlockCell;
+///
+/// let cell = SeqlockCell::new(42u64);
+/// assert_eq!(cell.get(), 42);
+///
+/// cell.set(100);
+/// assert_eq!(cell.get(), 100);
+///
+/// cell.update(|value| *value += 1);
+/// assert_eq!(cell.get(), 101);
+/// ```
+///
+/// The constructor is `const`, so a `SeqlockCell` can live in a `static`:
+///
+/// ```rust
+/// use send_cells::SeqlockCell;
+///
+/// static FRAME_TIME: SeqlockCell<(u64, f32)> = SeqlockCell::new((0, 0.0));
+///
+/// FRAME_TIME.set((1, 16.6));
+/// assert_eq!(FRAME_TIME.get(), (1, 16.6));
+/// ```
+///
+/// # Thread Safety
+///
+/// The cell implements `Sync` when the wrapped type implements `Send`, since values
+/// are copied into and out of the cell from any thread.
+pub struct SeqlockCell<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: All shared access to the value goes through the sequence lock: writers are
+// exclusive, and readers only return copies that were validated against the sequence
+// number. Values move between threads by copy, which requires T: Send.
+unsafe impl<T: Copy + Send> Sync for SeqlockCell<T> {}
+
+/// Restores an even sequence number when a write finishes, even if it unwinds.
+struct WriteGuard<'a> {
+    seq: &'a AtomicUsize,
+    start: usize,
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        self.seq
+            .store(self.start.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl<T: Copy> SeqlockCell<T> {
+    /// Creates a new `SeqlockCell` wrapping the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let cell = SeqlockCell::new([0u8; 16]);
+    /// assert_eq!(cell.get(), [0u8; 16]);
+    /// ```
+    #[inline]
+    pub const fn new(value: T) -> SeqlockCell<T> {
+        SeqlockCell {
+            seq: AtomicUsize::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a copy of the wrapped value.
+    ///
+    /// This never takes a lock. If a write is in progress, or completes while the value
+    /// is being copied, the read is retried, so the returned value is always one that
+    /// some writer stored in full.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let cell = SeqlockCell::new((1, 2));
+    /// assert_eq!(cell.get(), (1, 2));
+    /// ```
+    pub fn get(&self) -> T {
+        // Miri reports the optimistic copy below as a data race, even though a racing copy
+        // is always discarded, so readers take the writer lock instead when running under it
+        #[cfg(miri)]
+        {
+            let _guard = self.lock_writer();
+            // SAFETY: We hold the writer lock, so nothing else accesses the value.
+            return unsafe { *self.value.get() };
+        }
+        #[cfg(not(miri))]
+        loop {
+            let start = self.seq.load(Ordering::Acquire);
+            if start & 1 == 1 {
+                // A writer is active
+                core::hint::spin_loop();
+                continue;
+            }
+            // SAFETY: The copy is made into MaybeUninit, so a torn value is never
+            // interpreted as a T. It is only assumed initialized once the sequence
+            // number shows no write overlapped the copy. Volatile reads keep the
+            // compiler from eliding or splitting the copy around the sequence checks;
+            // this is the same approach crossbeam's AtomicCell takes for its seqlock.
+            let copy =
+                unsafe { core::ptr::read_volatile(self.value.get().cast::<MaybeUninit<T>>()) };
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == start {
+                // SAFETY: No write overlapped the copy, so it is a complete value.
+                return unsafe { copy.assume_init() };
+            }
+        }
+    }
+
+    /// Replaces the wrapped value.
+    ///
+    /// Writers are exclusive: concurrent calls to `set` or [`Self::update`] wait for
+    /// each other. Readers are never blocked, but retry if they overlap the write.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let cell = SeqlockCell::new(1);
+    /// cell.set(2);
+    /// assert_eq!(cell.get(), 2);
+    /// ```
+    pub fn set(&self, value: T) {
+        let _guard = self.lock_writer();
+        // SAFETY: We hold the writer lock, so no other writer is active. Readers may
+        // copy concurrently, but they discard any copy that overlaps this write.
+        unsafe { core::ptr::write_volatile(self.value.get(), value) };
+    }
+
+    /// Updates the wrapped value in place through a closure.
+    ///
+    /// The closure receives a mutable reference to a copy of the current value; the
+    /// modified copy is stored when the closure returns. Returns the new value.
+    ///
+    /// The closure runs while the writer lock is held, so it should be short and must
+    /// not access the same cell (which would spin forever).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let cell = SeqlockCell::new((0u32, 0u32));
+    /// let new = cell.update(|(frames, _)| *frames += 1);
+    /// assert_eq!(new, (1, 0));
+    /// ```
+    pub fn update(&self, f: impl FnOnce(&mut T)) -> T {
+        let _guard = self.lock_writer();
+        // SAFETY: We hold the writer lock, so only we write the value.
+        let mut value = unsafe { core::ptr::read_volatile(self.value.get()) };
+        f(&mut value);
+        // SAFETY: As in `set`.
+        unsafe { core::ptr::write_volatile(self.value.get(), value) };
+        value
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking or retrying is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let mut cell = SeqlockCell::new(1);
+    /// *cell.get_mut() = 2;
+    /// assert_eq!(cell.get(), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SeqlockCell;
+    ///
+    /// let cell = SeqlockCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Makes the sequence number odd, waiting for any active writer to finish first.
+    fn lock_writer(&self) -> WriteGuard<'_> {
+        loop {
+            let start = self.seq.load(Ordering::Relaxed);
+            if start & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(
+                        start,
+                        start.wrapping_add(1),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                // Ensure readers see the odd sequence number before any data is written
+                fence(Ordering::Release);
+                return WriteGuard {
+                    seq: &self.seq,
+                    start,
+                };
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<T: Copy + Debug> Debug for SeqlockCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: Copy + Default> Default for SeqlockCell<T> {
+    fn default() -> SeqlockCell<T> {
+        SeqlockCell::new(T::default())
+    }
+}
+
+impl<T: Copy> From<T> for SeqlockCell<T> {
+    fn from(value: T) -> Self {
+        SeqlockCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let mut cell = SeqlockCell::new(42);
+        assert_eq!(cell.get(), 42);
+
+        cell.set(100);
+        assert_eq!(cell.get(), 100);
+
+        assert_eq!(cell.update(|value| *value += 1), 101);
+        *cell.get_mut() += 1;
+        assert_eq!(cell.into_inner(), 102);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let cell = SeqlockCell::new(42);
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_update_panic_releases_writer() {
+        let cell = SeqlockCell::new(1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cell.update(|_| panic!("test panic"));
+        }));
+        assert!(result.is_err());
+
+        // The value is unchanged and the cell is usable for both readers and writers
+        assert_eq!(cell.get(), 1);
+        cell.set(2);
+        assert_eq!(cell.get(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_no_torn_reads() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+        use std::sync::atomic::AtomicBool;
+
+        let cell = Arc::new(SeqlockCell::new([0u64; 8]));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let writer = {
+            let cell = Arc::clone(&cell);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                for i in 1..=10_000u64 {
+                    cell.set([i; 8]);
+                }
+                done.store(true, Ordering::Release);
+            })
+        };
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    while !done.load(Ordering::Acquire) {
+                        let value = cell.get();
+                        assert!(value.iter().all(|v| *v == value[0]), "torn read");
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(cell.get(), [10_000; 8]);
+    }
+}
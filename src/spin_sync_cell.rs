@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A spinlock-backed cell for sharing non-Sync types without operating system support.
+
+This module provides [`SpinSyncCell<T>`], which offers the closure-based API of
+[`crate::SyncCell`] on top of [`SpinRawMutex`]. It is written against `core` alone, for
+bare-metal and kernel-adjacent code where there is no operating system to block a thread
+on, and it can be placed in a `static` because its constructor is `const`.
+
+# Comparison with SyncCell
+
+| Type | Waiting | Poisoning | Extras |
+|------|---------|-----------|--------|
+| [`crate::SyncCell`] | Blocks the thread | Yes | Condition waits, instrumentation, deadlock detection |
+| [`SpinSyncCell`] | Spins | No | `const` constructor |
+
+A `SyncCell` can also spin, through
+`SyncCell::with_raw_mutex(value, SpinRawMutex::INIT)`, but it still depends on `std` for
+its other features.
+
+# Examples
+
+```rust
+use send_cells::SpinSyncCell;
+
+static EVENTS: SpinSyncCell<[u32; 4]> = SpinSyncCell::new([0; 4]);
+
+EVENTS.with_mut(|events| events[1] += 1);
+assert_eq!(EVENTS.with(|events| events[1]), 1);
+```
+*/
+
+use crate::raw_mutex::{RawMutex, SpinRawMutex};
+use core::fmt::{Debug, Formatter};
+
+/// A guard that gives access to the value of a [`SpinSyncCell`] until it is dropped.
+///
+/// Returned by [`SpinSyncCell::lock`].
+pub type SpinGuard<'a, T> = lock_api::MutexGuard<'a, SpinRawMutex, T>;
+
+/// A cell that shares a value between threads behind a spinlock.
+///
+/// `SpinSyncCell<T>` is `Sync` whenever `T: Send`, like [`crate::SyncCell`], and gives
+/// access to the value through closures that run while the lock is held. Threads waiting
+/// for the lock spin rather than block, so closures should be short.
+///
+/// Unlike `SyncCell`, the cell is never poisoned: if a closure panics, the lock is released
+/// and the value is left as the closure left it. (Bare-metal targets usually abort on
+/// panic, in which case this never comes up.)
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SpinSyncCell;
+/// use std::cell::Cell;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// // Cell is Send but not Sync
+/// let cell = Arc::new(SpinSyncCell::new(Cell::new(0)));
+///
+/// let cell_clone = Arc::clone(&cell);
+/// thread::spawn(move || {
+///     cell_clone.with(|c| c.set(c.get() + 1));
+/// }).join().unwrap();
+///
+/// assert_eq!(cell.with(|c| c.get()), 1);
+/// ```
+pub struct SpinSyncCell<T> {
+    mutex: lock_api::Mutex<SpinRawMutex, T>,
+}
+
+impl<T> SpinSyncCell<T> {
+    /// Creates a new `SpinSyncCell` wrapping the given value.
+    ///
+    /// The constructor is `const`, so the cell can be used in a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// static COUNTER: SpinSyncCell<u64> = SpinSyncCell::new(0);
+    ///
+    /// COUNTER.with_mut(|count| *count += 1);
+    /// assert_eq!(COUNTER.with(|count| *count), 1);
+    /// ```
+    #[inline]
+    pub const fn new(value: T) -> SpinSyncCell<T> {
+        SpinSyncCell {
+            mutex: lock_api::Mutex::from_raw(SpinRawMutex::INIT, value),
+        }
+    }
+
+    /// Accesses the wrapped value through a closure while holding the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let cell = SpinSyncCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.with(|vec| vec.len()), 3);
+    /// ```
+    #[inline]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.mutex.lock())
+    }
+
+    /// Mutably accesses the wrapped value through a closure while holding the lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let cell = SpinSyncCell::new(vec![1, 2, 3]);
+    /// cell.with_mut(|vec| vec.push(4));
+    /// assert_eq!(cell.with(|vec| vec.len()), 4);
+    /// ```
+    #[inline]
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.mutex.lock())
+    }
+
+    /// Mutably accesses the wrapped value through a closure if the lock is free, without
+    /// spinning.
+    ///
+    /// Returns `None` if another thread holds the lock. This is useful in interrupt
+    /// handlers, which must not spin on a lock the code they interrupted may hold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let cell = SpinSyncCell::new(1);
+    /// assert_eq!(cell.try_with_mut(|value| { *value += 1; *value }), Some(2));
+    ///
+    /// let guard = cell.lock();
+    /// assert_eq!(cell.try_with_mut(|value| *value), None);
+    /// drop(guard);
+    /// ```
+    #[inline]
+    pub fn try_with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.mutex.try_lock().map(|mut guard| f(&mut guard))
+    }
+
+    /// Locks the cell and returns a guard that gives access to the value until it is
+    /// dropped.
+    ///
+    /// Prefer [`Self::with`] and [`Self::with_mut`], which cannot hold the lock for longer
+    /// than the closure runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let cell = SpinSyncCell::new(vec![1]);
+    /// let mut guard = cell.lock();
+    /// guard.push(2);
+    /// assert_eq!(*guard, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn lock(&self) -> SpinGuard<'_, T> {
+        self.mutex.lock()
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let mut cell = SpinSyncCell::new(1);
+    /// *cell.get_mut() = 2;
+    /// assert_eq!(cell.with(|value| *value), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.mutex.get_mut()
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SpinSyncCell;
+    ///
+    /// let cell = SpinSyncCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.mutex.into_inner()
+    }
+}
+
+impl<T: Debug> Debug for SpinSyncCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // Spinning here could hang a Debug call made while the lock is held
+        match self.mutex.try_lock() {
+            Some(guard) => (*guard).fmt(f),
+            None => f.write_str("<locked>"),
+        }
+    }
+}
+
+impl<T: Default> Default for SpinSyncCell<T> {
+    fn default() -> SpinSyncCell<T> {
+        SpinSyncCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for SpinSyncCell<T> {
+    fn from(value: T) -> Self {
+        SpinSyncCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let mut cell = SpinSyncCell::new(42);
+        assert_eq!(cell.with(|value| *value), 42);
+        cell.with_mut(|value| *value += 1);
+        assert_eq!(cell.try_with_mut(|value| *value), Some(43));
+
+        let guard = cell.lock();
+        assert_eq!(cell.try_with_mut(|value| *value), None);
+        assert_eq!(format!("{cell:?}"), "<locked>");
+        drop(guard);
+        assert_eq!(format!("{cell:?}"), "43");
+
+        *cell.get_mut() += 1;
+        assert_eq!(cell.into_inner(), 44);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        // Cell is Send but not Sync
+        let cell = SpinSyncCell::new(std::cell::Cell::new(0));
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    //no unwind on wasm!
+    #[test]
+    fn test_panic_releases_lock() {
+        let cell = SpinSyncCell::new(1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cell.with_mut(|value| {
+                *value = 2;
+                panic!("test panic");
+            });
+        }));
+        assert!(result.is_err());
+
+        // Not poisoned: the value is as the closure left it
+        assert_eq!(cell.with(|value| *value), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concurrent_access() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cell = Arc::new(SpinSyncCell::new(0u32));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || (0..1000).for_each(|_| cell.with_mut(|v| *v += 1)))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cell.with(|v| *v), 4000);
+    }
+}
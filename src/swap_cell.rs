@@ -0,0 +1,318 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A read-mostly cell where readers take cheap snapshots and writers publish whole new values.
+
+This module provides [`SwapCell<T>`], built on the `arc-swap` crate. Instead of handing
+out references guarded by a lock, the cell stores an [`Arc<T>`]:
+
+- Readers call [`SwapCell::load`] to get an `Arc<T>` snapshot. The snapshot stays valid
+  (and unchanged) for as long as the reader keeps it, no matter what writers do.
+- Writers call [`SwapCell::store`] or [`SwapCell::update`] to publish a completely new
+  value. Readers holding an older snapshot are unaffected.
+
+Because published values are shared by every reader, `SwapCell<T>` is only `Sync` when
+`T: Send + Sync`. The value itself can be assembled however you like, including with
+non-Sync scratch state, as long as what gets published is shareable.
+
+# Performance
+
+Internally the current `Arc` is an `arc_swap::ArcSwap`, an atomic pointer. Loading a
+snapshot is lock-free: readers never wait for each other or for writers, and a writer
+never waits for readers to finish using their snapshots. Compared to [`crate::SyncCell`]
+or [`crate::SyncRwCell`], no lock is taken at all, let alone held while your code runs.
+
+# Examples
+
+```rust
+use send_cells::SwapCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+let config = Arc::new(SwapCell::new(HashMap::from([("mode", "fast")])));
+
+let snapshot = config.load();
+
+// Publish a new configuration
+let writer = Arc::clone(&config);
+thread::spawn(move || {
+    writer.store(HashMap::from([("mode", "safe")]));
+}).join().unwrap();
+
+// The old snapshot is unchanged; new loads see the new value
+assert_eq!(snapshot["mode"], "fast");
+assert_eq!(config.load()["mode"], "safe");
+```
+*/
+
+use arc_swap::{ArcSwap, Guard};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// A cell holding an `Arc<T>` that readers snapshot and writers replace.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SwapCell;
+///
+/// let cell = SwapCell::new(vec![1, 2, 3]);
+/// let before = cell.load();
+///
+/// cell.update(|vec| {
+///     let mut vec = vec.clone();
+///     vec.push(4);
+///     vec
+/// });
+///
+/// assert_eq!(*before, vec![1, 2, 3]);
+/// assert_eq!(*cell.load(), vec![1, 2, 3, 4]);
+/// ```
+///
+/// # Thread Safety
+///
+/// The cell implements `Send` and `Sync` when the wrapped type implements both `Send`
+/// and `Sync`, since every reader shares the published value.
+pub struct SwapCell<T> {
+    current: ArcSwap<T>,
+}
+
+impl<T> SwapCell<T> {
+    /// Creates a new `SwapCell` publishing the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new(42);
+    /// assert_eq!(*cell.load(), 42);
+    /// ```
+    #[inline]
+    pub fn new(value: T) -> SwapCell<T> {
+        SwapCell::from_arc(Arc::new(value))
+    }
+
+    /// Creates a new `SwapCell` publishing an existing `Arc`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    /// use std::sync::Arc;
+    ///
+    /// let value = Arc::new(42);
+    /// let cell = SwapCell::from_arc(Arc::clone(&value));
+    /// assert!(Arc::ptr_eq(&cell.load(), &value));
+    /// ```
+    #[inline]
+    pub fn from_arc(value: Arc<T>) -> SwapCell<T> {
+        SwapCell {
+            current: ArcSwap::new(value),
+        }
+    }
+
+    /// Returns a snapshot of the currently published value.
+    ///
+    /// The snapshot is an ordinary `Arc<T>`; it keeps the value alive and unchanged
+    /// even if a writer publishes a new value afterwards. Loading never blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new("first");
+    /// let snapshot = cell.load();
+    /// cell.store("second");
+    ///
+    /// assert_eq!(*snapshot, "first");
+    /// assert_eq!(*cell.load(), "second");
+    /// ```
+    #[inline]
+    pub fn load(&self) -> Arc<T> {
+        self.current.load_full()
+    }
+
+    /// Publishes a new value.
+    ///
+    /// Existing snapshots are unaffected. The previous value is dropped once the last
+    /// snapshot of it is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new(1);
+    /// cell.store(2);
+    /// assert_eq!(*cell.load(), 2);
+    /// ```
+    #[inline]
+    pub fn store(&self, value: T) {
+        drop(self.swap_arc(Arc::new(value)));
+    }
+
+    /// Publishes a new value, returning the previously published one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new(1);
+    /// let previous = cell.swap(2);
+    /// assert_eq!(*previous, 1);
+    /// assert_eq!(*cell.load(), 2);
+    /// ```
+    #[inline]
+    pub fn swap(&self, value: T) -> Arc<T> {
+        self.swap_arc(Arc::new(value))
+    }
+
+    /// Publishes an existing `Arc`, returning the previously published one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    /// use std::sync::Arc;
+    ///
+    /// let cell = SwapCell::new(1);
+    /// let previous = cell.swap_arc(Arc::new(2));
+    /// assert_eq!(*previous, 1);
+    /// ```
+    pub fn swap_arc(&self, value: Arc<T>) -> Arc<T> {
+        self.current.swap(value)
+    }
+
+    /// Publishes a value computed from the current one, returning the new snapshot.
+    ///
+    /// The closure runs without holding any lock, so readers and other writers are never
+    /// blocked by it, and the new value is published with a compare-and-swap. If another writer publishes a value while the closure runs, the
+    /// closure is called again with that newer value, so that no update is lost. The
+    /// closure may therefore run more than once and should not have side effects.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new(1);
+    /// let new = cell.update(|value| value + 1);
+    /// assert_eq!(*new, 2);
+    /// ```
+    pub fn update(&self, mut f: impl FnMut(&T) -> T) -> Arc<T> {
+        let mut current = self.load();
+        loop {
+            let new = Arc::new(f(&current));
+            let published = self.current.compare_and_swap(&current, Arc::clone(&new));
+            if Arc::ptr_eq(&published, &current) {
+                return new;
+            }
+            // Another writer got there first; retry against its value
+            current = Guard::into_inner(published);
+        }
+    }
+
+    /// Consumes the cell and returns the currently published value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SwapCell;
+    ///
+    /// let cell = SwapCell::new(42);
+    /// assert_eq!(*cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> Arc<T> {
+        self.current.into_inner()
+    }
+}
+
+impl<T: Debug> Debug for SwapCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.load().fmt(f)
+    }
+}
+
+impl<T: Default> Default for SwapCell<T> {
+    fn default() -> SwapCell<T> {
+        SwapCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for SwapCell<T> {
+    fn from(value: T) -> Self {
+        SwapCell::new(value)
+    }
+}
+
+impl<T> From<Arc<T>> for SwapCell<T> {
+    fn from(value: Arc<T>) -> Self {
+        SwapCell::from_arc(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let cell = SwapCell::new(1);
+        let snapshot = cell.load();
+
+        cell.store(2);
+        assert_eq!(*snapshot, 1);
+        assert_eq!(*cell.load(), 2);
+
+        assert_eq!(*cell.swap(3), 2);
+        assert_eq!(*cell.update(|value| value * 2), 6);
+        assert_eq!(*cell.into_inner(), 6);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let cell = SwapCell::new(42);
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        use crate::sys::thread;
+
+        let cell = Arc::new(SwapCell::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        cell.update(|value| value + 1);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        // No update is lost
+        assert_eq!(*cell.load(), 400);
+    }
+}
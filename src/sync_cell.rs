@@ -16,11 +16,46 @@ mutex management.
 
 # Thread Safety Model
 
-[`SyncCell<T>`] uses a [`std::sync::Mutex`] internally to provide thread-safe access:
+[`SyncCell<T>`] uses a mutex internally to provide thread-safe access:
 - All access is through closures that receive references to the wrapped value
 - Mutex guards are automatically acquired and released by the closure methods
 - This prevents holding guards across await points or other blocking operations
 - The wrapped value itself doesn't need to implement `Sync`
+- The wrapped value lives inside a `lock_api::Mutex`, so it can only be reached through
+  a lock guard
+
+For call sites that need to pass the borrow through helper functions, [`SyncCell::lock`]
+returns a [`SyncGuard`] instead. The guard is `!Send`, so a future holding it across an
+`.await` is itself `!Send` and cannot be spawned on a multi-threaded executor.
+
+The mutex is a [`lock_api::RawMutex`] chosen by the second type parameter,
+`SyncCell<T, M = DefaultRawMutex>`. The default is built on `std::sync`, or comes from
+`parking_lot` with the `parking_lot` feature; [`SyncCell::with_raw_mutex`] accepts any other
+implementation, such as a spinlock or a critical section. See [`crate::raw_mutex`].
+Neither default is fair; [`SyncCell::new_fair`] creates a cell that hands the lock to
+waiting threads in first-in, first-out order.
+
+Poisoning is tracked by the cell itself, so it works the same with every raw mutex: a
+panic inside a closure marks the cell as poisoned, as with [`std::sync::Mutex`]. This is
+deliberate even for mutexes like `parking_lot`'s that don't poison: features are
+additive, so a dependency enabling `parking_lot` must not change how cells behave after
+a panic. The cost is one relaxed load and two checks of `std::thread::panicking` per
+acquisition; the flag is only written when a closure panics.
+
+With the `tracing` feature, [`SyncCell::with`] and [`SyncCell::with_mut`] run inside a
+`TRACE` span, and lock acquisition, contention and holds longer than 10ms are reported as
+events.
+
+With the `deadlock-detection` feature, debug builds track which cells each thread holds.
+Locking a cell the thread already holds, or locking two cells in the opposite order to
+an earlier acquisition, panics with a description of the cycle instead of risking a hang.
+
+A [`std::sync::Condvar`], allocated the first time a thread waits, lets threads sleep
+until the wrapped value reaches a condition with [`SyncCell::wait_until`],
+[`SyncCell::wait_until_mut`] or [`SyncCell::wait_while`]. While a thread sleeps, every release of the lock wakes it to
+re-check its condition, so writers don't need to do anything special;
+[`SyncCell::notify_one`] and [`SyncCell::notify_all`] wake waiters explicitly, for
+conditions that depend on more than the wrapped value.
 
 # Examples
 
@@ -90,10 +125,33 @@ cell.with_mut(|vec| {
 ```
 */
 
-use crate::unsafe_sync_cell::UnsafeSyncCell;
-use std::fmt::{Debug, Formatter};
-use std::hash::{Hash, Hasher};
-use std::sync::Mutex;
+use crate::deadlock::LockId;
+#[cfg(feature = "metrics")]
+use crate::instrument::LockStats;
+use crate::instrument::{Held, Recorder};
+#[cfg(feature = "std")]
+use crate::raw_mutex::FairRawMutex;
+use crate::raw_mutex::{DefaultRawMutex, GuardNoSend, RawMutex};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+#[cfg(all(feature = "std", not(loom)))]
+use core::sync::atomic::{AtomicPtr, AtomicUsize};
+use lock_api::{MappedMutexGuard, MutexGuard};
+// loom's equivalents let `loom::model` explore the interleavings of SyncCell's own state
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+#[cfg(loom)]
+use loom::sync::{Condvar, Mutex};
+#[cfg(feature = "std")]
+use std::sync::PoisonError;
+#[cfg(all(feature = "std", not(loom)))]
+use std::sync::{Condvar, Mutex};
 
 /// A runtime-checked cell that allows sharing non-Sync types between threads.
 ///
@@ -149,11 +207,15 @@ use std::sync::Mutex;
 ///
 /// # Thread Safety
 ///
-/// The cell implements both `Send` and `Sync` when the wrapped type implements `Send`.
-/// Access is always protected by the internal mutex, ensuring thread safety.
-pub struct SyncCell<T> {
-    inner: UnsafeSyncCell<T>,
-    mutex: Mutex<()>,
+/// The cell implements both `Send` and `Sync` when the wrapped type implements `Send`
+/// (and the raw mutex `M` is `Send` and `Sync`, as the default is). Access is always
+/// protected by the internal mutex, ensuring thread safety.
+pub struct SyncCell<T, M = DefaultRawMutex> {
+    mutex: lock_api::Mutex<M, T>,
+    poisoned: AtomicBool,
+    waiters: Waiters,
+    lock_id: LockId,
+    recorder: Recorder,
 }
 
 impl<T> SyncCell<T> {
@@ -177,9 +239,76 @@ impl<T> SyncCell<T> {
     /// ```
     #[inline]
     pub fn new(value: T) -> SyncCell<T> {
+        SyncCell::with_raw_mutex(value, DefaultRawMutex::INIT)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> SyncCell<T, FairRawMutex> {
+    /// Creates a new `SyncCell` whose lock is handed to waiting threads in the order they
+    /// arrived.
+    ///
+    /// By default, a thread that releases the lock and immediately takes it again usually
+    /// wins against threads already waiting, so under heavy contention one busy thread can
+    /// starve the others. A fair cell queues every acquisition instead, at some cost in
+    /// throughput. See [`FairRawMutex`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::collections::VecDeque;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let queue = Arc::new(SyncCell::new_fair(VecDeque::new()));
+    ///
+    /// // A producer that locks in a tight loop does not starve the consumer
+    /// let producer = {
+    ///     let queue = Arc::clone(&queue);
+    ///     thread::spawn(move || {
+    ///         for job in 0..100 {
+    ///             queue.with_mut(|queue| queue.push_back(job));
+    ///         }
+    ///     })
+    /// };
+    /// let mut consumed = 0;
+    /// while consumed < 100 {
+    ///     if queue.with_mut(|queue| queue.pop_front()).is_some() {
+    ///         consumed += 1;
+    ///     }
+    /// }
+    /// producer.join().unwrap();
+    /// ```
+    #[inline]
+    pub fn new_fair(value: T) -> SyncCell<T, FairRawMutex> {
+        SyncCell::with_raw_mutex(value, FairRawMutex::INIT)
+    }
+}
+
+impl<T, M: RawMutex> SyncCell<T, M> {
+    /// Creates a new `SyncCell` protected by the given raw mutex.
+    ///
+    /// This allows plugging in a lock other than [`DefaultRawMutex`], such as a spinlock
+    /// or a critical section; see [`crate::raw_mutex`] for an example.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use send_cells::raw_mutex::{RawMutex, SpinRawMutex};
+    ///
+    /// let cell: SyncCell<i32, SpinRawMutex> = SyncCell::with_raw_mutex(42, SpinRawMutex::INIT);
+    /// assert_eq!(cell.with(|value| *value), 42);
+    /// ```
+    #[inline]
+    pub fn with_raw_mutex(value: T, raw_mutex: M) -> SyncCell<T, M> {
         SyncCell {
-            inner: UnsafeSyncCell::new(value),
-            mutex: Mutex::new(()),
+            mutex: lock_api::Mutex::from_raw(raw_mutex, value),
+            poisoned: AtomicBool::new(false),
+            waiters: Waiters::default(),
+            lock_id: LockId::new(),
+            recorder: Recorder::new(),
         }
     }
 
@@ -215,9 +344,9 @@ impl<T> SyncCell<T> {
     /// ```
     #[inline]
     pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
-        let _guard = self.mutex.lock().unwrap();
-        let value = unsafe { self.inner.get() };
-        f(value)
+        #[cfg(feature = "tracing")]
+        let _span = crate::instrument::access_span("with", core::any::type_name::<Self>());
+        f(&self.acquire())
     }
 
     /// Accesses the underlying value mutably through a synchronous closure.
@@ -253,10 +382,715 @@ impl<T> SyncCell<T> {
     /// ```
     #[inline]
     pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
-        let _guard = self.mutex.lock().unwrap();
-        //safe since we hold the lock
-        let value = unsafe { self.inner.get_mut_unchecked() };
-        f(value)
+        #[cfg(feature = "tracing")]
+        let _span = crate::instrument::access_span("with_mut", core::any::type_name::<Self>());
+        f(&mut self.acquire())
+    }
+
+    /// Like [`Self::with`], but reports poisoning instead of panicking.
+    ///
+    /// The closure runs even if the mutex is poisoned, so it can inspect the value left
+    /// behind by the panicking thread. Its result is returned in `Ok` if the mutex was not
+    /// poisoned, and wrapped in a [`PoisonError`] otherwise; use
+    /// [`PoisonError::into_inner`] to recover it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let cell = SyncCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.with_checked(|vec| vec.len()).ok(), Some(3));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_checked<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, PoisonError<R>> {
+        let (guard, poisoned) = self.acquire_checked();
+        let result = f(&guard);
+        if poisoned {
+            Err(PoisonError::new(result))
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Like [`Self::with_mut`], but reports poisoning instead of panicking.
+    ///
+    /// This is the mutable counterpart of [`Self::with_checked`]. Since the closure runs
+    /// even if the mutex is poisoned, it can repair the value before the poison is cleared
+    /// with [`Self::clear_poison`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::panic::AssertUnwindSafe;
+    /// use std::sync::PoisonError;
+    ///
+    /// let cell = SyncCell::new(Vec::new());
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     cell.with_mut(|vec| {
+    ///         vec.push(1);
+    ///         panic!("interrupted halfway");
+    ///     })
+    /// }));
+    ///
+    /// // Repair the value, then clear the poison
+    /// let len = cell
+    ///     .with_mut_checked(|vec| {
+    ///         vec.clear();
+    ///         vec.len()
+    ///     })
+    ///     .unwrap_or_else(PoisonError::into_inner);
+    /// assert_eq!(len, 0);
+    /// cell.clear_poison();
+    /// assert!(!cell.is_poisoned());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_mut_checked<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, PoisonError<R>> {
+        let (mut guard, poisoned) = self.acquire_checked();
+        let result = f(&mut guard);
+        if poisoned {
+            Err(PoisonError::new(result))
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Returns whether the cell is poisoned.
+    ///
+    /// The cell is poisoned when a closure panics while holding its mutex, for example inside
+    /// [`Self::with_mut`]. While it is poisoned, [`Self::with`], [`Self::with_mut`] and
+    /// the other locking methods panic; [`Self::with_checked`] and
+    /// [`Self::with_mut_checked`] return an error instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::panic::AssertUnwindSafe;
+    ///
+    /// let cell = SyncCell::new(0);
+    /// assert!(!cell.is_poisoned());
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| cell.with(|_| panic!())));
+    /// if cell.is_poisoned() {
+    ///     cell.clear_poison();
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    /// Clears the poisoned state of the cell.
+    ///
+    /// Call this once the value has been checked or repaired, for example with
+    /// [`Self::with_mut_checked`], to make the panicking locking methods usable again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::panic::AssertUnwindSafe;
+    ///
+    /// let cell = SyncCell::new(0);
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| cell.with(|_| panic!())));
+    /// cell.clear_poison();
+    /// assert_eq!(cell.with(|value| *value), 0);
+    /// ```
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    /// Replaces the wrapped value under the lock, returning the old value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::rc::Rc;
+    ///
+    /// let cell = SyncCell::new(Rc::new("old"));
+    /// let old = cell.replace(Rc::new("new"));
+    /// assert_eq!(*old, "old");
+    /// assert_eq!(cell.with(|value| **value), "new");
+    /// ```
+    #[inline]
+    pub fn replace(&self, value: T) -> T {
+        self.with_mut(|old| core::mem::replace(old, value))
+    }
+
+    /// Takes the wrapped value under the lock, leaving `T::default()` in its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let cell = SyncCell::new(vec![1, 2, 3]);
+    /// let taken = cell.take();
+    /// assert_eq!(taken, [1, 2, 3]);
+    /// assert!(cell.with(|vec| vec.is_empty()));
+    /// ```
+    #[inline]
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /// Returns a clone of the wrapped value, taken under the lock.
+    ///
+    /// This is a convenient way to snapshot shared state. To clone the whole cell instead,
+    /// use `SyncCell`'s [`Clone`] implementation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let cell = SyncCell::new(vec![1, 2]);
+    /// let snapshot = cell.get_cloned();
+    /// cell.with_mut(|vec| vec.push(3));
+    /// assert_eq!(snapshot, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn get_cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Swaps the values of two cells, holding both locks.
+    ///
+    /// The locks are acquired in a fixed order, so two threads swapping the same pair of
+    /// cells in opposite directions cannot deadlock. Swapping a cell with itself does
+    /// nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let a = SyncCell::new("a");
+    /// let b = SyncCell::new("b");
+    /// a.swap(&b);
+    /// assert_eq!(a.into_inner(), "b");
+    /// assert_eq!(b.into_inner(), "a");
+    /// ```
+    pub fn swap(&self, other: &SyncCell<T, M>) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+        let (mut a, mut b) = self.lock_pair(other);
+        core::mem::swap(&mut *a, &mut *b);
+    }
+
+    /// Runs `f` with mutable access to the values of two cells, holding both locks.
+    ///
+    /// Like [`Self::swap`], the locks are acquired in a fixed order, so an operation that
+    /// spans two cells cannot deadlock against the same operation in the other direction.
+    /// The cells may wrap different types.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` are the same cell, or if either cell is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let checking = SyncCell::new(100);
+    /// let history = SyncCell::new(Vec::new());
+    ///
+    /// SyncCell::with_both(&checking, &history, |balance, history| {
+    ///     *balance -= 30;
+    ///     history.push(-30);
+    /// });
+    /// assert_eq!(checking.into_inner(), 70);
+    /// assert_eq!(history.into_inner(), [-30]);
+    /// ```
+    pub fn with_both<U, N: RawMutex, R>(
+        &self,
+        other: &SyncCell<U, N>,
+        f: impl FnOnce(&mut T, &mut U) -> R,
+    ) -> R {
+        assert!(
+            !core::ptr::addr_eq(self, other),
+            "SyncCell::with_both called with the same cell twice"
+        );
+        let (mut a, mut b) = self.lock_pair(other);
+        f(&mut a, &mut b)
+    }
+
+    /// Locks several cells at once, returning their guards in the order of `cells`.
+    ///
+    /// The locks are acquired in a fixed order, so two threads locking overlapping sets of
+    /// cells cannot deadlock, whatever order they list them in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the same cell appears more than once, or if any cell is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let accounts = [SyncCell::new(10), SyncCell::new(20), SyncCell::new(30)];
+    /// let mut guards = SyncCell::lock_many(&[&accounts[2], &accounts[0]]);
+    /// *guards[0] -= 5;
+    /// *guards[1] += 5;
+    /// drop(guards);
+    /// assert_eq!(accounts.map(SyncCell::into_inner), [15, 20, 25]);
+    /// ```
+    pub fn lock_many<'a>(cells: &[&'a SyncCell<T, M>]) -> Vec<SyncGuard<'a, T, M>> {
+        let mut order: Vec<usize> = (0..cells.len()).collect();
+        order.sort_by_key(|&index| cells[index] as *const Self);
+        assert!(
+            !order
+                .windows(2)
+                .any(|pair| core::ptr::eq(cells[pair[0]], cells[pair[1]])),
+            "SyncCell::lock_many called with the same cell more than once"
+        );
+        let mut guards: Vec<Option<SyncGuard<'a, T, M>>> = cells.iter().map(|_| None).collect();
+        let mut outer = Vec::with_capacity(cells.len());
+        for index in order {
+            let cell = cells[index];
+            guards[index] = Some(cell.acquire_nested(&outer));
+            outer.push(&cell.lock_id);
+        }
+        guards.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Runs `f` on the values of two cells, holding both locks.
+    ///
+    /// Comparing a cell with itself locks it only once.
+    fn with_pair<R>(&self, other: &SyncCell<T, M>, f: impl FnOnce(&T, &T) -> R) -> R {
+        if core::ptr::eq(self, other) {
+            return self.with(|value| f(value, value));
+        }
+        let (a, b) = self.lock_pair(other);
+        f(&a, &b)
+    }
+
+    /// Locks the mutexes of two distinct cells in address order, so that concurrent calls
+    /// on the same pair cannot deadlock. The guards are returned in argument order.
+    fn lock_pair<'a, U, N: RawMutex>(
+        &'a self,
+        other: &'a SyncCell<U, N>,
+    ) -> (SyncGuard<'a, T, M>, SyncGuard<'a, U, N>) {
+        debug_assert!(!core::ptr::addr_eq(self, other));
+        if (self as *const Self).cast::<()>() < (other as *const SyncCell<U, N>).cast::<()>() {
+            let first = self.acquire();
+            (first, other.acquire_nested(&[&self.lock_id]))
+        } else {
+            let second = other.acquire();
+            (self.acquire_nested(&[&other.lock_id]), second)
+        }
+    }
+
+    /// Locks the internal mutex, panicking if the cell is poisoned.
+    fn acquire(&self) -> SyncGuard<'_, T, M> {
+        self.acquire_nested(&[])
+    }
+
+    /// Like [`Self::acquire`], where `outer` are cells already locked by
+    /// [`Self::lock_pair`] or [`Self::lock_many`]. Their order is fixed by address rather
+    /// than by the caller, so deadlock detection does not record it.
+    fn acquire_nested(&self, outer: &[&LockId]) -> SyncGuard<'_, T, M> {
+        let (guard, poisoned) = self.lock_raw(outer);
+        if poisoned {
+            panic!("SyncCell<{}> is poisoned", core::any::type_name::<T>());
+        }
+        guard
+    }
+
+    /// Locks the internal mutex, also returning whether the cell was poisoned.
+    #[cfg(feature = "std")]
+    fn acquire_checked(&self) -> (SyncGuard<'_, T, M>, bool) {
+        self.lock_raw(&[])
+    }
+
+    fn lock_raw(&self, outer: &[&LockId]) -> (SyncGuard<'_, T, M>, bool) {
+        self.lock_id
+            .before_lock(core::any::type_name::<Self>(), outer);
+        let (guard, held) = self
+            .recorder
+            .lock(&self.mutex, core::any::type_name::<Self>());
+        (self.guard(guard, held), self.is_poisoned())
+    }
+
+    /// Locks the internal mutex if it is free, ignoring poisoning.
+    fn try_acquire(&self) -> Option<SyncGuard<'_, T, M>> {
+        let guard = self.mutex.try_lock()?;
+        Some(self.guard(
+            guard,
+            self.recorder.acquired(core::any::type_name::<Self>()),
+        ))
+    }
+
+    /// Wraps a lock on the internal mutex into a guard that also keeps the cell's
+    /// bookkeeping: poisoning, deadlock detection and instrumentation.
+    fn guard<'a>(&'a self, guard: MutexGuard<'a, M, T>, held: Held) -> SyncGuard<'a, T, M> {
+        self.lock_id.after_lock();
+        SyncGuard {
+            state: LockState {
+                poisoned: &self.poisoned,
+                lock_id: &self.lock_id,
+                recorder: &self.recorder,
+                waiters: &self.waiters,
+                wake_waiters: true,
+                held,
+                cell: core::any::type_name::<Self>(),
+                panicking: panicking(),
+                _not_send: PhantomData,
+            },
+            guard: MutexGuard::map(guard, |value| value),
+        }
+    }
+
+    /// Blocks until notified, releasing the lock while asleep.
+    #[cfg(feature = "std")]
+    fn wait<'a>(&'a self, mut guard: SyncGuard<'a, T, M>) -> SyncGuard<'a, T, M> {
+        assert!(
+            crate::sys::can_block() != Some(false),
+            "SyncCell cannot wait on the browser main thread, which is not allowed to block"
+        );
+        // The generation is read and the sleeper counted before the lock is released, so a
+        // thread that changes the value afterwards always wakes us
+        let waiters = self.waiters.get_or_init();
+        let generation = waiters
+            .generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let seen = *generation;
+        waiters.sleeping.fetch_add(1, Ordering::Relaxed);
+        // Waiters re-checking a condition that still holds must not wake each other, or
+        // they would keep each other awake forever
+        guard.state.wake_waiters = false;
+        drop(guard);
+        let mut generation = generation;
+        while *generation == seen {
+            generation = waiters
+                .condvar
+                .wait(generation)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        drop(generation);
+        waiters.sleeping.fetch_sub(1, Ordering::Relaxed);
+        self.acquire()
+    }
+
+    /// Acquires the internal mutex and returns a guard that dereferences to the wrapped value.
+    ///
+    /// This is an alternative to [`Self::with`] and [`Self::with_mut`] for code that needs
+    /// to pass the borrow to helper functions. The mutex is released when the guard is
+    /// dropped.
+    ///
+    /// The guard is `!Send`: it must be dropped on the thread that acquired it, and a
+    /// future that holds it across an `.await` is `!Send` as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// fn push_twice(vec: &mut Vec<i32>, value: i32) {
+    ///     vec.push(value);
+    ///     vec.push(value);
+    /// }
+    ///
+    /// let cell = SyncCell::new(Vec::new());
+    /// let mut guard = cell.lock();
+    /// push_twice(&mut guard, 7);
+    /// assert_eq!(*guard, [7, 7]);
+    /// ```
+    pub fn lock(&self) -> SyncGuard<'_, T, M> {
+        self.acquire()
+    }
+
+    /// Blocks the current thread until `predicate` holds, then runs `f` under the same lock.
+    ///
+    /// The internal mutex is acquired and `predicate` is evaluated against the wrapped
+    /// value. Until it returns `true`, the thread sleeps on an internal condition variable
+    /// (releasing the mutex) and re-evaluates `predicate` whenever another thread releases
+    /// the lock, so writers wake it just by changing the value through [`Self::with_mut`] or
+    /// any other method. Once `predicate` returns `true`, `f` is called without releasing
+    /// the mutex, so the state it observes is the state that ended the wait.
+    ///
+    /// This replaces polling the cell with [`Self::with`] in a loop: the thread uses no CPU
+    /// while it sleeps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// Also panics if called on the browser main thread on `wasm32` with threads, which is
+    /// not allowed to block; wait from a web worker instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let progress = Arc::new(SyncCell::new(0));
+    ///
+    /// let worker = Arc::clone(&progress);
+    /// thread::spawn(move || {
+    ///     for _ in 0..10 {
+    ///         worker.with_mut(|progress| *progress += 10);
+    ///     }
+    /// });
+    ///
+    /// let done = progress.wait_until(|progress| *progress == 100, |progress| *progress);
+    /// assert_eq!(done, 100);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_until<R>(
+        &self,
+        mut predicate: impl FnMut(&T) -> bool,
+        f: impl FnOnce(&T) -> R,
+    ) -> R {
+        let mut guard = self.acquire();
+        while !predicate(&guard) {
+            guard = self.wait(guard);
+        }
+        f(&guard)
+    }
+
+    /// Blocks the current thread until `predicate` holds, then runs `f` with mutable access
+    /// under the same lock.
+    ///
+    /// This is [`Self::wait_until`] for consumers that change the value once it is ready,
+    /// such as taking an item off a queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// Also panics if called on the browser main thread on `wasm32` with threads, which is
+    /// not allowed to block; wait from a web worker instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::collections::VecDeque;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let queue = Arc::new(SyncCell::new(VecDeque::new()));
+    ///
+    /// let producer = Arc::clone(&queue);
+    /// thread::spawn(move || producer.with_mut(|queue| queue.push_back("job")));
+    ///
+    /// let job = queue.wait_until_mut(|queue| !queue.is_empty(), |queue| queue.pop_front());
+    /// assert_eq!(job, Some("job"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_until_mut<R>(
+        &self,
+        mut predicate: impl FnMut(&T) -> bool,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> R {
+        let mut guard = self.acquire();
+        while !predicate(&guard) {
+            guard = self.wait(guard);
+        }
+        f(&mut guard)
+    }
+
+    /// Blocks the current thread while `condition` holds, then runs `f` under the same lock.
+    ///
+    /// The internal mutex is acquired and `condition` is evaluated against the wrapped
+    /// value. While it returns `true`, the thread sleeps on an internal condition variable
+    /// (releasing the mutex) until another thread releases the lock or calls
+    /// [`Self::notify_one`] or [`Self::notify_all`], and then re-evaluates `condition`. Once
+    /// `condition` returns `false`, `f` is called without releasing the mutex, so the state
+    /// it observes is the state that ended the wait.
+    ///
+    /// Unlike [`Self::wait_until_mut`], the condition receives `&mut T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// Also panics if called on the browser main thread on `wasm32` with threads, which is
+    /// not allowed to block; wait from a web worker instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::collections::VecDeque;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let queue = Arc::new(SyncCell::new(VecDeque::new()));
+    ///
+    /// let producer = Arc::clone(&queue);
+    /// thread::spawn(move || {
+    ///     producer.with_mut(|queue| queue.push_back("job"));
+    /// });
+    ///
+    /// // Sleep until the producer has pushed something, then take it
+    /// let job = queue.wait_while(|queue| queue.is_empty(), |queue| queue.pop_front());
+    /// assert_eq!(job, Some("job"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn wait_while<R>(
+        &self,
+        mut condition: impl FnMut(&mut T) -> bool,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> R {
+        let mut guard = self.acquire();
+        while condition(&mut guard) {
+            guard = self.wait(guard);
+        }
+        f(&mut guard)
+    }
+
+    /// Wakes up one thread blocked in [`Self::wait_until`], [`Self::wait_until_mut`] or
+    /// [`Self::wait_while`] on this cell.
+    ///
+    /// The woken thread re-evaluates its condition, and goes back to sleep if it
+    /// still holds. Releasing the lock already wakes waiters, so this is only needed when
+    /// a condition depends on state outside the cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let cell = SyncCell::new(false);
+    /// cell.with_mut(|ready| *ready = true);
+    /// cell.notify_one();
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn notify_one(&self) {
+        self.waiters.notify_one();
+    }
+
+    /// Wakes up all threads blocked in [`Self::wait_until`], [`Self::wait_until_mut`] or
+    /// [`Self::wait_while`] on this cell.
+    ///
+    /// Each woken thread re-evaluates its condition, and goes back to sleep if it
+    /// still holds. Releasing the lock already wakes waiters, so this is only needed when
+    /// a condition depends on state outside the cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::thread;
+    ///
+    /// let jobs = Arc::new(SyncCell::new(Vec::<u32>::new()));
+    /// let shutdown = Arc::new(AtomicBool::new(false));
+    ///
+    /// let workers: Vec<_> = (0..3)
+    ///     .map(|_| {
+    ///         let jobs = Arc::clone(&jobs);
+    ///         let shutdown = Arc::clone(&shutdown);
+    ///         thread::spawn(move || {
+    ///             jobs.wait_until(|jobs| !jobs.is_empty() || shutdown.load(Ordering::SeqCst), |_| ())
+    ///         })
+    ///     })
+    ///     .collect();
+    ///
+    /// // The flag lives outside the cell, so waiters have to be woken explicitly
+    /// shutdown.store(true, Ordering::SeqCst);
+    /// jobs.notify_all();
+    ///
+    /// for worker in workers {
+    ///     worker.join().unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn notify_all(&self) {
+        self.waiters.notify_all();
+    }
+
+    /// Returns contention statistics for this cell's mutex.
+    ///
+    /// The counts and times cover every acquisition since the cell was created, including
+    /// those by [`Self::lock`] and the trait implementations. Comparing the statistics of
+    /// different cells shows which ones are hot.
+    ///
+    /// This method is only available with the `metrics` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let cell = SyncCell::new(Vec::new());
+    /// for i in 0..3 {
+    ///     cell.with_mut(|vec| vec.push(i));
+    /// }
+    /// let stats = cell.stats();
+    /// assert_eq!(stats.acquisitions, 3);
+    /// assert!(stats.max_hold <= stats.total_hold);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> LockStats {
+        self.recorder.stats()
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking is needed. As with
+    /// [`Self::into_inner`], the value is returned even if the cell is poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncCell;
+    ///
+    /// let mut cell = SyncCell::new(vec![1, 2]);
+    /// cell.get_mut().push(3);
+    /// assert_eq!(cell.into_inner(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.mutex.get_mut()
     }
 
     /// Consumes the cell and returns the wrapped value.
@@ -278,7 +1112,7 @@ impl<T> SyncCell<T> {
     /// ```
     #[inline]
     pub fn into_inner(self) -> T {
-        self.inner.into_inner()
+        self.mutex.into_inner()
     }
 
     /// Unsafely accesses the underlying value without acquiring the mutex.
@@ -288,7 +1122,7 @@ impl<T> SyncCell<T> {
     /// The caller must ensure that:
     /// - No other thread is currently accessing the value
     /// - The access is properly synchronized through external means
-    /// - The mutex is not poisoned
+    /// - The cell is not poisoned
     ///
     /// This method bypasses all synchronization and may lead to data races
     /// if used incorrectly.
@@ -305,10 +1139,8 @@ impl<T> SyncCell<T> {
     /// assert_eq!(*value, 42);
     /// ```
     pub unsafe fn with_unchecked(&self) -> &T {
-        unsafe {
-            // SAFETY: Caller guarantees proper synchronization
-            self.inner.get()
-        }
+        // SAFETY: Caller guarantees proper synchronization
+        unsafe { &*self.mutex.data_ptr() }
     }
 
     /// Unsafely accesses the underlying value mutably without acquiring the mutex.
@@ -318,7 +1150,7 @@ impl<T> SyncCell<T> {
     /// The caller must ensure that:
     /// - No other thread is currently accessing the value
     /// - The access is properly synchronized through external means  
-    /// - The mutex is not poisoned
+    /// - The cell is not poisoned
     /// - No other references (mutable or immutable) to the value exist
     ///
     /// This method bypasses all synchronization and may lead to data races
@@ -342,20 +1174,297 @@ impl<T> SyncCell<T> {
     /// ```
     #[allow(clippy::mut_from_ref)]
     pub unsafe fn with_mut_unchecked(&self) -> &mut T {
-        unsafe {
-            // SAFETY: Caller guarantees proper synchronization
-            self.inner.get_mut_unchecked()
+        // SAFETY: Caller guarantees proper synchronization
+        unsafe { &mut *self.mutex.data_ptr() }
+    }
+}
+
+// SyncCell<T, M> is Send when T: Send and M: Send, and Sync when T: Send and M: Sync,
+// because the value lives inside a `lock_api::Mutex<M, T>`, which has those bounds.
+
+/// Lets threads sleep until a [`SyncCell`] changes, whatever its raw mutex is.
+///
+/// Most cells are never waited on, so the state is only allocated by the first thread
+/// that sleeps, and a cell without it stays one pointer larger than its mutex.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct Waiters {
+    state: AtomicPtr<WaitState>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct WaitState {
+    // Bumped to wake sleepers; a std condition variable can't wait on `M` directly
+    generation: Mutex<u64>,
+    condvar: Condvar,
+    // Threads asleep in `SyncCell::wait`; releases only pay for a wakeup when non-zero
+    sleeping: AtomicUsize,
+}
+
+#[cfg(feature = "std")]
+impl Waiters {
+    /// The wait state, if any thread has ever slept on the cell.
+    #[inline]
+    fn get(&self) -> Option<&WaitState> {
+        // SAFETY: a non-null pointer came from `Box::into_raw` in `get_or_init`, and is
+        // only freed when the cell is dropped, which can't happen while it is borrowed
+        unsafe { self.state.load(Ordering::Acquire).as_ref() }
+    }
+
+    fn get_or_init(&self) -> &WaitState {
+        if let Some(state) = self.get() {
+            return state;
+        }
+        let new = Box::into_raw(Box::new(WaitState::default()));
+        match self.state.compare_exchange(
+            core::ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            // SAFETY: the pointer is now owned by the cell, see `get`
+            Ok(_) => unsafe { &*new },
+            Err(existing) => {
+                // SAFETY: `new` was never published, so this is its only owner, and
+                // `existing` is owned by the cell like in `get`
+                unsafe {
+                    drop(Box::from_raw(new));
+                    &*existing
+                }
+            }
         }
     }
+
+    // A sleeper allocates the state before it registers, so when there is none there is
+    // nobody to wake
+    fn notify_one(&self) {
+        if let Some(state) = self.get() {
+            state.notify_one();
+        }
+    }
+
+    fn notify_all(&self) {
+        if let Some(state) = self.get() {
+            state.notify_all();
+        }
+    }
+
+    /// Called while the cell's lock is held, which orders it with sleepers registering.
+    #[inline]
+    fn lock_released(&self) {
+        if let Some(state) = self.get() {
+            if state.sleeping.load(Ordering::Relaxed) != 0 {
+                state.notify_all();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for Waiters {
+    fn drop(&mut self) {
+        let state = self.state.load(Ordering::Relaxed);
+        if !state.is_null() {
+            // SAFETY: the cell is being dropped, so nothing borrows the state any more
+            drop(unsafe { Box::from_raw(state) });
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl WaitState {
+    fn advance_generation(&self) {
+        let mut generation = self
+            .generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *generation = generation.wrapping_add(1);
+    }
+
+    fn notify_one(&self) {
+        self.advance_generation();
+        self.condvar.notify_one();
+    }
+
+    fn notify_all(&self) {
+        self.advance_generation();
+        self.condvar.notify_all();
+    }
+}
+
+/// Without `std` there is nothing to sleep on, so no thread ever waits.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Default)]
+struct Waiters {}
+
+#[cfg(not(feature = "std"))]
+impl Waiters {
+    #[inline]
+    fn lock_released(&self) {}
 }
 
-// SAFETY: SyncCell<T> can be Send when T: Send because the mutex ensures
-// that only one thread can access the inner value at a time.
-unsafe impl<T: Send> Send for SyncCell<T> {}
+/// Whether the current thread is unwinding from a panic. Without `std` this can't be
+/// known, so cells are never poisoned there.
+#[inline]
+fn panicking() -> bool {
+    #[cfg(feature = "std")]
+    return std::thread::panicking();
+    #[cfg(not(feature = "std"))]
+    return false;
+}
 
-// SAFETY: SyncCell<T> can be Sync when T: Send because the mutex provides
-// the necessary synchronization for shared access across threads.
-unsafe impl<T: Send> Sync for SyncCell<T> {}
+/// The bookkeeping for one acquisition of a [`SyncCell`]'s mutex. It poisons the cell if
+/// the lock is released during a panic, like the guard of [`std::sync::Mutex`].
+struct LockState<'a> {
+    poisoned: &'a AtomicBool,
+    lock_id: &'a LockId,
+    recorder: &'a Recorder,
+    waiters: &'a Waiters,
+    // Whether releasing the lock wakes threads waiting for the value to change
+    wake_waiters: bool,
+    held: Held,
+    cell: &'static str,
+    // Whether the thread was already panicking when the lock was acquired
+    panicking: bool,
+    // Keeps the guard !Send even if `M::GuardMarker` would allow sending it
+    _not_send: PhantomData<GuardNoSend>,
+}
+
+impl Drop for LockState<'_> {
+    fn drop(&mut self) {
+        if !self.panicking && panicking() {
+            self.poisoned.store(true, Ordering::Relaxed);
+        }
+        if self.wake_waiters {
+            self.waiters.lock_released();
+        }
+        self.lock_id.after_unlock();
+        self.recorder.released(&self.held, self.cell);
+    }
+}
+
+/// An RAII guard giving access to the value in a [`SyncCell`], returned by [`SyncCell::lock`].
+///
+/// The cell's mutex is released when the guard is dropped. The guard is `!Send`, like
+/// [`std::sync::MutexGuard`].
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncCell;
+/// use std::cell::RefCell;
+///
+/// let cell = SyncCell::new(RefCell::new(1));
+/// {
+///     let guard = cell.lock();
+///     *guard.borrow_mut() += 1;
+/// } // Lock released here
+/// assert_eq!(cell.with(|value| *value.borrow()), 2);
+/// ```
+///
+/// The guard cannot be sent to another thread:
+///
+/// ```compile_fail
+/// use send_cells::SyncCell;
+///
+/// fn assert_send<T: Send>(_: T) {}
+///
+/// let cell = SyncCell::new(42);
+/// assert_send(cell.lock());
+/// ```
+#[must_use = "if unused the SyncCell will immediately unlock"]
+pub struct SyncGuard<'a, T: ?Sized, M: RawMutex = DefaultRawMutex> {
+    // Dropped before the mutex guard, so poisoning is recorded before the mutex unlocks
+    state: LockState<'a>,
+    guard: MappedMutexGuard<'a, M, T>,
+}
+
+impl<'a, T: ?Sized, M: RawMutex> SyncGuard<'a, T, M> {
+    /// Narrows the guard to a part of the locked value, such as a field.
+    ///
+    /// The mutex stays locked until the returned guard is dropped. This is an associated
+    /// function rather than a method, so that it does not shadow a `map` method on `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{SyncCell, SyncGuard};
+    ///
+    /// struct State {
+    ///     name: String,
+    ///     hits: u32,
+    /// }
+    ///
+    /// let cell = SyncCell::new(State { name: "index".to_string(), hits: 0 });
+    /// let mut hits = SyncGuard::map(cell.lock(), |state| &mut state.hits);
+    /// *hits += 1;
+    /// assert_eq!(*hits, 1);
+    /// ```
+    pub fn map<U: ?Sized>(orig: Self, f: impl FnOnce(&mut T) -> &mut U) -> SyncGuard<'a, U, M> {
+        SyncGuard {
+            state: orig.state,
+            guard: MappedMutexGuard::map(orig.guard, f),
+        }
+    }
+
+    /// Narrows the guard to a part of the locked value, if `f` returns `Some`.
+    ///
+    /// If `f` returns `None`, the original guard is returned in `Err`, still holding the
+    /// lock. Like [`Self::map`], this is an associated function.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::{SyncCell, SyncGuard};
+    /// use std::collections::HashMap;
+    ///
+    /// let cell = SyncCell::new(HashMap::from([("a", 1)]));
+    ///
+    /// let mut a = SyncGuard::try_map(cell.lock(), |map| map.get_mut("a")).unwrap();
+    /// *a += 1;
+    /// drop(a);
+    ///
+    /// let missing = SyncGuard::try_map(cell.lock(), |map| map.get_mut("b"));
+    /// let guard = missing.unwrap_err();
+    /// assert_eq!(guard["a"], 2);
+    /// ```
+    pub fn try_map<U: ?Sized>(
+        orig: Self,
+        f: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Result<SyncGuard<'a, U, M>, Self> {
+        let SyncGuard { state, guard } = orig;
+        match MappedMutexGuard::try_map(guard, f) {
+            Ok(guard) => Ok(SyncGuard { state, guard }),
+            Err(guard) => Err(SyncGuard { state, guard }),
+        }
+    }
+}
+
+impl<T: ?Sized, M: RawMutex> Deref for SyncGuard<'_, T, M> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized, M: RawMutex> DerefMut for SyncGuard<'_, T, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: Debug + ?Sized, M: RawMutex> Debug for SyncGuard<'_, T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: core::fmt::Display + ?Sized, M: RawMutex> core::fmt::Display for SyncGuard<'_, T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
 
 // ===========================================================================================
 // BOILERPLATE TRAIT IMPLEMENTATIONS
@@ -366,63 +1475,70 @@ unsafe impl<T: Send> Sync for SyncCell<T> {}
 // Design Notes:
 // - AsRef/Deref are intentionally NOT implemented because they would require returning references
 //   that outlive the mutex guard, which could lead to deadlocks or use-after-free issues
-// - All implementations use the safe `with()` method for immutable access
+// - All implementations use the safe `with()` method for immutable access, except Debug,
+//   which only tries the lock and prints `<locked>` if it is held
 // - Clone creates a new independent SyncCell to maintain the ownership model
 
 // Basic formatting and construction traits
-impl<T: Debug> Debug for SyncCell<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        self.with(|value| value.fmt(f))
+impl<T: Debug, M: RawMutex> Debug for SyncCell<T, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // Waiting for the lock here would deadlock when formatting a cell whose guard is
+        // held further up the stack. Like std's Mutex, a poisoned value is still shown.
+        let Some(guard) = self.try_acquire() else {
+            return f.write_str("<locked>");
+        };
+        (*guard).fmt(f)
     }
 }
 
-impl<T: std::fmt::Display> std::fmt::Display for SyncCell<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: core::fmt::Display, M: RawMutex> core::fmt::Display for SyncCell<T, M> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.with(|value| value.fmt(f))
     }
 }
 
-impl<T: Default> Default for SyncCell<T> {
-    fn default() -> SyncCell<T> {
-        SyncCell::new(T::default())
+impl<T: Default, M: RawMutex> Default for SyncCell<T, M> {
+    fn default() -> SyncCell<T, M> {
+        SyncCell::with_raw_mutex(T::default(), M::INIT)
     }
 }
 
-impl<T> From<T> for SyncCell<T> {
+impl<T, M: RawMutex> From<T> for SyncCell<T, M> {
     fn from(value: T) -> Self {
-        SyncCell::new(value)
+        SyncCell::with_raw_mutex(value, M::INIT)
     }
 }
 
-// Clone creates a new independent SyncCell with a cloned value
-impl<T: Clone> Clone for SyncCell<T> {
+// Clone creates a new independent SyncCell with a cloned value and a fresh mutex
+impl<T: Clone, M: RawMutex> Clone for SyncCell<T, M> {
     fn clone(&self) -> Self {
-        self.with(|value| SyncCell::new(value.clone()))
+        SyncCell::with_raw_mutex(self.get_cloned(), M::INIT)
     }
 }
 
-// Comparison traits - all use safe closure-based access
-impl<T: PartialEq> PartialEq for SyncCell<T> {
+// Comparison traits - both cells are locked in a deterministic order, so comparing the
+// same pair from two threads in opposite directions cannot deadlock
+impl<T: PartialEq, M: RawMutex> PartialEq for SyncCell<T, M> {
     fn eq(&self, other: &Self) -> bool {
-        self.with(|a| other.with(|b| a == b))
+        self.with_pair(other, |a, b| a == b)
     }
 }
 
-impl<T: Eq> Eq for SyncCell<T> {}
+impl<T: Eq, M: RawMutex> Eq for SyncCell<T, M> {}
 
-impl<T: PartialOrd> PartialOrd for SyncCell<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.with(|a| other.with(|b| a.partial_cmp(b)))
+impl<T: PartialOrd, M: RawMutex> PartialOrd for SyncCell<T, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.with_pair(other, |a, b| a.partial_cmp(b))
     }
 }
 
-impl<T: Ord> Ord for SyncCell<T> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.with(|a| other.with(|b| a.cmp(b)))
+impl<T: Ord, M: RawMutex> Ord for SyncCell<T, M> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.with_pair(other, |a, b| a.cmp(b))
     }
 }
 
-impl<T: Hash> Hash for SyncCell<T> {
+impl<T: Hash, M: RawMutex> Hash for SyncCell<T, M> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.with(|value| value.hash(state))
     }
@@ -432,7 +1548,10 @@ impl<T: Hash> Hash for SyncCell<T> {
 mod tests {
     use super::*;
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_basic_usage() {
         let cell = SyncCell::new(42);
@@ -444,20 +1563,34 @@ mod tests {
         let result = cell.with(|value| *value);
         assert_eq!(result, 100);
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_into_inner() {
         let cell = SyncCell::new(42);
         assert_eq!(cell.into_inner(), 42);
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_debug() {
         let cell = SyncCell::new(42);
         let debug_str = format!("{:?}", cell);
         assert_eq!(debug_str, "42");
+
+        let guard = cell.lock();
+        assert_eq!(format!("{:?}", cell), "<locked>");
+        drop(guard);
+        assert_eq!(format!("{:?}", cell), "42");
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_display() {
         let cell = SyncCell::new(42);
@@ -468,15 +1601,27 @@ mod tests {
         let display_str = format!("{}", cell_str);
         assert_eq!(display_str, "hello world");
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_default() {
         let cell: SyncCell<i32> = SyncCell::default();
         let value = cell.with(|v| *v);
         assert_eq!(value, 0);
+        // Containing structs can derive Default and Debug
+        #[derive(Default, Debug)]
+        struct State {
+            _counter: SyncCell<u32>,
+        }
+        assert_eq!(format!("{:?}", State::default()), "State { _counter: 0 }");
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_from() {
         let cell: SyncCell<i32> = SyncCell::from(42);
@@ -484,7 +1629,10 @@ mod tests {
         assert_eq!(value, 42);
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_clone() {
         let cell = SyncCell::new(42);
@@ -497,7 +1645,10 @@ mod tests {
         assert_eq!(cloned.with(|v| *v), 42); // Clone is independent
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_partial_eq() {
         let cell1 = SyncCell::new(42);
@@ -506,9 +1657,41 @@ mod tests {
 
         assert_eq!(cell1, cell2);
         assert_ne!(cell1, cell3);
+
+        // Comparing a cell with itself must not deadlock
+        #[allow(clippy::eq_op)]
+        let reflexive = cell1 == cell1;
+        assert!(reflexive);
+        let nan = SyncCell::new(f64::NAN);
+        #[allow(clippy::eq_op)]
+        let nan_reflexive = nan == nan;
+        assert!(!nan_reflexive);
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_compare_opposite_directions() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let a = Arc::new(SyncCell::new(0));
+        let b = Arc::new(SyncCell::new(0));
+        let handles: Vec<_> = [
+            (Arc::clone(&a), Arc::clone(&b)),
+            (Arc::clone(&b), Arc::clone(&a)),
+        ]
+        .into_iter()
+        .map(|(x, y)| thread::spawn(move || (0..1000).all(|_| *x == *y)))
+        .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_ord() {
         let cell1 = SyncCell::new(1);
@@ -520,8 +1703,12 @@ mod tests {
         assert!(cell1 < cell3);
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
+    #[allow(clippy::mutable_key_type)]
     fn test_hash() {
         use std::collections::HashMap;
 
@@ -537,7 +1724,10 @@ mod tests {
         assert_eq!(map.len(), 2);
     }
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_send_sync() {
         fn assert_send<T: Send>(_: &T) {}
@@ -547,7 +1737,10 @@ mod tests {
         assert_send(&cell);
         assert_sync(&cell);
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_no_deadlock_on_nested_access() {
         let cell = SyncCell::new(vec![1, 2, 3]);
@@ -567,6 +1760,404 @@ mod tests {
         assert_eq!(new_len, 4);
     }
 
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_lock() {
+        fn append(vec: &mut Vec<i32>, value: i32) {
+            vec.push(value);
+        }
+
+        let cell = SyncCell::new(vec![1]);
+        {
+            let mut guard = cell.lock();
+            append(&mut guard, 2);
+            assert_eq!(guard.len(), 2);
+            // The lock is held while the guard lives
+            assert!(cell.try_acquire().is_none());
+        }
+        assert!(cell.try_acquire().is_some());
+        assert_eq!(format!("{:?}", cell.lock()), "[1, 2]");
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_replace_take_swap() {
+        let a = SyncCell::new(vec![1]);
+        let b = SyncCell::new(vec![2]);
+
+        assert_eq!(a.replace(vec![3]), [1]);
+        a.swap(&b);
+        assert_eq!(a.get_cloned(), [2]);
+        assert_eq!(b.take(), [3]);
+        assert!(b.with(|vec| vec.is_empty()));
+
+        // Swapping with itself must not deadlock
+        a.swap(&a);
+        assert_eq!(a.into_inner(), [2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_swap_opposite_directions() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let a = Arc::new(SyncCell::new(0));
+        let b = Arc::new(SyncCell::new(1));
+        let handles: Vec<_> = [
+            (Arc::clone(&a), Arc::clone(&b)),
+            (Arc::clone(&b), Arc::clone(&a)),
+        ]
+        .into_iter()
+        .map(|(x, y)| thread::spawn(move || (0..1000).for_each(|_| x.swap(&y))))
+        .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        // An even number of swaps in total
+        assert_eq!(a.with(|value| *value), 0);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_with_both() {
+        let names = SyncCell::new(vec!["a"]);
+        let count = SyncCell::new(1usize);
+        names.with_both(&count, |names, count| {
+            names.push("b");
+            *count = names.len();
+        });
+        assert_eq!(count.with(|count| *count), 2);
+        // Locked in address order, whichever cell comes first
+        let total = count.with_both(&names, |count, names| *count + names.len());
+        assert_eq!(total, 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lock_many() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cells = Arc::new([SyncCell::new(0), SyncCell::new(0), SyncCell::new(0)]);
+        let handles: Vec<_> = [[0, 1, 2], [2, 1, 0], [1, 2, 0]]
+            .into_iter()
+            .map(|order| {
+                let cells = Arc::clone(&cells);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        let mut guards = SyncCell::lock_many(&order.map(|index| &cells[index]));
+                        *guards[0] += 1;
+                        *guards[2] -= 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        // Each thread moves 1000 from its last cell to its first
+        let values: Vec<i32> = cells.iter().map(SyncCell::get_cloned).collect();
+        assert_eq!(values, [-1000, 1000, 0]);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_get_mut() {
+        let mut cell = SyncCell::new(std::rc::Rc::new(1));
+        *cell.get_mut() = std::rc::Rc::new(2);
+        assert_eq!(cell.with(|value| **value), 2);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_mapped_guards() {
+        let cell = SyncCell::new((String::from("name"), vec![1, 2, 3]));
+        {
+            let mut slice = SyncGuard::map(cell.lock(), |(_, vec)| vec.as_mut_slice());
+            slice[0] = 10;
+            // Mapping keeps the lock held
+            assert!(cell.try_acquire().is_none());
+        }
+
+        let found = SyncGuard::try_map(cell.lock(), |(_, vec)| vec.iter_mut().find(|v| **v == 2));
+        *found.unwrap() = 20;
+
+        let missing =
+            SyncGuard::try_map(cell.lock(), |(_, vec)| vec.iter_mut().find(|v| **v == 99));
+        let guard = missing.unwrap_err();
+        assert!(cell.try_acquire().is_none());
+        assert_eq!(guard.1, [10, 20, 3]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_wait_while() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cell = Arc::new(SyncCell::new(0));
+
+        let producer = {
+            let cell = Arc::clone(&cell);
+            thread::spawn(move || {
+                for _ in 0..5 {
+                    cell.with_mut(|value| *value += 1);
+                    cell.notify_all();
+                }
+            })
+        };
+
+        let observed = cell.wait_while(|value| *value < 5, |value| *value);
+        assert_eq!(observed, 5);
+        producer.join().unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_wait_until() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+
+        let cell = Arc::new(SyncCell::new(Vec::new()));
+
+        // Consumers and the main thread are woken only by other threads releasing the lock
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    cell.wait_until_mut(|jobs| !jobs.is_empty(), |jobs| jobs.pop().unwrap())
+                })
+            })
+            .collect();
+
+        for job in 0..10 {
+            cell.with_mut(|jobs| jobs.push(job));
+        }
+        let remaining = cell.wait_until(|jobs: &Vec<i32>| jobs.len() == 7, |jobs| jobs.len());
+        assert_eq!(remaining, 7);
+
+        let mut taken: Vec<_> = consumers.into_iter().map(|c| c.join().unwrap()).collect();
+        taken.sort();
+        taken.dedup();
+        assert_eq!(taken.len(), 3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_custom_raw_mutex() {
+        use crate::raw_mutex::GuardSend;
+        use crate::sys::thread;
+        use core::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        // A spinlock, the kind of lock a bare-metal user would plug in
+        struct SpinLock(AtomicBool);
+        unsafe impl RawMutex for SpinLock {
+            #[allow(clippy::declare_interior_mutable_const)]
+            const INIT: SpinLock = SpinLock(AtomicBool::new(false));
+            type GuardMarker = GuardSend;
+            fn lock(&self) {
+                while !self.try_lock() {
+                    std::hint::spin_loop();
+                }
+            }
+            fn try_lock(&self) -> bool {
+                self.0
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            }
+            unsafe fn unlock(&self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        let cell = Arc::new(SyncCell::with_raw_mutex(
+            std::cell::Cell::new(0),
+            SpinLock::INIT,
+        ));
+        let producer = {
+            let cell = Arc::clone(&cell);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    cell.with(|value| value.set(value.get() + 1));
+                    cell.notify_all();
+                }
+            })
+        };
+        let observed = cell.wait_while(|value| value.get() < 100, |value| value.get());
+        assert_eq!(observed, 100);
+        producer.join().unwrap();
+
+        let guard = cell.lock();
+        assert!(cell.try_acquire().is_none());
+        drop(guard);
+        assert_eq!(format!("{:?}", cell.clone()), "Cell { value: 100 }");
+    }
+
+    //no unwind on wasm!
+    #[cfg(all(feature = "deadlock-detection", debug_assertions))]
+    #[test]
+    fn test_deadlock_detection() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let message = |result: std::thread::Result<()>| {
+            let payload = result.unwrap_err();
+            payload.downcast_ref::<String>().unwrap().clone()
+        };
+
+        let cell = SyncCell::new(0);
+        let reentrant = catch_unwind(AssertUnwindSafe(|| cell.with(|_| cell.with(|_| ()))));
+        assert!(message(reentrant).starts_with("Re-entrant lock of"));
+
+        let a = SyncCell::new("a");
+        let b = SyncCell::new("b");
+        a.with(|_| b.with(|_| ()));
+        // The same order again is fine
+        a.with(|_| b.with(|_| ()));
+        let inverted = catch_unwind(AssertUnwindSafe(|| b.with(|_| a.with(|_| ()))));
+        let message = message(inverted);
+        assert!(message.starts_with("Inconsistent lock order"), "{message}");
+        assert!(message.contains("Cycle:"), "{message}");
+
+        // Pairs locked by swap are ordered internally, so either direction is fine
+        let c = SyncCell::new("c");
+        let d = SyncCell::new("d");
+        c.swap(&d);
+        d.swap(&c);
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_stats() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let cell = Arc::new(SyncCell::new(0));
+        let guard = cell.lock();
+        let waiter = {
+            let cell = Arc::clone(&cell);
+            thread::spawn(move || cell.with_mut(|value| *value += 1))
+        };
+        thread::sleep(Duration::from_millis(20));
+        drop(guard);
+        waiter.join().unwrap();
+
+        let stats = cell.stats();
+        assert_eq!(stats.acquisitions, 2);
+        assert_eq!(stats.contended, 1);
+        assert!(stats.max_wait > Duration::ZERO);
+        assert!(stats.max_hold >= Duration::from_millis(20));
+        assert!(stats.total_hold >= stats.max_hold);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        // Records the level and message of every event, and the name of every span
+        #[derive(Clone, Default)]
+        struct Recording(Arc<Mutex<Vec<(Level, String)>>>);
+        struct Message(String);
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+        impl Subscriber for Recording {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let name = span.metadata().name().to_string();
+                self.0.lock().unwrap().push((Level::TRACE, name));
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut message = Message(String::new());
+                event.record(&mut message);
+                let level = *event.metadata().level();
+                self.0.lock().unwrap().push((level, message.0));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recording = Recording::default();
+        tracing::subscriber::with_default(recording.clone(), || {
+            let cell = SyncCell::new(0);
+            cell.with_mut(|value| *value += 1);
+            let guard = cell.lock();
+            std::thread::sleep(crate::instrument::LONG_HOLD);
+            drop(guard);
+        });
+
+        let recorded = recording.0.lock().unwrap().clone();
+        let has =
+            |level: Level, message: &str| recorded.iter().any(|(l, m)| *l == level && m == message);
+        assert!(has(Level::TRACE, "SyncCell access"), "{recorded:?}");
+        assert!(has(Level::TRACE, "acquired SyncCell lock"), "{recorded:?}");
+        assert!(has(Level::TRACE, "released SyncCell lock"), "{recorded:?}");
+        assert!(
+            has(Level::WARN, "SyncCell lock was held for a long time"),
+            "{recorded:?}"
+        );
+    }
+
+    //no unwind on wasm!
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_poison_api() {
+        let cell = SyncCell::new(vec![1]);
+        assert_eq!(cell.with_checked(|vec| vec.len()).ok(), Some(1));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cell.with_mut(|vec| {
+                vec.push(2);
+                panic!("test panic");
+            })
+        }));
+        assert!(result.is_err());
+        assert!(cell.is_poisoned());
+
+        let len = cell.with_checked(|vec| vec.len()).unwrap_err().into_inner();
+        assert_eq!(len, 2);
+        let popped = cell
+            .with_mut_checked(|vec| vec.pop())
+            .unwrap_err()
+            .into_inner();
+        assert_eq!(popped, Some(2));
+
+        cell.clear_poison();
+        assert!(!cell.is_poisoned());
+        assert_eq!(cell.with(|vec| vec.clone()), [1]);
+    }
+
     //no unwind on wasm!
     #[test]
     //note: unwind tests are not supported in wasm
@@ -586,10 +2177,70 @@ mod tests {
         assert!(panic_result.is_err());
 
         // The next access should panic due to mutex poisoning
-        let poison_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            cell.with(|v| *v);
-        }));
+        #[cfg(feature = "std")]
+        {
+            let poison_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                cell.with(|v| *v);
+            }));
+            assert!(poison_result.is_err());
+        }
+        // Without std, panics can't be detected, so the cell is never poisoned
+        #[cfg(not(feature = "std"))]
+        assert_eq!(cell.with(|v| *v), 42);
+    }
 
-        assert!(poison_result.is_err());
+    // Run with RUSTFLAGS="--cfg loom" cargo test --lib loom
+    #[cfg(feature = "std")]
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_with_mut() {
+        loom::model(|| {
+            let cell = loom::sync::Arc::new(SyncCell::new(0));
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let cell = loom::sync::Arc::clone(&cell);
+                    loom::thread::spawn(move || cell.with_mut(|value| *value += 1))
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            assert_eq!(cell.with(|value| *value), 2);
+        });
+    }
+
+    // The wait state and the bookkeeping of optional features must not grow every cell
+    #[cfg(not(any(loom, feature = "metrics", feature = "deadlock-detection")))]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_size() {
+        let mutex = core::mem::size_of::<lock_api::Mutex<DefaultRawMutex, u64>>();
+        let waiters = if cfg!(feature = "std") {
+            core::mem::size_of::<usize>()
+        } else {
+            0
+        };
+        // The poison flag shares the padding after the pointer
+        assert!(
+            core::mem::size_of::<SyncCell<u64>>() <= mutex + waiters + core::mem::align_of::<u64>()
+        );
+        assert_eq!(core::mem::size_of::<LockId>(), 0);
+        assert_eq!(core::mem::size_of::<Recorder>(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_wait_until() {
+        loom::model(|| {
+            let cell = loom::sync::Arc::new(SyncCell::new(false));
+            let worker = loom::sync::Arc::clone(&cell);
+            let handle = loom::thread::spawn(move || worker.with_mut(|ready| *ready = true));
+            assert!(cell.wait_until(|ready| *ready, |ready| *ready));
+            handle.join().unwrap();
+        });
     }
 }
@@ -0,0 +1,408 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A reader-writer cell with closure-based access for read-heavy shared state.
+
+This module provides [`SyncRwCell<T>`], a sibling of [`crate::SyncCell`] that keeps its
+value in a [`std::sync::RwLock`] instead of a mutex. Any number of threads can read the
+wrapped value at the same time through [`SyncRwCell::with_read`], while
+[`SyncRwCell::with_write`] provides exclusive mutable access.
+
+# Choosing Between SyncCell and SyncRwCell
+
+Allowing several readers to run at once means several threads hold `&T` simultaneously,
+which is precisely what `Sync` describes. For that reason `SyncRwCell<T>` is only `Sync`
+when `T: Send + Sync`, exactly like the `RwLock<T>` it wraps:
+
+| Type | Concurrent readers | `Sync` when |
+|------|--------------------|-------------|
+| [`crate::SyncCell`] | No | `T: Send` |
+| [`SyncRwCell`] | Yes | `T: Send + Sync` |
+
+Types that are not `Sync` at all (like `RefCell<T>` or `Rc<T>`) must use
+[`crate::SyncCell`], which serializes every access. `SyncRwCell` is for state that is
+read far more often than it is written, where serializing readers behind a mutex would
+be a bottleneck.
+
+# Thread Safety Model
+
+- All access is through closures that receive references to the wrapped value
+- Lock guards are automatically acquired and released by the closure methods
+- This prevents holding guards across await points or other blocking operations
+- Poisoning is reported like [`crate::SyncCell`] does, through
+  [`SyncRwCell::with_read_checked`], [`SyncRwCell::is_poisoned`] and
+  [`SyncRwCell::clear_poison`]. Unlike a `SyncCell`, the cell is only poisoned by a panic
+  inside [`SyncRwCell::with_write`]; a panicking reader cannot have left the value
+  half-modified
+- Reads are not re-entrant: calling `with_read` from inside another `with_read` closure
+  on the same cell may deadlock or panic, as with [`std::sync::RwLock::read`], because a
+  writer waiting in between can block the inner read
+
+# Examples
+
+```rust
+use send_cells::SyncRwCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+let cell = Arc::new(SyncRwCell::new(HashMap::new()));
+
+cell.with_write(|map| {
+    map.insert("key", "value");
+});
+
+// Many readers can access the map at the same time
+let handles: Vec<_> = (0..4)
+    .map(|_| {
+        let cell = Arc::clone(&cell);
+        thread::spawn(move || cell.with_read(|map| map.get("key").copied()))
+    })
+    .collect();
+
+for handle in handles {
+    assert_eq!(handle.join().unwrap(), Some("value"));
+}
+```
+*/
+
+use std::fmt::{Debug, Formatter};
+use std::sync::{PoisonError, RwLock};
+
+/// A cell that allows concurrent readers and exclusive writers through closures.
+///
+/// `SyncRwCell<T>` wraps a value of type `T` and protects it with an internal
+/// reader-writer lock. Unlike [`crate::SyncCell`], readers do not block each other:
+/// any number of [`with_read`](Self::with_read) closures may run at the same time,
+/// while [`with_write`](Self::with_write) waits for exclusive access.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncRwCell;
+///
+/// let cell = SyncRwCell::new(vec![1, 2, 3]);
+///
+/// let sum: i32 = cell.with_read(|vec| vec.iter().sum());
+/// assert_eq!(sum, 6);
+///
+/// cell.with_write(|vec| vec.push(4));
+/// assert_eq!(cell.with_read(|vec| vec.len()), 4);
+/// ```
+///
+/// # Thread Safety
+///
+/// The cell implements `Send` when the wrapped type implements `Send`, and `Sync`
+/// when the wrapped type implements both `Send` and `Sync`. See the
+/// [module documentation](self) for why concurrent readers require `T: Sync`.
+pub struct SyncRwCell<T> {
+    inner: RwLock<T>,
+}
+
+impl<T> SyncRwCell<T> {
+    /// Creates a new `SyncRwCell` wrapping the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    ///
+    /// let cell = SyncRwCell::new("Hello, world!");
+    /// cell.with_read(|value| println!("{}", value));
+    /// ```
+    #[inline]
+    pub fn new(value: T) -> SyncRwCell<T> {
+        SyncRwCell {
+            inner: RwLock::new(value),
+        }
+    }
+
+    /// Accesses the underlying value through a synchronous closure, alongside other readers.
+    ///
+    /// The closure receives a shared reference to the wrapped value. A read lock is
+    /// acquired before calling the closure and released when the closure returns.
+    /// Other readers may run concurrently; writers wait until all readers have finished.
+    ///
+    /// Reads are not re-entrant: calling this from inside another `with_read` closure on
+    /// the same cell may deadlock or panic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is poisoned (i.e., a closure passed to
+    /// [`Self::with_write`] panicked).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    ///
+    /// let cell = SyncRwCell::new(42);
+    /// assert_eq!(cell.with_read(|value| *value * 2), 84);
+    /// ```
+    #[inline]
+    pub fn with_read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with_read_checked(f)
+            .unwrap_or_else(|_| panic!("SyncRwCell<{}> is poisoned", core::any::type_name::<T>()))
+    }
+
+    /// Accesses the underlying value mutably through a synchronous closure.
+    ///
+    /// The closure receives a mutable reference to the wrapped value. The write lock is
+    /// acquired before calling the closure and released when the closure returns, so
+    /// the closure has exclusive access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is poisoned (i.e., an earlier closure passed to
+    /// this method panicked).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    ///
+    /// let cell = SyncRwCell::new(42);
+    /// cell.with_write(|value| *value = 100);
+    /// assert_eq!(cell.with_read(|value| *value), 100);
+    /// ```
+    #[inline]
+    pub fn with_write<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.with_write_checked(f)
+            .unwrap_or_else(|_| panic!("SyncRwCell<{}> is poisoned", core::any::type_name::<T>()))
+    }
+
+    /// Like [`Self::with_read`], but reports poisoning instead of panicking.
+    ///
+    /// The closure runs even if the cell is poisoned, so it can inspect the value left
+    /// behind by the panicking writer. Its result is returned in `Ok` if the cell was not
+    /// poisoned, and wrapped in a [`PoisonError`] otherwise; use
+    /// [`PoisonError::into_inner`] to recover it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    ///
+    /// let cell = SyncRwCell::new(vec![1, 2, 3]);
+    /// assert_eq!(cell.with_read_checked(|vec| vec.len()).ok(), Some(3));
+    /// ```
+    pub fn with_read_checked<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, PoisonError<R>> {
+        match self.inner.read() {
+            Ok(guard) => Ok(f(&guard)),
+            Err(poisoned) => Err(PoisonError::new(f(&poisoned.into_inner()))),
+        }
+    }
+
+    /// Like [`Self::with_write`], but reports poisoning instead of panicking.
+    ///
+    /// This is the mutable counterpart of [`Self::with_read_checked`]. Since the closure
+    /// runs even if the cell is poisoned, it can repair the value before the poison is
+    /// cleared with [`Self::clear_poison`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    /// use std::panic::AssertUnwindSafe;
+    /// use std::sync::PoisonError;
+    ///
+    /// let cell = SyncRwCell::new(Vec::new());
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     cell.with_write(|vec| {
+    ///         vec.push(1);
+    ///         panic!("interrupted halfway");
+    ///     })
+    /// }));
+    ///
+    /// // Repair the value, then clear the poison
+    /// let len = cell
+    ///     .with_write_checked(|vec| {
+    ///         vec.clear();
+    ///         vec.len()
+    ///     })
+    ///     .unwrap_or_else(PoisonError::into_inner);
+    /// assert_eq!(len, 0);
+    /// cell.clear_poison();
+    /// assert!(!cell.is_poisoned());
+    /// ```
+    pub fn with_write_checked<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, PoisonError<R>> {
+        match self.inner.write() {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(poisoned) => Err(PoisonError::new(f(&mut poisoned.into_inner()))),
+        }
+    }
+
+    /// Returns whether the cell is poisoned.
+    ///
+    /// The cell is poisoned when a closure passed to [`Self::with_write`] panics. Unlike
+    /// [`crate::SyncCell`], panics in [`Self::with_read`] do not poison it, since readers
+    /// cannot modify the value. While it is poisoned, [`Self::with_read`] and
+    /// [`Self::with_write`] panic; the checked methods return an error instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    /// use std::panic::AssertUnwindSafe;
+    ///
+    /// let cell = SyncRwCell::new(0);
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| cell.with_read(|_| panic!())));
+    /// assert!(!cell.is_poisoned());
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| cell.with_write(|_| panic!())));
+    /// assert!(cell.is_poisoned());
+    /// ```
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    /// Clears the poisoned state of the cell.
+    ///
+    /// Call this once the value has been checked or repaired, for example with
+    /// [`Self::with_write_checked`], to make the panicking methods usable again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    /// use std::panic::AssertUnwindSafe;
+    ///
+    /// let cell = SyncRwCell::new(0);
+    /// let _ = std::panic::catch_unwind(AssertUnwindSafe(|| cell.with_write(|_| panic!())));
+    /// cell.clear_poison();
+    /// assert_eq!(cell.with_read(|value| *value), 0);
+    /// ```
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncRwCell;
+    ///
+    /// let cell = SyncRwCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Debug> Debug for SyncRwCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.with_read(|value| value.fmt(f))
+    }
+}
+
+impl<T: Default> Default for SyncRwCell<T> {
+    fn default() -> SyncRwCell<T> {
+        SyncRwCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for SyncRwCell<T> {
+    fn from(value: T) -> Self {
+        SyncRwCell::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let cell = SyncRwCell::new(42);
+
+        let result = cell.with_read(|value| *value * 2);
+        assert_eq!(result, 84);
+
+        cell.with_write(|value| *value = 100);
+        assert_eq!(cell.with_read(|value| *value), 100);
+        assert_eq!(cell.into_inner(), 100);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    //no unwind on wasm!
+    #[test]
+    fn test_poisoning() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let cell = SyncRwCell::new(vec![1]);
+        // Readers cannot leave the value half-modified, so they don't poison the cell
+        let _ = catch_unwind(AssertUnwindSafe(|| cell.with_read(|_| panic!("reader"))));
+        assert!(!cell.is_poisoned());
+
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            cell.with_write(|vec| {
+                vec.push(2);
+                panic!("writer")
+            })
+        }));
+        assert!(cell.is_poisoned());
+        assert!(catch_unwind(AssertUnwindSafe(|| cell.with_read(|vec| vec.len()))).is_err());
+        let len = cell
+            .with_read_checked(|vec| vec.len())
+            .unwrap_err()
+            .into_inner();
+        assert_eq!(len, 2);
+
+        let _ = cell.with_write_checked(|vec| vec.pop());
+        cell.clear_poison();
+        assert_eq!(cell.with_read(|vec| vec.clone()), vec![1]);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let cell = SyncRwCell::new(42);
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    #[test]
+    fn test_concurrent_readers() {
+        use crate::sys::thread;
+        use std::sync::{Arc, Barrier};
+
+        let cell = Arc::new(SyncRwCell::new(42));
+        let barrier = Arc::new(Barrier::new(2));
+
+        // Both readers must be inside with_read at the same time to pass the barrier.
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    cell.with_read(|value| {
+                        barrier.wait();
+                        *value
+                    })
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+    }
+}
@@ -0,0 +1,532 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A watch-style cell that notifies readers when its value changes.
+
+This module provides [`SyncWatchCell<T>`], which gives non-Sync values the ergonomics of
+tokio's `watch` channel. Writers replace or modify the value with [`SyncWatchCell::send`]
+and [`SyncWatchCell::modify`]; readers create a [`Watcher`] and wait for the next change,
+either blocking the thread with [`Watcher::wait_changed`] or suspending a task with
+[`Watcher::changed`]. Waiting needs no particular async runtime.
+
+Like a watch channel, the cell only keeps the latest value: a watcher that falls behind
+skips the intermediate values and sees the newest one.
+
+# Examples
+
+```rust
+use send_cells::SyncWatchCell;
+use std::cell::RefCell;
+use std::thread;
+
+// RefCell is not Sync, but SyncWatchCell<RefCell<T>> is
+let config = SyncWatchCell::new(RefCell::new(String::from("v1")));
+
+thread::scope(|scope| {
+    // Watchers borrow the cell; creating one before spawning means no change is missed
+    let mut watcher = config.watch();
+    let reader = scope.spawn(move || {
+        watcher.wait_changed();
+        watcher.borrow().borrow().clone()
+    });
+
+    config.send(RefCell::new(String::from("v2")));
+    assert_eq!(reader.join().unwrap(), "v2");
+});
+```
+*/
+
+use crate::sync_cell::{SyncCell, SyncGuard};
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Poll, Waker};
+
+/// A cell holding a value that readers can watch for changes.
+///
+/// `SyncWatchCell<T>` stores its value in a [`SyncCell`], so it is `Sync` whenever
+/// `T: Send`, and adds a version number that increases with every [`Self::send`] or
+/// [`Self::modify`]. A [`Watcher`], created with [`Self::watch`], remembers the last
+/// version it has seen and can wait for a newer one.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncWatchCell;
+///
+/// let cell = SyncWatchCell::new(1);
+/// let mut watcher = cell.watch();
+/// assert!(!watcher.has_changed());
+///
+/// cell.modify(|value| *value += 1);
+/// assert!(watcher.has_changed());
+/// assert_eq!(*watcher.borrow_and_update(), 2);
+/// assert!(!watcher.has_changed());
+/// ```
+pub struct SyncWatchCell<T> {
+    value: SyncCell<T>,
+    state: Mutex<WatchState>,
+    changed: Condvar,
+}
+
+struct WatchState {
+    // Only changed while the value's lock is held, so it always matches the value
+    version: u64,
+    // Tasks waiting in Watcher::changed
+    wakers: Vec<Waker>,
+}
+
+impl<T> SyncWatchCell<T> {
+    /// Creates a new `SyncWatchCell` wrapping the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(42);
+    /// assert_eq!(*cell.borrow(), 42);
+    /// ```
+    pub fn new(value: T) -> SyncWatchCell<T> {
+        SyncWatchCell {
+            value: SyncCell::new(value),
+            state: Mutex::new(WatchState {
+                version: 0,
+                wakers: Vec::new(),
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Replaces the value and notifies all watchers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new("idle");
+    /// cell.send("running");
+    /// assert_eq!(*cell.borrow(), "running");
+    /// ```
+    pub fn send(&self, value: T) {
+        self.modify(|old| *old = value);
+    }
+
+    /// Modifies the value in place through a closure and notifies all watchers.
+    ///
+    /// Watchers are notified even if the closure leaves the value unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(vec![1]);
+    /// let len = cell.modify(|vec| {
+    ///     vec.push(2);
+    ///     vec.len()
+    /// });
+    /// assert_eq!(len, 2);
+    /// ```
+    pub fn modify<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut guard = self.value.lock();
+        let result = f(&mut guard);
+        let wakers = {
+            let mut state = self.lock_state();
+            state.version = state.version.wrapping_add(1);
+            std::mem::take(&mut state.wakers)
+        };
+        drop(guard);
+        self.changed.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+        result
+    }
+
+    /// Returns a read-only guard to the current value.
+    ///
+    /// Writers wait until the guard is dropped, so it should not be held for long.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is poisoned (i.e., another thread panicked while
+    /// holding the lock).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(String::from("hello"));
+    /// assert_eq!(cell.borrow().len(), 5);
+    /// ```
+    pub fn borrow(&self) -> WatchRef<'_, T> {
+        WatchRef {
+            guard: self.value.lock(),
+        }
+    }
+
+    /// Creates a [`Watcher`] that has seen the current value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(0);
+    /// let watcher = cell.watch();
+    /// cell.send(1);
+    /// assert!(watcher.has_changed());
+    /// ```
+    pub fn watch(&self) -> Watcher<'_, T> {
+        Watcher {
+            cell: self,
+            seen: self.lock_state().version,
+        }
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// This requires exclusive access to the cell, so no locking is needed and no
+    /// watchers exist to be notified.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let mut cell = SyncWatchCell::new(1);
+    /// *cell.get_mut() = 2;
+    /// assert_eq!(*cell.borrow(), 2);
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consumes the cell and returns the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(42);
+    /// assert_eq!(cell.into_inner(), 42);
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, WatchState> {
+        // The state is never left inconsistent by a panic
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Debug> Debug for SyncWatchCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: Default> Default for SyncWatchCell<T> {
+    fn default() -> SyncWatchCell<T> {
+        SyncWatchCell::new(T::default())
+    }
+}
+
+impl<T> From<T> for SyncWatchCell<T> {
+    fn from(value: T) -> Self {
+        SyncWatchCell::new(value)
+    }
+}
+
+/// A read-only guard to the value of a [`SyncWatchCell`].
+///
+/// Returned by [`SyncWatchCell::borrow`], [`Watcher::borrow`] and
+/// [`Watcher::borrow_and_update`]. The cell's lock is held until the guard is dropped.
+pub struct WatchRef<'a, T> {
+    guard: SyncGuard<'a, T>,
+}
+
+impl<T> Deref for WatchRef<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: Debug> Debug for WatchRef<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Waits for changes to a [`SyncWatchCell`].
+///
+/// A watcher remembers the version of the value it has seen. [`Self::has_changed`] reports
+/// whether a newer version has been sent; [`Self::wait_changed`] and [`Self::changed`] wait
+/// for one and mark it as seen.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::SyncWatchCell;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let cell = Arc::new(SyncWatchCell::new(0));
+///
+/// let watcher_thread = {
+///     let cell = Arc::clone(&cell);
+///     thread::spawn(move || {
+///         let mut watcher = cell.watch();
+///         // Later values may be skipped, but the last one is always seen
+///         while *watcher.borrow_and_update() != 3 {
+///             watcher.wait_changed();
+///         }
+///     })
+/// };
+///
+/// for value in 1..=3 {
+///     cell.send(value);
+/// }
+/// watcher_thread.join().unwrap();
+/// ```
+pub struct Watcher<'a, T> {
+    cell: &'a SyncWatchCell<T>,
+    seen: u64,
+}
+
+impl<'a, T> Watcher<'a, T> {
+    /// Returns `true` if the value has changed since this watcher last saw it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(0);
+    /// let watcher = cell.watch();
+    /// assert!(!watcher.has_changed());
+    /// cell.send(0);
+    /// assert!(watcher.has_changed());
+    /// ```
+    pub fn has_changed(&self) -> bool {
+        self.cell.lock_state().version != self.seen
+    }
+
+    /// Returns a read-only guard to the current value, without marking it as seen.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(0);
+    /// let watcher = cell.watch();
+    /// cell.send(1);
+    /// assert_eq!(*watcher.borrow(), 1);
+    /// assert!(watcher.has_changed());
+    /// ```
+    pub fn borrow(&self) -> WatchRef<'a, T> {
+        self.cell.borrow()
+    }
+
+    /// Returns a read-only guard to the current value and marks it as seen.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// let cell = SyncWatchCell::new(0);
+    /// let mut watcher = cell.watch();
+    /// cell.send(1);
+    /// assert_eq!(*watcher.borrow_and_update(), 1);
+    /// assert!(!watcher.has_changed());
+    /// ```
+    pub fn borrow_and_update(&mut self) -> WatchRef<'a, T> {
+        let value = self.cell.borrow();
+        // The version only changes under the value's lock, so it matches the value
+        self.seen = self.cell.lock_state().version;
+        value
+    }
+
+    /// Blocks the current thread until the value changes, then marks it as seen.
+    ///
+    /// Returns immediately if the value has already changed since this watcher last saw
+    /// it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let cell = Arc::new(SyncWatchCell::new(false));
+    /// let mut watcher = cell.watch();
+    ///
+    /// let sender = Arc::clone(&cell);
+    /// thread::spawn(move || sender.send(true));
+    ///
+    /// watcher.wait_changed();
+    /// assert!(*watcher.borrow());
+    /// ```
+    pub fn wait_changed(&mut self) {
+        let state = self.cell.lock_state();
+        let state = self
+            .cell
+            .changed
+            .wait_while(state, |state| state.version == self.seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        self.seen = state.version;
+    }
+
+    /// Waits for the value to change without blocking the thread, then marks it as seen.
+    ///
+    /// The future completes immediately if the value has already changed since this
+    /// watcher last saw it. It works with any executor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SyncWatchCell;
+    ///
+    /// async fn example(cell: &SyncWatchCell<u32>) {
+    ///     let mut watcher = cell.watch();
+    ///     loop {
+    ///         watcher.changed().await;
+    ///         if *watcher.borrow() == 0 {
+    ///             break;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn changed(&mut self) {
+        std::future::poll_fn(|cx| {
+            let mut state = self.cell.lock_state();
+            if state.version != self.seen {
+                self.seen = state.version;
+                return Poll::Ready(());
+            }
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<T> Debug for Watcher<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher").field("seen", &self.seen).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Wake};
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let cell = SyncWatchCell::new(std::cell::Cell::new(1));
+        let mut watcher = cell.watch();
+        assert!(!watcher.has_changed());
+        assert_eq!(watcher.borrow().get(), 1);
+
+        cell.send(std::cell::Cell::new(2));
+        assert!(watcher.has_changed());
+        assert_eq!(watcher.borrow_and_update().get(), 2);
+        assert!(!watcher.has_changed());
+
+        assert_eq!(cell.modify(|value| value.replace(3)), 2);
+        watcher.wait_changed();
+        assert!(!watcher.has_changed());
+        assert_eq!(cell.into_inner().get(), 3);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_changed_future() {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+        let cell = SyncWatchCell::new(0);
+        let mut watcher = cell.watch();
+
+        {
+            let mut changed = pin!(watcher.changed());
+            assert_eq!(changed.as_mut().poll(&mut cx), Poll::Pending);
+            // Polling again doesn't register the same waker twice
+            assert_eq!(changed.as_mut().poll(&mut cx), Poll::Pending);
+            cell.send(1);
+            // Miri gives vtables a new address on each use, so will_wake can't match there
+            #[cfg(not(miri))]
+            assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+            assert_eq!(changed.poll(&mut cx), Poll::Ready(()));
+        }
+        assert!(!watcher.has_changed());
+        assert_eq!(*watcher.borrow(), 1);
+    }
+
+    #[test]
+    fn test_wait_changed_across_threads() {
+        use crate::sys::thread;
+
+        let cell = Arc::new(SyncWatchCell::new(0));
+        let watchers: Vec<_> = (0..3)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    let mut watcher = cell.watch();
+                    while *watcher.borrow_and_update() < 100 {
+                        watcher.wait_changed();
+                    }
+                })
+            })
+            .collect();
+
+        for value in 1..=100 {
+            cell.send(value);
+        }
+        for watcher in watchers {
+            watcher.join().unwrap();
+        }
+    }
+}
@@ -11,39 +11,895 @@ between standard native threading (using `std::thread`) and WebAssembly threadin
 
 ## Native Platforms
 
-On native platforms (non-WebAssembly), this module re-exports `std::thread`,
+On native platforms (non-WebAssembly), [`thread`] re-exports `std::thread`,
 providing access to the standard library's threading primitives.
 
 ## WebAssembly
 
-On WebAssembly (`wasm32-unknown-unknown`), this module re-exports the `wasm_thread`
+On WebAssembly (`wasm32-unknown-unknown`), [`thread`] re-exports the `wasm_thread`
 crate, which provides Web Worker-based threading support. This allows the send_cells
 crate to work correctly in browser environments with Web Workers.
 
-# Usage
+`wasm_thread` is built on `wasm-bindgen`. Builds that don't use `wasm-bindgen` (custom
+JavaScript glue, or single-instance builds without threads) can disable the default
+`wasm-bindgen` feature; this module then re-exports `std::thread` on WebAssembly as well.
+[`current_id`] does not depend on either, so thread identity works the same in both
+configurations.
 
-This module is primarily used internally by the send_cells crate to:
-- Get current thread IDs for runtime checking in [`crate::SendCell`]
-- Provide thread-safe abstractions that work across platforms
-- Enable consistent behavior between native and WebAssembly environments
+## Emscripten
+
+On `wasm32-unknown-emscripten`, threads are Emscripten's pthreads, which `std::thread`
+supports directly, so this module re-exports `std::thread` and neither `wasm_thread` nor
+`wasm-bindgen` is used. Each pthread gets its own [`ThreadId`], exactly as on native
+platforms, and Emscripten emulates blocking on the browser main thread, so `SyncCell`
+keeps its usual lock there.
+
+# Main Thread
+
+[`thread::is_main_thread`] tells whether the current thread is the process's main thread
+(the browser's main thread on WebAssembly), using each platform's own notion of it, for
+code that must assert it runs where a UI toolkit or other main-thread-only API expects.
+
+# Thread Identity
+
+[`current_id`] and [`ThreadId`] are the thread identity behind every runtime check in
+this crate, and are a stable public API: crates building their own thread-affinity
+checks can use them to get the same behavior as [`crate::SendCell`] on every platform,
+including WebAssembly. A [`ThreadId`] is `Copy`, `Eq`, `Hash` and `Debug`, and is never
+reused for another thread while the process runs.
 
 # Examples
 
 ```rust
-// Get the current thread ID (works on both native and WASM)
-let thread_id = send_cells::sys::thread::current().id();
-println!("Current thread: {:?}", thread_id);
+# #[cfg(not(feature = "std"))] {
+#     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+#     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+#     // SAFETY: Every thread takes a new number
+#     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+# }
+use send_cells::sys::{self, ThreadId};
+use std::collections::HashSet;
+
+// Record which threads have touched a resource (works on both native and WASM)
+let mut seen: HashSet<ThreadId> = HashSet::new();
+seen.insert(sys::current_id());
+assert!(seen.contains(&sys::current_id()));
 ```
 
 # Dependencies
 
 On WebAssembly platforms, this module depends on the `wasm_thread` crate,
-which is automatically included when building for `wasm32-unknown-unknown`.
-On other platforms, it uses the standard library's `std::thread`.
+which is included when building for `wasm32-unknown-unknown` with the default
+`wasm-bindgen` feature. On Emscripten and other platforms, it uses the standard library's
+`std::thread`.
+
+# `no_std`
+
+Without the default `std` feature there are no threads to ask for an id, so the platform
+must provide one: the unsafe `set_id_source` installs a function returning a distinct
+non-zero number for every running thread (a core number, a task id, a pointer to the current
+task's control block), and [`current_id`] calls it. Until a source is installed,
+[`current_id`] panics. [`thread`] and [`thread_name`] are unavailable.
 */
 
-#[cfg(target_arch = "wasm32")]
-pub use wasm_thread as thread;
+/// Threading primitives for the current platform, plus main-thread detection.
+///
+/// Everything from `std::thread` (or `wasm_thread` on WebAssembly with the default
+/// `wasm-bindgen` feature) is re-exported here, so `sys::thread::spawn` and friends work
+/// on every platform. [`is_main_thread`](thread::is_main_thread) is added on top.
+#[cfg(feature = "std")]
+pub mod thread {
+    #[cfg(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        feature = "wasm-bindgen"
+    ))]
+    pub use wasm_thread::*;
+    // Not re-exported by wasm_thread, and work the same in web workers
+    #[cfg(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        feature = "wasm-bindgen"
+    ))]
+    pub use std::thread::{panicking, park, park_timeout, yield_now};
+
+    #[cfg(not(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        feature = "wasm-bindgen"
+    )))]
+    pub use std::thread::*;
+
+    /// Returns whether the current thread is the process's main thread.
+    ///
+    /// The main thread is the one that ran `main` (or, in a browser, the page's own thread
+    /// rather than a web worker). Many platform APIs, such as UI toolkits, may only be used
+    /// there; this lets code assert that before touching them.
+    ///
+    /// | Platform | Implementation |
+    /// |----------|----------------|
+    /// | macOS, iOS, FreeBSD, OpenBSD | `pthread_main_np()` |
+    /// | Linux, Android | `gettid() == getpid()` |
+    /// | Windows (MSVC) | Compares with the id of the thread that ran the C runtime initializers |
+    /// | Emscripten | `emscripten_is_main_browser_thread()` |
+    /// | `wasm32-unknown-unknown` | Not a web worker (`wasm-bindgen` feature), or always true without the `atomics` target feature |
+    ///
+    /// On other platforms, on Windows with a toolchain other than MSVC (whose C runtime
+    /// does not run the initializer this relies on), and on `wasm32-unknown-unknown` with
+    /// threads but without the `wasm-bindgen` feature, the main thread can't be detected
+    /// and this function is not available.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::sys::thread;
+    ///
+    /// let spawned = thread::spawn(thread::is_main_thread).join().unwrap();
+    /// assert!(!spawned);
+    /// ```
+    #[cfg(any(
+        target_vendor = "apple",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "linux",
+        target_os = "android",
+        all(windows, target_env = "msvc"),
+        target_os = "emscripten",
+        all(
+            target_arch = "wasm32",
+            any(feature = "wasm-bindgen", not(target_feature = "atomics"))
+        ),
+    ))]
+    pub fn is_main_thread() -> bool {
+        imp::is_main_thread()
+    }
+
+    /// Returns the id of the thread that created the window `hwnd`.
+    ///
+    /// A window belongs to the thread that created it: only that thread receives its
+    /// messages, and many window and COM APIs may only be called there. Binding cells to
+    /// this id (see `SendCell::new_bound_to_window`) checks their accesses against that
+    /// thread rather than whichever thread created the cell.
+    ///
+    /// Returns `None` if `hwnd` is not a window of this process, or if the window's thread
+    /// has no id yet. Threads get their id the first time they call
+    /// [`super::current_id`] (directly or by creating a cell), so a UI thread should do so
+    /// before handing out its windows.
+    ///
+    /// Only available on Windows with the `windows` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::sys::thread;
+    ///
+    /// // A null handle is never a window
+    /// assert_eq!(thread::window_thread_id(std::ptr::null_mut()), None);
+    /// ```
+    #[cfg(all(windows, feature = "windows"))]
+    // An HWND is an opaque handle that Windows validates, never dereferenced
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn window_thread_id(hwnd: windows_sys::Win32::Foundation::HWND) -> Option<super::ThreadId> {
+        use windows_sys::Win32::System::Threading::GetCurrentProcessId;
+        use windows_sys::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+        let mut process = 0;
+        // SAFETY: GetWindowThreadProcessId accepts any handle, returning 0 if it is not a
+        // window, and `process` is valid for writes
+        let thread = unsafe { GetWindowThreadProcessId(hwnd, &mut process) };
+        // SAFETY: GetCurrentProcessId has no preconditions
+        if thread == 0 || process != unsafe { GetCurrentProcessId() } {
+            return None;
+        }
+        super::windows_threads().get(&thread).copied()
+    }
+
+    /// Returns a new id that belongs to no real thread, for use with [`mock_current_id`].
+    ///
+    /// Mock ids are taken from the top half of the id space, so they never equal the id of
+    /// a real thread.
+    #[cfg(feature = "test-util")]
+    pub fn mock_id() -> MockThreadId {
+        use core::num::NonZeroU64;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static COUNTER: AtomicU64 = AtomicU64::new(1 << 63);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        MockThreadId {
+            id: super::ThreadId(NonZeroU64::new(id).expect("mock ids are never 0")),
+            _not_send: core::marker::PhantomData,
+        }
+    }
+
+    /// A simulated thread id, returned by [`mock_id`].
+    ///
+    /// Only mock ids can be passed to [`mock_current_id`]: simulating a real thread would
+    /// let two threads pass the same checks at once. A mock id can't leave the thread that
+    /// created it, so only that thread can ever run as it.
+    ///
+    /// ```compile_fail
+    /// use send_cells::sys::thread;
+    ///
+    /// let id = thread::mock_id();
+    /// std::thread::spawn(move || thread::with_current_id(id, || ()));
+    /// ```
+    #[cfg(feature = "test-util")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MockThreadId {
+        id: super::ThreadId,
+        // Two threads running as the same mock id could share its cells' values
+        _not_send: core::marker::PhantomData<*const ()>,
+    }
+
+    #[cfg(feature = "test-util")]
+    impl MockThreadId {
+        /// Returns the id that [`super::current_id`] reports while this id is mocked.
+        #[inline]
+        pub fn id(self) -> super::ThreadId {
+            self.id
+        }
+    }
+
+    /// Makes [`super::current_id`] return `id` on this thread until the guard is dropped.
+    ///
+    /// Every runtime check in this crate then behaves as if it ran on the thread `id`
+    /// identifies, so tests can simulate wrong-thread accesses deterministically and on
+    /// targets that can't spawn threads. Cells created while the guard lives belong to
+    /// `id`. Guards can be nested; dropping one restores the id that was current before it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    /// use send_cells::sys::thread;
+    /// use std::panic::{AssertUnwindSafe, catch_unwind};
+    ///
+    /// let cell = SendCell::new(42);
+    /// {
+    ///     let _guard = thread::mock_current_id(thread::mock_id());
+    ///     // Accessing the cell now looks like an access from another thread
+    ///     assert!(catch_unwind(AssertUnwindSafe(|| *cell.get())).is_err());
+    /// }
+    /// assert_eq!(*cell.get(), 42);
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn mock_current_id(id: MockThreadId) -> MockIdGuard {
+        MockIdGuard {
+            previous: MOCKED_ID.replace(Some(id.id)),
+            _not_send: core::marker::PhantomData,
+        }
+    }
+
+    /// Runs `f` with [`super::current_id`] returning `id`, as with [`mock_current_id`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::SendCell;
+    /// use send_cells::sys::thread;
+    ///
+    /// // A cell that belongs to a simulated thread can only be used as that thread
+    /// let id = thread::mock_id();
+    /// let cell = thread::with_current_id(id, || SendCell::new(42));
+    /// assert_eq!(thread::with_current_id(id, || *cell.get()), 42);
+    /// thread::with_current_id(id, || drop(cell));
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn with_current_id<R>(id: MockThreadId, f: impl FnOnce() -> R) -> R {
+        let _guard = mock_current_id(id);
+        f()
+    }
+
+    /// Restores the previous thread id when dropped. Returned by [`mock_current_id`].
+    #[cfg(feature = "test-util")]
+    #[must_use = "the mocked id is only in effect while the guard lives"]
+    #[derive(Debug)]
+    pub struct MockIdGuard {
+        previous: Option<super::ThreadId>,
+        // The mock is per thread, so the guard must be dropped where it was created
+        _not_send: core::marker::PhantomData<*const ()>,
+    }
+
+    #[cfg(feature = "test-util")]
+    impl Drop for MockIdGuard {
+        fn drop(&mut self) {
+            MOCKED_ID.set(self.previous);
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    std::thread_local! {
+        /// The id set with [`mock_current_id`], if any.
+        pub(super) static MOCKED_ID: core::cell::Cell<Option<super::ThreadId>> =
+            const { core::cell::Cell::new(None) };
+    }
+
+    #[cfg(any(target_vendor = "apple", target_os = "freebsd", target_os = "openbsd"))]
+    mod imp {
+        pub(super) fn is_main_thread() -> bool {
+            // SAFETY: pthread_main_np has no preconditions
+            unsafe { libc::pthread_main_np() == 1 }
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    mod imp {
+        pub(super) fn is_main_thread() -> bool {
+            // The main thread's id is the process id
+            // SAFETY: gettid and getpid have no preconditions and cannot fail
+            unsafe { libc::syscall(libc::SYS_gettid) == libc::getpid() as libc::c_long }
+        }
+    }
+
+    #[cfg(all(windows, target_env = "msvc"))]
+    mod imp {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+
+        /// The id of the thread that ran the C runtime initializers, which is the main
+        /// thread. Windows thread ids are never 0.
+        static MAIN_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+
+        // Runs on the main thread before `main`, like a C++ static constructor. Only the
+        // MSVC C runtime walks this section, hence the `target_env` gate
+        #[used]
+        #[unsafe(link_section = ".CRT$XCU")]
+        static RECORD_MAIN_THREAD: extern "C" fn() = record_main_thread;
+
+        extern "C" fn record_main_thread() {
+            // SAFETY: GetCurrentThreadId has no preconditions
+            let id = unsafe { GetCurrentThreadId() };
+            MAIN_THREAD_ID.store(id, Ordering::Relaxed);
+        }
+
+        pub(super) fn is_main_thread() -> bool {
+            // SAFETY: GetCurrentThreadId has no preconditions
+            MAIN_THREAD_ID.load(Ordering::Relaxed) == unsafe { GetCurrentThreadId() }
+        }
+    }
+
+    #[cfg(target_os = "emscripten")]
+    mod imp {
+        unsafe extern "C" {
+            fn emscripten_is_main_browser_thread() -> core::ffi::c_int;
+        }
+
+        pub(super) fn is_main_thread() -> bool {
+            // SAFETY: The function only reads the runtime's thread state
+            unsafe { emscripten_is_main_browser_thread() != 0 }
+        }
+    }
+
+    #[cfg(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        feature = "wasm-bindgen"
+    ))]
+    mod imp {
+        pub(super) fn is_main_thread() -> bool {
+            !super::is_web_worker_thread()
+        }
+    }
+
+    #[cfg(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        not(feature = "wasm-bindgen"),
+        not(target_feature = "atomics")
+    ))]
+    mod imp {
+        // Without threads there is only the main thread
+        pub(super) fn is_main_thread() -> bool {
+            true
+        }
+    }
+}
+
+use core::fmt::{Display, Formatter};
+use core::num::NonZeroU64;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::AtomicPtr;
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+#[cfg(feature = "std")]
+use std::cell::Cell;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+#[cfg(feature = "std-thread-id")]
+use std::collections::HashMap;
+#[cfg(feature = "std-thread-id")]
+use std::sync::LazyLock;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A unique identifier of a thread, as recorded by the runtime-checked types in this crate.
+///
+/// Ids are taken from a process-wide 64-bit counter the first time a thread asks for one,
+/// and are never reused, not even after the thread exits. Unlike operating system thread
+/// ids, which are recycled, a new thread therefore can never pass the check of a cell that
+/// belonged to a dead one.
+///
+/// With the `std-thread-id` feature, running threads that asked for an id are also
+/// recorded under their [`std::thread::ThreadId`]s, so a stored std id can be compared with
+/// this crate's through `ThreadId::from_std`. Without the `std`
+/// feature, ids are the numbers returned by the source installed with `set_id_source`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(not(feature = "std"))] {
+/// #     static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+/// #     std::thread_local!(static ID: u64 = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+/// #     // SAFETY: Every thread takes a new number
+/// #     unsafe { send_cells::sys::set_id_source(|| ID.with(|id| (*id).try_into().unwrap())) };
+/// # }
+/// use send_cells::sys;
+///
+/// let id = sys::current_id();
+/// println!("Current thread: {:?}", id);
+/// assert!(id.as_u64().get() >= 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ThreadId(NonZeroU64);
+
+impl ThreadId {
+    /// Returns the id as a number.
+    ///
+    /// Numbers start at 1 and count up in the order threads first asked for their id.
+    /// Without the `std` feature, they are the numbers returned by the id source.
+    #[inline]
+    pub fn as_u64(self) -> NonZeroU64 {
+        self.0
+    }
+
+    /// Takes the next id from the process-wide counter.
+    #[cfg(feature = "std")]
+    #[cold]
+    fn next() -> ThreadId {
+        static COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = COUNTER
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .expect("exhausted the space of thread ids");
+        ThreadId(NonZeroU64::new(id).expect("thread ids start at 1"))
+    }
+
+    /// Returns the id this crate records for the thread with the given std id.
+    ///
+    /// The current thread always has one. Another thread only has one while it is running
+    /// and after it first asked for its id: `None` means no cell can be bound to that
+    /// thread at the moment, either because it has exited or because it has not used this
+    /// crate yet. Nothing is recorded for such ids, so converting them costs no memory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::sys::{self, ThreadId};
+    /// use std::thread;
+    ///
+    /// let stored = thread::current().id();
+    /// assert_eq!(ThreadId::from_std(stored), Some(sys::current_id()));
+    ///
+    /// let exited = thread::spawn(|| thread::current().id()).join().unwrap();
+    /// assert_eq!(ThreadId::from_std(exited), None);
+    /// ```
+    #[cfg(feature = "std-thread-id")]
+    pub fn from_std(id: std::thread::ThreadId) -> Option<ThreadId> {
+        if id == thread::current().id() {
+            return Some(own_id());
+        }
+        std_ids().get(&id).copied()
+    }
+}
+
+/// The crate ids of running threads that asked for an id, by their std ids.
+///
+/// std only exposes the number of its ids on nightly, so ids are looked up instead of
+/// converted. Threads add their entry when they first ask for an id and remove it when they
+/// exit, so the map only ever holds the running threads.
+#[cfg(feature = "std-thread-id")]
+fn std_ids() -> MutexGuard<'static, HashMap<std::thread::ThreadId, ThreadId>> {
+    static IDS: LazyLock<Mutex<HashMap<std::thread::ThreadId, ThreadId>>> =
+        LazyLock::new(Mutex::default);
+    IDS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the identifier of the current thread.
+///
+/// Two calls return equal ids if and only if they are made on the same thread. This also
+/// works while the thread's thread-locals are being destroyed, so it can be called from
+/// `Drop` implementations that run then.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::sys;
+/// use std::thread;
+///
+/// let main = sys::current_id();
+/// assert_eq!(main, sys::current_id());
+///
+/// let worker = thread::spawn(sys::current_id).join().unwrap();
+/// assert_ne!(main, worker);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn current_id() -> ThreadId {
+    #[cfg(feature = "test-util")]
+    if let Some(id) = thread::MOCKED_ID.with(Cell::get) {
+        return id;
+    }
+    own_id()
+}
+
+/// Returns the current thread's own id, ignoring any mock.
+#[cfg(feature = "std")]
+#[inline]
+fn own_id() -> ThreadId {
+    // A const-initialized thread-local without drop glue is never destroyed
+    #[cfg(not(loom))]
+    thread_local! {
+        static CURRENT: Cell<Option<ThreadId>> = const { Cell::new(None) };
+    }
+    // loom runs its threads on one OS thread, so they need loom's thread-locals to differ
+    #[cfg(loom)]
+    loom::thread_local! {
+        static CURRENT: Cell<Option<ThreadId>> = Cell::new(None);
+    }
+    CURRENT.with(|current| match current.get() {
+        Some(id) => id,
+        None => {
+            let id = ThreadId::next();
+            current.set(Some(id));
+            register(id);
+            id
+        }
+    })
+}
+
+/// The id source installed with [`set_id_source`], stored as a data pointer because there
+/// are no atomic function pointers.
+#[cfg(not(feature = "std"))]
+static ID_SOURCE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Returns the identifier of the current thread.
+///
+/// Without the `std` feature, this calls the source installed with [`set_id_source`]. Two
+/// calls return equal ids if and only if the source returns equal numbers for them.
+///
+/// # Panics
+///
+/// Panics if no id source has been installed.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub fn current_id() -> ThreadId {
+    let source = ID_SOURCE.load(Ordering::Acquire);
+    // Unit tests run on std's threads, and can't install a source for all of them
+    #[cfg(test)]
+    if source.is_null() {
+        return test_id();
+    }
+    assert!(
+        !source.is_null(),
+        "no thread id source: call send_cells::sys::set_id_source before using checked cells"
+    );
+    // SAFETY: Only set_id_source stores to ID_SOURCE, and it only stores this function type
+    let source = unsafe { core::mem::transmute::<*mut (), fn() -> NonZeroU64>(source) };
+    ThreadId(source())
+}
+
+/// Numbers std's threads for unit tests without the `std` feature.
+#[cfg(all(test, not(feature = "std")))]
+fn test_id() -> ThreadId {
+    static COUNTER: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(1);
+    std::thread_local! {
+        static ID: ThreadId = ThreadId(
+            NonZeroU64::new(COUNTER.fetch_add(1, Ordering::Relaxed)).expect("ids start at 1"),
+        );
+    }
+    ID.with(|id| *id)
+}
+
+/// Installs the function [`current_id`] uses to identify threads without the `std` feature.
+///
+/// Every runtime check in this crate trusts the numbers `source` returns. On a
+/// single-threaded target it can simply return 1.
+///
+/// # Safety
+///
+/// `source` must return the same number every time it is called on one thread, and
+/// different numbers on threads that run at the same time; otherwise two threads could
+/// pass the same checks and share values that are not `Send` or `Sync`. It must also not
+/// reuse the number of a thread that has exited while values bound to that thread may
+/// still exist, or a new thread could pass their checks.
+///
+/// # Panics
+///
+/// Panics if a source was already installed; swapping sources would change the identity
+/// of threads that own cells.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use core::num::NonZeroU64;
+///
+/// fn core_id() -> NonZeroU64 {
+///     // On a multi-core microcontroller, each core is a thread
+///     NonZeroU64::new(read_core_number() as u64 + 1).unwrap()
+/// }
+///
+/// // SAFETY: Each core runs one thread, and the core numbers are distinct
+/// unsafe { send_cells::sys::set_id_source(core_id) };
+/// ```
+#[cfg(not(feature = "std"))]
+pub unsafe fn set_id_source(source: fn() -> NonZeroU64) {
+    let installed = ID_SOURCE.compare_exchange(
+        core::ptr::null_mut(),
+        source as *mut (),
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    );
+    assert!(installed.is_ok(), "a thread id source is already installed");
+}
+
+/// Returns whether the current thread is allowed to block, or `None` if that is unknown.
+///
+/// Every thread may block except the browser main thread on `wasm32` with threads, where
+/// `Atomics.wait` traps. Telling the main thread from web workers takes `wasm-bindgen`.
+#[cfg(all(
+    target_arch = "wasm32",
+    not(target_os = "emscripten"),
+    target_feature = "atomics",
+    feature = "wasm-bindgen"
+))]
+pub(crate) fn can_block() -> Option<bool> {
+    thread_local! {
+        static WORKER: bool = thread::is_web_worker_thread();
+    }
+    Some(WORKER.with(|worker| *worker))
+}
+
+/// Returns whether the current thread is allowed to block, or `None` if that is unknown.
+///
+/// Every thread may block except the browser main thread on `wasm32` with threads, where
+/// `Atomics.wait` traps. Telling the main thread from web workers takes `wasm-bindgen`.
+#[cfg(all(
+    feature = "std",
+    target_arch = "wasm32",
+    not(target_os = "emscripten"),
+    target_feature = "atomics",
+    not(feature = "wasm-bindgen")
+))]
+#[inline]
+pub(crate) fn can_block() -> Option<bool> {
+    None
+}
+
+/// Returns whether the current thread is allowed to block, or `None` if that is unknown.
+///
+/// Every thread may block except the browser main thread on `wasm32` with threads, where
+/// `Atomics.wait` traps. Telling the main thread from web workers takes `wasm-bindgen`.
+/// Emscripten emulates blocking on its main thread, so every thread may block there.
+#[cfg(all(
+    feature = "std",
+    not(all(
+        target_arch = "wasm32",
+        not(target_os = "emscripten"),
+        target_feature = "atomics"
+    ))
+))]
+#[inline]
+pub(crate) fn can_block() -> Option<bool> {
+    Some(true)
+}
+
+/// Returns the name of the thread with the given id, if the thread is named and still
+/// running.
+///
+/// Names are recorded when a thread first asks for its id, which every runtime-checked
+/// type in this crate does on construction, so the origin thread of a cell can be named
+/// in wrong-thread panic messages.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::sys;
+/// use std::thread;
+///
+/// let (id, name) = thread::Builder::new()
+///     .name("worker".into())
+///     .spawn(|| {
+///         let id = sys::current_id();
+///         (id, sys::thread_name(id))
+///     })
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// assert_eq!(name.as_deref(), Some("worker"));
+///
+/// // The name is forgotten once the thread has exited
+/// assert_eq!(sys::thread_name(id), None);
+/// ```
+#[cfg(feature = "std")]
+pub fn thread_name(id: ThreadId) -> Option<String> {
+    names().get(&id).map(|name| name.to_string())
+}
+
+/// Names of the running named threads that have asked for their id.
+#[cfg(feature = "std")]
+fn names() -> MutexGuard<'static, BTreeMap<ThreadId, Box<str>>> {
+    static NAMES: Mutex<BTreeMap<ThreadId, Box<str>>> = Mutex::new(BTreeMap::new());
+    NAMES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The crate ids of the running threads that have asked for one, by Windows thread id.
+#[cfg(all(windows, feature = "windows"))]
+fn windows_threads() -> MutexGuard<'static, BTreeMap<u32, ThreadId>> {
+    static THREADS: Mutex<BTreeMap<u32, ThreadId>> = Mutex::new(BTreeMap::new());
+    THREADS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the Windows id of the current thread.
+#[cfg(all(windows, feature = "windows"))]
+fn windows_thread_id() -> u32 {
+    // SAFETY: GetCurrentThreadId has no preconditions
+    unsafe { windows_sys::Win32::System::Threading::GetCurrentThreadId() }
+}
+
+/// Records the name of the current thread, if it has one, with the `windows` feature its
+/// Windows thread id, and with the `std-thread-id` feature its std id, until the thread
+/// exits.
+#[cfg(feature = "std")]
+#[cold]
+fn register(id: ThreadId) {
+    /// Forgets the thread when its thread-locals are destroyed.
+    struct Unregister {
+        id: ThreadId,
+        #[cfg(feature = "std-thread-id")]
+        std_id: std::thread::ThreadId,
+    }
+
+    impl Drop for Unregister {
+        fn drop(&mut self) {
+            names().remove(&self.id);
+            #[cfg(all(windows, feature = "windows"))]
+            windows_threads().remove(&windows_thread_id());
+            #[cfg(feature = "std-thread-id")]
+            std_ids().remove(&self.std_id);
+        }
+    }
+
+    thread_local! {
+        static UNREGISTER: Cell<Option<Unregister>> = const { Cell::new(None) };
+    }
+
+    let current = thread::current();
+    let name = current.name().map(Box::<str>::from);
+    #[cfg(not(any(all(windows, feature = "windows"), feature = "std-thread-id")))]
+    if name.is_none() {
+        return;
+    }
+    let unregister = Unregister {
+        id,
+        #[cfg(feature = "std-thread-id")]
+        std_id: current.id(),
+    };
+    // Once the thread-locals are being destroyed the thread could never be forgotten, so
+    // it is not recorded
+    if UNREGISTER
+        .try_with(|slot| slot.set(Some(unregister)))
+        .is_ok()
+    {
+        if let Some(name) = name {
+            names().insert(id, name);
+        }
+        #[cfg(all(windows, feature = "windows"))]
+        windows_threads().insert(windows_thread_id(), id);
+        #[cfg(feature = "std-thread-id")]
+        std_ids().insert(current.id(), id);
+    }
+}
+
+/// Reports a thread-affinity violation described by `message`.
+///
+/// This panics, or with the `abort-on-violation` feature prints the message to stderr and
+/// aborts the process, so that a violation never unwinds through foreign frames.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn violation(message: core::fmt::Arguments<'_>) -> ! {
+    #[cfg(feature = "abort-on-violation")]
+    {
+        std::eprintln!("{message}");
+        std::process::abort()
+    }
+    #[cfg(not(feature = "abort-on-violation"))]
+    panic!("{message}")
+}
+
+/// Formats a thread for wrong-thread panic messages: its id, then its name if it has one.
+pub(crate) struct Described(pub(crate) ThreadId);
+
+impl Display for Described {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0.as_u64())?;
+        #[cfg(feature = "std")]
+        if let Some(name) = thread_name(self.0) {
+            write!(f, " {name:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, any(feature = "test-util", feature = "std-thread-id")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "test-util")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_mock_current_id_nests() {
+        let real = current_id();
+        let (outer, inner) = (thread::mock_id(), thread::mock_id());
+        assert_ne!(outer, inner);
+        assert_ne!(outer.id(), real);
+        {
+            let _outer = thread::mock_current_id(outer);
+            assert_eq!(current_id(), outer.id());
+            thread::with_current_id(inner, || assert_eq!(current_id(), inner.id()));
+            assert_eq!(current_id(), outer.id());
+        }
+        assert_eq!(current_id(), real);
+    }
+
+    #[cfg(feature = "test-util")]
+    //no unwind on wasm!
+    #[test]
+    fn test_mocked_wrong_thread_access_panics() {
+        let cell = crate::SendCell::new(1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            thread::with_current_id(thread::mock_id(), || *cell.get())
+        }));
+        assert!(result.is_err());
+        // The guard was dropped during unwinding, so the real id is back
+        assert_eq!(*cell.get(), 1);
+    }
+
+    #[cfg(feature = "std-thread-id")]
+    #[test]
+    fn test_std_thread_id_conversion() {
+        use std::sync::{Arc, Barrier};
+
+        assert_eq!(
+            ThreadId::from_std(thread::current().id()),
+            Some(current_id())
+        );
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use std::thread;
+        let barrier = Arc::new(Barrier::new(2));
+        let (std_sender, std_receiver) = std::sync::mpsc::channel();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let worker = {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                // Not yet asked for an id
+                std_sender.send(thread::current().id()).unwrap();
+                barrier.wait();
+                sender.send(current_id()).unwrap();
+                barrier.wait();
+            })
+        };
+        let std_id = std_receiver.recv().unwrap();
+        assert_eq!(ThreadId::from_std(std_id), None);
+        barrier.wait();
+        let id = receiver.recv().unwrap();
+        assert_eq!(ThreadId::from_std(std_id), Some(id));
+        barrier.wait();
+        worker.join().unwrap();
+        // The thread has exited, so its entry is gone rather than kept forever
+        assert_eq!(ThreadId::from_std(std_id), None);
+        assert!(!std_ids().contains_key(&std_id));
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+/*!
+A cell holding an independent value for each thread that accesses it.
+
+Some resources are inherently per-thread: a cache built around `Rc`, a random number
+generator, or an FFI context that must only be used on the thread that created it.
+Sharing one such value between threads is impossible, but giving every thread its own
+copy is often exactly what's wanted. The `thread_local!` macro does this for `static`
+items; [`ThreadLocalCell<T>`] does it for ordinary values, so a per-thread resource can
+be stored in a struct and shared through an `Arc`.
+
+Each thread that calls [`ThreadLocalCell::get`] receives its own `T`, created on that
+thread by the cell's initialization closure the first time the thread accesses the cell.
+A thread never observes another thread's value, so `T` need not be `Send` or `Sync` for
+the cell to be shared.
+
+# Lifetime of Per-Thread Values
+
+Values live as long as the cell, even after the thread that created them exits. When the
+cell is dropped, only the value belonging to the dropping thread can be dropped safely;
+values created by other threads are leaked rather than dropped on the wrong thread.
+Cells that are accessed from many short-lived threads therefore grow without bound, and
+are better replaced by a pool or by `thread_local!`.
+
+# Examples
+
+```rust
+use send_cells::ThreadLocalCell;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+
+// Rc<RefCell<Vec<u32>>> is neither Send nor Sync, but each thread gets its own
+let scratch = Arc::new(ThreadLocalCell::new(|| Rc::new(RefCell::new(Vec::new()))));
+
+let worker = Arc::clone(&scratch);
+thread::spawn(move || {
+    worker.get().borrow_mut().push(1);
+    assert_eq!(worker.get().borrow().len(), 1);
+}).join().unwrap();
+
+// This thread's value is independent of the worker's
+assert!(scratch.get().borrow().is_empty());
+```
+*/
+
+use crate::sys::ThreadId;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Mutex, PoisonError};
+
+/// A cell that lazily creates an independent value for each accessing thread.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// # Examples
+///
+/// ```rust
+/// use send_cells::ThreadLocalCell;
+/// use std::cell::Cell;
+///
+/// let counter = ThreadLocalCell::new(|| Cell::new(0));
+/// counter.get().set(counter.get().get() + 1);
+/// assert_eq!(counter.get().get(), 1);
+/// ```
+///
+/// # Thread Safety
+///
+/// The cell implements `Sync` when the initialization closure implements `Sync`, and
+/// `Send` when the closure implements `Send`, for any `T`.
+pub struct ThreadLocalCell<T, F = fn() -> T> {
+    // Values are boxed so that references handed out by `get` stay valid when the map
+    // reallocates. Entries are never removed while the cell is shared.
+    values: Mutex<HashMap<ThreadId, Box<T>>>,
+    init: F,
+}
+
+impl<T, F: Fn() -> T> ThreadLocalCell<T, F> {
+    /// Creates a new `ThreadLocalCell` that creates each thread's value with `init`.
+    ///
+    /// No values are created until a thread first accesses the cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ThreadLocalCell;
+    /// use std::rc::Rc;
+    ///
+    /// let cell = ThreadLocalCell::new(|| Rc::new("per-thread"));
+    /// assert_eq!(**cell.get(), "per-thread");
+    /// ```
+    #[inline]
+    pub fn new(init: F) -> ThreadLocalCell<T, F> {
+        ThreadLocalCell {
+            values: Mutex::new(HashMap::new()),
+            init,
+        }
+    }
+
+    /// Accesses the current thread's value, creating it on first access.
+    ///
+    /// The initialization closure runs on the current thread, without holding the cell's
+    /// internal lock.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ThreadLocalCell;
+    /// use std::cell::RefCell;
+    ///
+    /// let cell = ThreadLocalCell::new(|| RefCell::new(String::new()));
+    /// cell.get().borrow_mut().push_str("hello");
+    /// assert_eq!(*cell.get().borrow(), "hello");
+    /// ```
+    pub fn get(&self) -> &T {
+        let thread_id = crate::sys::current_id();
+        if let Some(value) = self.lock().get(&thread_id) {
+            let value: *const T = &**value;
+            // SAFETY: The box is never dropped or removed while `self` is borrowed, and
+            // only this thread ever accesses the value stored under its id.
+            return unsafe { &*value };
+        }
+        let value = Box::new((self.init)());
+        let value: *const T = &**self.lock().entry(thread_id).or_insert(value);
+        // SAFETY: As above.
+        unsafe { &*value }
+    }
+}
+
+impl<T, F> ThreadLocalCell<T, F> {
+    /// Accesses the current thread's value, if the current thread has created one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::ThreadLocalCell;
+    ///
+    /// let cell = ThreadLocalCell::new(|| 42);
+    /// assert_eq!(cell.get_if_initialized(), None);
+    /// cell.get();
+    /// assert_eq!(cell.get_if_initialized(), Some(&42));
+    /// ```
+    pub fn get_if_initialized(&self) -> Option<&T> {
+        let thread_id = crate::sys::current_id();
+        let value: *const T = &**self.lock().get(&thread_id)?;
+        // SAFETY: As in `get`.
+        Some(unsafe { &*value })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ThreadId, Box<T>>> {
+        // The map is never left in an inconsistent state, so poisoning can be ignored
+        self.values.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// SAFETY: Moving the cell moves the per-thread values without accessing them, and Drop
+// only drops the value belonging to the dropping thread. The closure moves with the cell.
+unsafe impl<T, F: Send> Send for ThreadLocalCell<T, F> {}
+
+// SAFETY: Each thread only ever accesses the value stored under its own id, which it
+// created itself, so no value is shared or moved between threads. The closure is called
+// from any thread through &F.
+unsafe impl<T, F: Sync> Sync for ThreadLocalCell<T, F> {}
+
+impl<T, F> Drop for ThreadLocalCell<T, F> {
+    fn drop(&mut self) {
+        let thread_id = crate::sys::current_id();
+        let values = std::mem::take(
+            self.values
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        for (id, value) in values {
+            if id == thread_id {
+                drop(value);
+            } else {
+                // Dropping another thread's value here would be unsound for !Send types
+                std::mem::forget(value);
+            }
+        }
+    }
+}
+
+impl<T: Debug, F> Debug for ThreadLocalCell<T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.get_if_initialized() {
+            Some(value) => value.fmt(f),
+            None => f.write_str("<uninitialized>"),
+        }
+    }
+}
+
+impl<T: Default> Default for ThreadLocalCell<T> {
+    fn default() -> ThreadLocalCell<T> {
+        ThreadLocalCell::new(T::default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_basic_usage() {
+        let cell = ThreadLocalCell::new(|| Rc::new(std::cell::Cell::new(0)));
+        assert!(cell.get_if_initialized().is_none());
+
+        cell.get().set(1);
+        assert_eq!(cell.get().get(), 1);
+        assert!(Rc::ptr_eq(cell.get(), cell.get_if_initialized().unwrap()));
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_send_sync() {
+        fn assert_send<T: Send>(_: &T) {}
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let cell = ThreadLocalCell::new(|| Rc::new(42));
+        assert_send(&cell);
+        assert_sync(&cell);
+    }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_drops_own_value() {
+        let marker = Rc::new(());
+        let cell = ThreadLocalCell::new({
+            let marker = Rc::clone(&marker);
+            move || Rc::clone(&marker)
+        });
+        cell.get();
+        assert_eq!(Rc::strong_count(&marker), 3);
+        drop(cell);
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
+
+    #[test]
+    fn test_values_are_per_thread() {
+        use crate::sys::thread;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let created = Arc::new(AtomicUsize::new(0));
+        let cell = Arc::new(ThreadLocalCell::new({
+            let created = Arc::clone(&created);
+            move || std::cell::Cell::new(created.fetch_add(1, Ordering::Relaxed))
+        }));
+
+        let main_value = cell.get().get();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                thread::spawn(move || {
+                    let value = cell.get().get();
+                    // Repeated access on one thread returns the same value
+                    assert_eq!(cell.get().get(), value);
+                    value
+                })
+            })
+            .collect();
+
+        let mut values: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        values.push(main_value);
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+        assert_eq!(created.load(Ordering::Relaxed), 5);
+    }
+}
@@ -116,10 +116,10 @@ and memory safety violations. Always prefer [`crate::SendCell`] unless you
 have specific performance requirements and can manually verify safety.
 */
 
-use std::fmt::Debug;
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use core::fmt::Debug;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 /// A cell that can be sent across threads without runtime checks.
 ///
@@ -191,12 +191,20 @@ use std::task::{Context, Poll};
 /// - Prototyping concurrent code
 ///
 /// For safer alternatives with runtime checks, see [`crate::SendCell`].
-pub struct UnsafeSendCell<T>(T);
+#[repr(transparent)]
+pub struct UnsafeSendCell<T: ?Sized>(T);
 
 // SAFETY: UnsafeSendCell implements Send for any T, regardless of whether T implements Send.
 // This is unsafe and requires the user to manually verify that the value won't be accessed
 // concurrently from multiple threads.
-unsafe impl<T> Send for UnsafeSendCell<T> {}
+unsafe impl<T: ?Sized> Send for UnsafeSendCell<T> {}
+
+// Allows e.g. `UnsafeSendCell<Box<T>>` to coerce to `UnsafeSendCell<Box<dyn Trait>>`, like `std::cell::Cell`
+#[cfg(feature = "nightly")]
+impl<T: core::ops::CoerceUnsized<U>, U> core::ops::CoerceUnsized<UnsafeSendCell<U>>
+    for UnsafeSendCell<T>
+{
+}
 
 impl<T> UnsafeSendCell<T> {
     /// Creates a new cell without verifying thread safety.
@@ -259,11 +267,14 @@ impl<T> UnsafeSendCell<T> {
     #[inline]
     pub fn new(value: T) -> Self {
         assert!(
-            !std::mem::needs_drop::<T>(),
+            !core::mem::needs_drop::<T>(),
             "Cannot use safe constructor for types that implement Drop; use new_unchecked instead. "
         );
         UnsafeSendCell(value)
     }
+}
+
+impl<T: ?Sized> UnsafeSendCell<T> {
     /// Gets a reference to the underlying value.
     ///
     /// # Safety
@@ -324,10 +335,13 @@ impl<T> UnsafeSendCell<T> {
     /// ```
     #[inline]
     pub unsafe fn get_mut(&mut self) -> &mut T {
-        //I think this should be safe, because we are the only ones with access to the inner value?
+        // A plain reborrow through the exclusive reference, never a raw pointer, so the
+        // result carries the cell's own provenance and checks cleanly under Miri
         &mut self.0
     }
+}
 
+impl<T> UnsafeSendCell<T> {
     /// Consumes the cell and returns the wrapped value.
     ///
     /// # Safety
@@ -400,6 +414,56 @@ impl<T: Future> UnsafeSendCell<T> {
     }
 }
 
+#[cfg(feature = "futures")]
+impl<T: futures_core::Stream> UnsafeSendCell<T> {
+    /// Converts the cell into a stream that implements Send.
+    ///
+    /// This method consumes the `UnsafeSendCell` and returns an [`UnsafeSendStream`]
+    /// that implements `Send`. The returned stream can be moved between threads
+    /// but requires the same safety guarantees as the original cell.
+    ///
+    /// This method is only available with the `futures` feature.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - The stream won't be polled concurrently from multiple threads
+    /// - If the stream is moved between threads, it's safe to do so
+    /// - The stream's state and any captured variables are thread-safe
+    /// - Drop implementations are safe to run on any thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::UnsafeSendCell;
+    /// use std::pin::Pin;
+    /// use std::rc::Rc;
+    /// use std::task::{Context, Poll};
+    ///
+    /// // A stream that is not Send
+    /// struct LocalStream(Rc<i32>);
+    ///
+    /// impl futures_core::Stream for LocalStream {
+    ///     type Item = i32;
+    ///     fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<i32>> {
+    ///         Poll::Ready(Some(*self.0))
+    ///     }
+    /// }
+    ///
+    /// // SAFETY: We guarantee this stream won't be sent between threads
+    /// let cell = unsafe { UnsafeSendCell::new_unchecked(LocalStream(Rc::new(42))) };
+    /// let send_stream = unsafe { cell.into_stream() };
+    ///
+    /// // Now it can be used in Send contexts
+    /// fn requires_send<T: Send>(_: T) {}
+    /// requires_send(send_stream);
+    /// ```
+    #[inline]
+    pub unsafe fn into_stream(self) -> UnsafeSendStream<T> {
+        UnsafeSendStream(self.0)
+    }
+}
+
 /// A future wrapper that unsafely implements Send.
 ///
 /// `UnsafeSendFuture<T>` wraps a future of type `T` and provides an unsafe `Send`
@@ -463,6 +527,91 @@ pub struct UnsafeSendFuture<T>(T);
 // concurrently from multiple threads.
 unsafe impl<T> Send for UnsafeSendFuture<T> {}
 
+impl<T> UnsafeSendFuture<T> {
+    /// Gets a reference to the wrapped future.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - No other thread is concurrently accessing the future
+    /// - The future is safe to access from the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::UnsafeSendCell;
+    /// use std::future::Ready;
+    ///
+    /// let cell = UnsafeSendCell::new(std::future::ready(42));
+    /// // SAFETY: We're on the same thread
+    /// let send_future = unsafe { cell.into_future() };
+    ///
+    /// // SAFETY: The future never left this thread
+    /// let future: &Ready<i32> = unsafe { send_future.get_ref() };
+    /// # let _ = future;
+    /// ```
+    #[inline]
+    pub unsafe fn get_ref(&self) -> &T {
+        &self.0
+    }
+
+    /// Gets a mutable reference to the wrapped future.
+    ///
+    /// The wrapper is not pinned here, so this does not let a pinned future be moved.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - No other thread is concurrently accessing the future
+    /// - The future is safe to access mutably from the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::UnsafeSendCell;
+    ///
+    /// let cell = UnsafeSendCell::new(std::future::ready(42));
+    /// // SAFETY: We're on the same thread
+    /// let mut send_future = unsafe { cell.into_future() };
+    ///
+    /// // SAFETY: The future never left this thread
+    /// let future = unsafe { send_future.get_mut() };
+    /// *future = std::future::ready(7);
+    /// ```
+    #[inline]
+    pub unsafe fn get_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Consumes the wrapper and returns the wrapped future.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - It's safe to take ownership of the future on the current thread
+    /// - The future can be safely dropped on the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use send_cells::UnsafeSendCell;
+    /// use std::rc::Rc;
+    ///
+    /// let local = Rc::new(42);
+    /// // SAFETY: We're on the same thread
+    /// let cell = unsafe { UnsafeSendCell::new_unchecked(async move { *local }) };
+    /// let send_future = unsafe { cell.into_future() };
+    ///
+    /// // SAFETY: The future never left this thread
+    /// let future = unsafe { send_future.into_inner() };
+    /// # drop(future);
+    /// ```
+    #[inline]
+    pub unsafe fn into_inner(self) -> T {
+        self.0
+    }
+}
+
 impl<T: Future> Future for UnsafeSendFuture<T> {
     type Output = T::Output;
 
@@ -473,6 +622,54 @@ impl<T: Future> Future for UnsafeSendFuture<T> {
     }
 }
 
+/// A stream wrapper that unsafely implements Send.
+///
+/// `UnsafeSendStream<T>` wraps a [`futures_core::Stream`] of type `T` and provides an
+/// unsafe `Send` implementation. This is the zero-cost counterpart to [`UnsafeSendFuture`]
+/// for streams: it allows non-Send streams to be used in contexts that require Send
+/// streams, but requires manual verification of thread safety.
+///
+/// An `UnsafeSendStream` is created with [`UnsafeSendCell::into_stream`]. This type is
+/// only available with the `futures` feature.
+///
+/// # Safety
+///
+/// When using `UnsafeSendStream<T>`, you must ensure:
+/// - The stream is never polled concurrently from multiple threads
+/// - If moved between threads, the stream's state is safe to access
+/// - Any captured variables in the stream are thread-safe
+/// - Drop implementations are safe to run on any thread
+///
+/// # Performance
+///
+/// This wrapper has zero runtime overhead compared to the underlying stream,
+/// making it suitable for performance-critical applications where the polling
+/// thread is under your control.
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub struct UnsafeSendStream<T>(T);
+
+// SAFETY: UnsafeSendStream implements Send for any T, regardless of whether T implements Send.
+// This is unsafe and requires the user to manually verify that the stream won't be accessed
+// concurrently from multiple threads.
+#[cfg(feature = "futures")]
+unsafe impl<T> Send for UnsafeSendStream<T> {}
+
+#[cfg(feature = "futures")]
+impl<T: futures_core::Stream> futures_core::Stream for UnsafeSendStream<T> {
+    type Item = T::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // SAFETY: We're maintaining the pinning invariant by not moving the inner stream
+        let inner = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        inner.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
 /*
 Design note about traits.
 
@@ -501,12 +698,12 @@ impl<T> From<T> for UnsafeSendCell<T> {
     }
 }
 
-impl<T> Debug for UnsafeSendCell<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: ?Sized> Debug for UnsafeSendCell<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Note: We can't safely access the underlying field here because it may have been sent
         // to a different thread where accessing it would be unsafe.
         f.debug_tuple("UnsafeSendCell")
-            .field(&std::any::type_name::<T>())
+            .field(&core::any::type_name::<T>())
             .finish()
     }
 }
@@ -549,7 +746,10 @@ mod tests {
     // Helper function to verify a type implements Send
     fn assert_send<T: Send>(_: &T) {}
 
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_non_send_future_to_send_future() {
         // Create a non-Send future
@@ -570,7 +770,10 @@ mod tests {
         // This demonstrates that we can now use this future in Send contexts
         // For example, we could spawn it on a thread pool (though we won't actually do that here)
     }
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
     #[test]
     fn test_future_functionality_preserved() {
         use std::task::{RawWaker, RawWakerVTable, Waker};
@@ -607,4 +810,85 @@ mod tests {
             Poll::Ready(value) => assert_eq!(value, 42),
         }
     }
+
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_future_accessors() {
+        let cell = unsafe { UnsafeSendCell::new_unchecked(NonSendFuture::new(42)) };
+        let mut send_future = unsafe { cell.into_future() };
+
+        assert!(!unsafe { send_future.get_ref() }.ready);
+        unsafe { send_future.get_mut() }.ready = true;
+
+        // The recovered future keeps the state set through the wrapper
+        let future = unsafe { send_future.into_inner() };
+        assert!(future.ready);
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "emscripten")),
+        wasm_bindgen_test::wasm_bindgen_test
+    )]
+    #[test]
+    fn test_stream_functionality_preserved() {
+        use futures_core::Stream;
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        // A stream that is NOT Send because it contains Rc<T>
+        struct NonSendStream {
+            remaining: Rc<std::cell::Cell<i32>>,
+        }
+
+        impl Stream for NonSendStream {
+            type Item = i32;
+
+            fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<i32>> {
+                let remaining = self.remaining.get();
+                if remaining == 0 {
+                    Poll::Ready(None)
+                } else {
+                    self.remaining.set(remaining - 1);
+                    Poll::Ready(Some(remaining))
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let remaining = self.remaining.get() as usize;
+                (remaining, Some(remaining))
+            }
+        }
+
+        let stream = NonSendStream {
+            remaining: Rc::new(std::cell::Cell::new(2)),
+        };
+        let cell = unsafe { UnsafeSendCell::new_unchecked(stream) };
+        let mut send_stream = unsafe { cell.into_stream() };
+        assert_send(&send_stream);
+        assert_eq!(send_stream.size_hint(), (2, Some(2)));
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+        let raw_waker = RawWaker::new(std::ptr::null(), &VTABLE);
+        let waker = unsafe { Waker::from_raw(raw_waker) };
+        let mut context = Context::from_waker(&waker);
+
+        let mut pinned = Pin::new(&mut send_stream);
+        assert_eq!(
+            pinned.as_mut().poll_next(&mut context),
+            Poll::Ready(Some(2))
+        );
+        assert_eq!(
+            pinned.as_mut().poll_next(&mut context),
+            Poll::Ready(Some(1))
+        );
+        assert_eq!(pinned.as_mut().poll_next(&mut context), Poll::Ready(None));
+    }
 }
@@ -118,8 +118,8 @@ and memory safety violations. Always prefer [`crate::SyncCell`] unless you
 have specific performance requirements and can manually verify safety.
 */
 
-use std::cell::UnsafeCell;
-use std::fmt::Debug;
+use core::cell::UnsafeCell;
+use core::fmt::Debug;
 
 /// A cell that can be shared between threads without synchronization.
 ///
@@ -215,6 +215,13 @@ pub struct UnsafeSyncCell<T>(UnsafeCell<T>);
 // or that external synchronization is provided.
 unsafe impl<T> Sync for UnsafeSyncCell<T> {}
 
+// Allows e.g. `UnsafeSyncCell<Box<T>>` to coerce to `UnsafeSyncCell<Box<dyn Trait>>`, like `std::cell::Cell`
+#[cfg(feature = "nightly")]
+impl<T: core::ops::CoerceUnsized<U>, U> core::ops::CoerceUnsized<UnsafeSyncCell<U>>
+    for UnsafeSyncCell<T>
+{
+}
+
 impl<T> UnsafeSyncCell<T> {
     /// Creates a new `UnsafeSyncCell` wrapping the given value.
     ///
@@ -359,11 +366,11 @@ DerefMut can't be implemented due to lack of deref type.
  */
 
 impl<T> Debug for UnsafeSyncCell<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         //we can't use the value here since we can't guarantee it's safe to do so.
         //but we can use the type name
         f.debug_tuple("SyncCell")
-            .field(&std::any::type_name::<T>())
+            .field(&core::any::type_name::<T>())
             .finish()
     }
 }